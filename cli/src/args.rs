@@ -0,0 +1,239 @@
+//! Command-line arguments
+
+use clap::{ArgEnum, Parser, Subcommand};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_token::state::PoolMode;
+use std::str::FromStr;
+
+/// Operates pools of the token wrapper program
+#[derive(Debug, Parser)]
+#[clap(name = "solana-token-cli", version)]
+pub struct Args {
+    /// JSON RPC URL of the cluster
+    #[clap(
+        long,
+        short = 'u',
+        global = true,
+        default_value = "http://localhost:8899"
+    )]
+    pub url: String,
+
+    /// Keypair file of the fee payer
+    #[clap(
+        long,
+        short = 'k',
+        global = true,
+        default_value = "~/.config/solana/id.json"
+    )]
+    pub keypair: String,
+
+    /// Keypair file or public key of the wallet owner, defaults to the fee payer.
+    /// A public key can only be used with `--sign-only`
+    #[clap(long, global = true)]
+    pub owner: Option<String>,
+
+    /// Address of the wrapper program
+    #[clap(long, global = true)]
+    pub program_id: Option<Pubkey>,
+
+    /// Output format
+    #[clap(long, global = true, arg_enum, default_value = "display")]
+    pub output: OutputFormat,
+
+    /// Sign the transaction without sending it, requires `--blockhash` since the
+    /// cluster isn't queried
+    #[clap(long, global = true, requires = "blockhash")]
+    pub sign_only: bool,
+
+    /// Blockhash to sign the transaction with instead of querying the cluster.
+    /// With `--nonce` this is the durable nonce stored in the nonce account
+    #[clap(long, global = true)]
+    pub blockhash: Option<Hash>,
+
+    /// Nonce account to use instead of a recent blockhash, the transaction then
+    /// starts by advancing the nonce
+    #[clap(long, global = true)]
+    pub nonce: Option<Pubkey>,
+
+    /// Keypair file or public key of the nonce authority, defaults to the fee payer
+    #[clap(long, global = true, requires = "nonce")]
+    pub nonce_authority: Option<String>,
+
+    /// Signature collected offline, as PUBKEY=SIGNATURE
+    #[clap(long = "signer", global = true, multiple_occurrences = true)]
+    pub signers: Vec<PresignedSignature>,
+
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+/// Signature of a key made on another machine
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PresignedSignature {
+    pub pubkey: Pubkey,
+    pub signature: Signature,
+}
+
+impl FromStr for PresignedSignature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (pubkey, signature) = s
+            .split_once('=')
+            .ok_or_else(|| "expected PUBKEY=SIGNATURE".to_string())?;
+        Ok(Self {
+            pubkey: pubkey
+                .parse()
+                .map_err(|_| format!("invalid pubkey {}", pubkey))?,
+            signature: signature
+                .parse()
+                .map_err(|_| format!("invalid signature {}", signature))?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ArgEnum)]
+pub enum OutputFormat {
+    Display,
+    Json,
+}
+
+/// How the pool tokens of a new pool are valued, see `PoolMode`
+#[derive(Debug, Clone, Copy, PartialEq, ArgEnum)]
+pub enum PoolModeArg {
+    Fixed,
+    Shares,
+}
+
+impl From<PoolModeArg> for PoolMode {
+    fn from(mode: PoolModeArg) -> Self {
+        match mode {
+            PoolModeArg::Fixed => PoolMode::Fixed,
+            PoolModeArg::Shares => PoolMode::Shares,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Subcommand)]
+pub enum Command {
+    /// Create a pool with its pool mint, metadata and vault. The owner is the admin
+    /// of the pool and pays for its accounts
+    InitPool {
+        /// Mint of token X
+        #[clap(long)]
+        token_x_mint: Pubkey,
+        /// Keypair file of the new pool mint, a random keypair is used if omitted
+        #[clap(long)]
+        pool_mint_keypair: Option<String>,
+        /// Name of the pool token in its metadata
+        #[clap(long)]
+        name: String,
+        /// Symbol of the pool token in its metadata
+        #[clap(long)]
+        symbol: String,
+        /// URI of the metadata JSON of the pool token
+        #[clap(long, default_value = "")]
+        uri: String,
+        /// How the pool tokens are valued
+        #[clap(long, arg_enum, default_value = "fixed")]
+        mode: PoolModeArg,
+        /// Seconds from requesting an unwrap until it can be claimed, 0 to unwrap
+        /// directly
+        #[clap(long, default_value = "0")]
+        unwrap_delay: u32,
+        /// Unix timestamp after which token X isn't wrapped anymore, 0 for none
+        #[clap(long, default_value = "0")]
+        subscription_deadline: i64,
+        /// Unix timestamp from which pool tokens are unwrapped, 0 for none
+        #[clap(long, default_value = "0")]
+        maturity: i64,
+    },
+    /// Change token X into pool tokens
+    Wrap {
+        /// Token X wallet of the owner
+        #[clap(long)]
+        wallet_x: Pubkey,
+        /// Mint of token X
+        #[clap(long)]
+        token_x_mint: Pubkey,
+        /// Pool mint
+        #[clap(long)]
+        pool_mint: Pubkey,
+        /// Pool token wallet of the owner, the associated token account is used
+        /// (and created if missing) when omitted
+        #[clap(long)]
+        wallet_y: Option<Pubkey>,
+        /// The pool mint was created off-chain rather than by `init-pool`, so token X
+        /// goes to the pool wallet of the token X wallet
+        #[clap(long)]
+        off_chain_pool: bool,
+        /// Amount of token X
+        amount: u64,
+    },
+    /// Change pool tokens back into token X
+    Unwrap {
+        /// Token X wallet of the owner
+        #[clap(long)]
+        wallet_x: Pubkey,
+        /// Pool mint
+        #[clap(long)]
+        pool_mint: Pubkey,
+        /// Pool token wallet of the owner, defaults to the associated token account
+        #[clap(long)]
+        wallet_y: Option<Pubkey>,
+        /// The pool mint was created off-chain rather than by `init-pool`, so token X
+        /// comes from the pool wallet of the token X wallet
+        #[clap(long)]
+        off_chain_pool: bool,
+        /// Amount of pool tokens
+        amount: u64,
+    },
+    /// Show the state of a pool mint
+    ShowPool {
+        /// Pool mint
+        pool_mint: Pubkey,
+    },
+    /// Show the account holding the token X of a pool: the vault of a pool created
+    /// by `init-pool`, or else the pool wallet of a user wallet
+    ShowVault {
+        /// Pool mint
+        pool_mint: Pubkey,
+        /// Token X wallet of the user, required for pools created off-chain
+        #[clap(long)]
+        wallet_x: Option<Pubkey>,
+    },
+    /// Add the signatures of the local signers to a transaction signed offline
+    Sign {
+        /// Encoded transaction, or a file with it or with the `--sign-only` output
+        transaction: String,
+    },
+    /// Combine the signatures of copies of the same transaction
+    Merge {
+        /// Encoded transactions, or files with them or with the `--sign-only` output
+        #[clap(required = true, min_values = 2)]
+        transactions: Vec<String>,
+    },
+    /// Send a fully signed transaction
+    Send {
+        /// Encoded transaction, or a file with it or with the `--sign-only` output
+        transaction: String,
+    },
+}
+
+impl Command {
+    /// Whether the command signs transactions, and so needs the local keypairs
+    pub fn signs(&self) -> bool {
+        match self {
+            Command::InitPool { .. }
+            | Command::Wrap { .. }
+            | Command::Unwrap { .. }
+            | Command::Sign { .. } => true,
+            Command::ShowPool { .. }
+            | Command::ShowVault { .. }
+            | Command::Merge { .. }
+            | Command::Send { .. } => false,
+        }
+    }
+}
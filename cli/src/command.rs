@@ -0,0 +1,264 @@
+//! Builds and sends the instructions of each command
+
+use crate::args::Command;
+use crate::config::{read_keypair, Config};
+use crate::error::CliError;
+use crate::offline;
+use crate::output::{CommandOutput, PoolStateOutput, TransactionOutput};
+use solana_program::system_instruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::nonce::state::{State, Versions};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+use solana_token::instruction;
+use solana_token_client::{ClientError, ClientTransport, WrapperClient};
+use spl_associated_token_account::get_associated_token_address;
+
+pub async fn process_command<T: ClientTransport>(
+    client: &mut WrapperClient<T>,
+    config: &Config,
+    command: Command,
+) -> Result<CommandOutput, CliError> {
+    match command {
+        Command::InitPool {
+            token_x_mint,
+            pool_mint_keypair,
+            name,
+            symbol,
+            uri,
+            mode,
+            unwrap_delay,
+            subscription_deadline,
+            maturity,
+        } => {
+            let pool_mint = match pool_mint_keypair {
+                Some(path) => read_keypair(&path)?,
+                None => Keypair::new(),
+            };
+            let ix = instruction::initialize_pool(
+                client.program_id(),
+                &config.owner.pubkey(),
+                &pool_mint.pubkey(),
+                &token_x_mint,
+                &name,
+                &symbol,
+                &uri,
+                mode.into(),
+                unwrap_delay,
+                subscription_deadline,
+                maturity,
+            );
+            let transaction = send_instructions(client, config, &[ix], &[&pool_mint]).await?;
+            Ok(CommandOutput::InitPool {
+                pool_mint: pool_mint.pubkey().to_string(),
+                pool_mint_authority: client.pool(&pool_mint.pubkey()).to_string(),
+                transaction,
+            })
+        }
+        Command::Wrap {
+            wallet_x,
+            token_x_mint,
+            pool_mint,
+            wallet_y,
+            off_chain_pool,
+            amount,
+        } => {
+            let owner = config.owner.pubkey();
+            let ix = match (wallet_y, off_chain_pool) {
+                (Some(wallet_y), false) => instruction::change_x_to_y_pool(
+                    client.program_id(),
+                    &owner,
+                    &wallet_x,
+                    &token_x_mint,
+                    &wallet_y,
+                    &pool_mint,
+                    amount,
+                ),
+                (Some(wallet_y), true) => instruction::change_x_to_y(
+                    client.program_id(),
+                    &owner,
+                    &wallet_x,
+                    &token_x_mint,
+                    &wallet_y,
+                    &pool_mint,
+                    amount,
+                ),
+                (None, false) => instruction::change_x_to_y_associated_pool(
+                    client.program_id(),
+                    &owner,
+                    &wallet_x,
+                    &token_x_mint,
+                    &pool_mint,
+                    amount,
+                ),
+                (None, true) => instruction::change_x_to_y_associated(
+                    client.program_id(),
+                    &owner,
+                    &wallet_x,
+                    &token_x_mint,
+                    &pool_mint,
+                    amount,
+                ),
+            };
+            let transaction = send_instructions(client, config, &[ix], &[]).await?;
+            Ok(CommandOutput::Transaction(transaction))
+        }
+        Command::Unwrap {
+            wallet_x,
+            pool_mint,
+            wallet_y,
+            off_chain_pool,
+            amount,
+        } => {
+            let owner = config.owner.pubkey();
+            let wallet_y =
+                wallet_y.unwrap_or_else(|| get_associated_token_address(&owner, &pool_mint));
+            let change_y_to_x = if off_chain_pool {
+                instruction::change_y_to_x
+            } else {
+                instruction::change_y_to_x_pool
+            };
+            let ix = change_y_to_x(
+                client.program_id(),
+                &owner,
+                &wallet_x,
+                &wallet_y,
+                &pool_mint,
+                amount,
+            );
+            let transaction = send_instructions(client, config, &[ix], &[]).await?;
+            Ok(CommandOutput::Transaction(transaction))
+        }
+        Command::ShowPool { pool_mint } => {
+            let pool = client.pool_info(&pool_mint).await?;
+            Ok(CommandOutput::Pool {
+                pool_mint: pool.pool_mint.to_string(),
+                pool_mint_authority: pool.pool_mint_authority.to_string(),
+                supply: pool.supply,
+                decimals: pool.decimals,
+                state: pool.state.map(|state| PoolStateOutput {
+                    vault: state.vault.to_string(),
+                    admin: state.admin.to_string(),
+                    token_x_mint: state.token_x_mint.to_string(),
+                    mode: state.mode,
+                    unwrap_delay: state.unwrap_delay,
+                    pending_unwrap: state.pending_unwrap,
+                    subscription_deadline: state.subscription_deadline,
+                    maturity: state.maturity,
+                }),
+            })
+        }
+        Command::ShowVault {
+            pool_mint,
+            wallet_x,
+        } => {
+            let vault = client.vault(&pool_mint, wallet_x.as_ref()).await?;
+            let balance = client.vault_balance(&pool_mint, wallet_x.as_ref()).await?;
+            Ok(CommandOutput::Vault {
+                pool_mint: pool_mint.to_string(),
+                wallet_x: wallet_x.map(|wallet_x| wallet_x.to_string()),
+                vault: vault.to_string(),
+                balance,
+            })
+        }
+        Command::Sign { transaction } => {
+            let mut tx = offline::read_transaction(&transaction)?;
+            offline::check_signatures(&tx)?;
+            offline::sign_transaction(&mut tx, &config.signers())?;
+            Ok(CommandOutput::Transaction(offline::sign_only_output(&tx)?))
+        }
+        Command::Merge { transactions } => {
+            let transactions = transactions
+                .iter()
+                .map(|transaction| offline::read_transaction(transaction))
+                .collect::<Result<Vec<_>, _>>()?;
+            let tx = offline::merge_transactions(transactions)?;
+            Ok(CommandOutput::Transaction(offline::sign_only_output(&tx)?))
+        }
+        Command::Send { transaction } => {
+            let tx = offline::read_transaction(&transaction)?;
+            offline::check_signatures(&tx)?;
+            let transaction = send_transaction(client, tx).await?;
+            Ok(CommandOutput::Transaction(transaction))
+        }
+    }
+}
+
+/// Signs the instructions with the configured signers, then sends the
+/// transaction or returns it for offline signing
+async fn send_instructions<T: ClientTransport>(
+    client: &mut WrapperClient<T>,
+    config: &Config,
+    instructions: &[Instruction],
+    extra_signers: &[&dyn Signer],
+) -> Result<TransactionOutput, CliError> {
+    let mut instructions = instructions.to_vec();
+    let blockhash = match config.nonce {
+        Some(nonce) => {
+            instructions.insert(
+                0,
+                system_instruction::advance_nonce_account(&nonce, &config.nonce_authority.pubkey()),
+            );
+            match config.blockhash {
+                Some(blockhash) => blockhash,
+                None => get_nonce_blockhash(client, &nonce).await?,
+            }
+        }
+        None => match config.blockhash {
+            Some(blockhash) => blockhash,
+            None => client.transport().get_latest_blockhash().await?,
+        },
+    };
+
+    let mut tx = Transaction::new_with_payer(&instructions, Some(&config.fee_payer.pubkey()));
+    tx.message.recent_blockhash = blockhash;
+    let mut signers = config.signers();
+    signers.extend_from_slice(extra_signers);
+    offline::sign_transaction(&mut tx, &signers)?;
+
+    if config.sign_only {
+        return offline::sign_only_output(&tx);
+    }
+    send_transaction(client, tx).await
+}
+
+/// Sends a transaction once every key has signed it
+async fn send_transaction<T: ClientTransport>(
+    client: &mut WrapperClient<T>,
+    tx: Transaction,
+) -> Result<TransactionOutput, CliError> {
+    if let Some(pubkey) = offline::first_absent_signer(&tx) {
+        return Err(CliError::MissingSignature(pubkey));
+    }
+    let signature = tx.signatures[0];
+    client.transport().process_transaction(tx).await?;
+    Ok(TransactionOutput::Sent {
+        signature: signature.to_string(),
+    })
+}
+
+/// Durable nonce stored in the nonce account
+async fn get_nonce_blockhash<T: ClientTransport>(
+    client: &mut WrapperClient<T>,
+    nonce: &Pubkey,
+) -> Result<Hash, CliError> {
+    let account = client
+        .transport()
+        .get_account(*nonce)
+        .await?
+        .ok_or(ClientError::AccountNotFound(*nonce))?;
+    if account.owner != system_program::id() {
+        return Err(CliError::InvalidNonceAccount(*nonce));
+    }
+    match bincode::deserialize(&account.data) {
+        Ok(Versions::Current(state)) => match *state {
+            State::Initialized(data) => Ok(data.blockhash),
+            State::Uninitialized => Err(CliError::InvalidNonceAccount(*nonce)),
+        },
+        Err(_) => Err(CliError::InvalidNonceAccount(*nonce)),
+    }
+}
@@ -0,0 +1,88 @@
+//! Signers and options shared by all commands
+
+use crate::args::{Args, OutputFormat};
+use crate::error::CliError;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use solana_sdk::signer::null_signer::NullSigner;
+use solana_sdk::signer::presigner::Presigner;
+use solana_sdk::signer::Signer;
+use std::str::FromStr;
+
+pub struct Config {
+    pub fee_payer: Box<dyn Signer>,
+    pub owner: Box<dyn Signer>,
+    pub program_id: Pubkey,
+    pub output: OutputFormat,
+    pub sign_only: bool,
+    pub blockhash: Option<Hash>,
+    pub nonce: Option<Pubkey>,
+    pub nonce_authority: Box<dyn Signer>,
+    pub presigners: Vec<Presigner>,
+}
+
+impl Config {
+    /// Keypairs are only read for commands that sign, the others get signers
+    /// without a key that are never used
+    pub fn from_args(args: &Args) -> Result<Self, CliError> {
+        let signer = |arg: Option<&String>| -> Result<Box<dyn Signer>, CliError> {
+            if !args.command.signs() {
+                return Ok(Box::new(NullSigner::new(&Pubkey::default())));
+            }
+            signer_from_arg(arg.unwrap_or(&args.keypair))
+        };
+        let fee_payer = signer(None)?;
+        let owner = signer(args.owner.as_ref())?;
+        let nonce_authority = signer(args.nonce_authority.as_ref())?;
+
+        Ok(Self {
+            fee_payer,
+            owner,
+            program_id: args.program_id.unwrap_or_else(solana_token::id),
+            output: args.output,
+            sign_only: args.sign_only,
+            blockhash: args.blockhash,
+            nonce: args.nonce,
+            nonce_authority,
+            presigners: args
+                .signers
+                .iter()
+                .map(|presigned| Presigner::new(&presigned.pubkey, &presigned.signature))
+                .collect(),
+        })
+    }
+
+    /// All signers, presigned signatures are checked against the message when signing
+    pub fn signers(&self) -> Vec<&dyn Signer> {
+        let mut signers = vec![
+            self.fee_payer.as_ref(),
+            self.owner.as_ref(),
+            self.nonce_authority.as_ref(),
+        ];
+        signers.extend(
+            self.presigners
+                .iter()
+                .map(|presigner| presigner as &dyn Signer),
+        );
+        signers
+    }
+}
+
+/// Reads a keypair file, a public key gives a signer that can only sign offline
+pub fn signer_from_arg(arg: &str) -> Result<Box<dyn Signer>, CliError> {
+    if let Ok(pubkey) = Pubkey::from_str(arg) {
+        return Ok(Box::new(NullSigner::new(&pubkey)));
+    }
+    Ok(Box::new(read_keypair(arg)?))
+}
+
+/// Reads a keypair file, `~` is expanded to the home directory
+pub fn read_keypair(path: &str) -> Result<Keypair, CliError> {
+    let expanded = match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+        _ => path.to_string(),
+    };
+    read_keypair_file(&expanded)
+        .map_err(|e| CliError::InvalidKeypair(path.to_string(), e.to_string()))
+}
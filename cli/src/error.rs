@@ -0,0 +1,34 @@
+//! Error types
+
+use solana_sdk::pubkey::Pubkey;
+use solana_token_client::ClientError;
+use thiserror::Error;
+
+/// Errors that may be returned by the command-line tool
+#[derive(Debug, Error)]
+pub enum CliError {
+    /// The client call failed
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    /// The keypair file couldn't be read
+    #[error("Can't read keypair {0}: {1}")]
+    InvalidKeypair(String, String),
+    /// The transaction needs a signature that isn't available
+    #[error("Missing signature of {0}, use --sign-only to sign offline")]
+    MissingSignature(Pubkey),
+    /// The transaction couldn't be signed
+    #[error("Signing failed: {0}")]
+    Signing(String),
+    /// The serialized transaction can't be decoded
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(String),
+    /// The transactions to merge don't have the same message
+    #[error("Transactions to merge have different messages")]
+    MessageMismatch,
+    /// The signature doesn't match the message
+    #[error("Invalid signature of {0}")]
+    InvalidSignature(Pubkey),
+    /// The account isn't an initialized nonce account
+    #[error("Account {0} is not an initialized nonce account")]
+    InvalidNonceAccount(Pubkey),
+}
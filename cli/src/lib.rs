@@ -0,0 +1,19 @@
+//! Command-line tool for the token wrapper program
+//!
+//! There are no `set-fees` or `pause` commands: the program keeps no fees nor a
+//! paused state that an operator could set
+
+pub mod args;
+pub mod command;
+pub mod config;
+pub mod error;
+pub mod offline;
+pub mod output;
+pub mod rpc;
+
+pub use args::{Args, Command, OutputFormat, PoolModeArg};
+pub use command::process_command;
+pub use config::Config;
+pub use error::CliError;
+pub use output::CommandOutput;
+pub use rpc::RpcTransport;
@@ -0,0 +1,25 @@
+use clap::Parser;
+use solana_token_cli::{process_command, Args, Config, OutputFormat, RpcTransport};
+use solana_token_client::WrapperClient;
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    if let Err(e) = run(args).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::from_args(&args)?;
+    let transport = RpcTransport::new(args.url.clone());
+    let mut client = WrapperClient::with_program_id(transport, config.program_id);
+
+    let output = process_command(&mut client, &config, args.command).await?;
+    match config.output {
+        OutputFormat::Display => print!("{}", output),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+    }
+    Ok(())
+}
@@ -0,0 +1,141 @@
+//! Serialized transactions passed between offline signers
+
+use crate::error::CliError;
+use crate::output::TransactionOutput;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use std::path::Path;
+
+/// Base58 encoding of the bincode serialized transaction
+pub fn encode_transaction(tx: &Transaction) -> Result<String, CliError> {
+    let data = bincode::serialize(tx).map_err(|e| CliError::InvalidTransaction(e.to_string()))?;
+    Ok(bs58::encode(data).into_string())
+}
+
+pub fn decode_transaction(encoded: &str) -> Result<Transaction, CliError> {
+    let data = bs58::decode(encoded.trim())
+        .into_vec()
+        .map_err(|e| CliError::InvalidTransaction(e.to_string()))?;
+    let tx: Transaction =
+        bincode::deserialize(&data).map_err(|e| CliError::InvalidTransaction(e.to_string()))?;
+    if tx.signatures.len() != tx.message.header.num_required_signatures as usize {
+        return Err(CliError::InvalidTransaction(
+            "signature count doesn't match the message".to_string(),
+        ));
+    }
+    Ok(tx)
+}
+
+/// Reads a transaction given inline or as a file, the file holds either the
+/// encoded transaction or the JSON output of `--sign-only`
+pub fn read_transaction(arg: &str) -> Result<Transaction, CliError> {
+    if !Path::new(arg).is_file() {
+        return decode_transaction(arg);
+    }
+    let contents = std::fs::read_to_string(arg)
+        .map_err(|e| CliError::InvalidTransaction(format!("{}: {}", arg, e)))?;
+    match serde_json::from_str::<serde_json::Value>(&contents) {
+        Ok(json) => json["transaction"]
+            .as_str()
+            .ok_or_else(|| CliError::InvalidTransaction(format!("{}: no transaction", arg)))
+            .and_then(decode_transaction),
+        Err(_) => decode_transaction(&contents),
+    }
+}
+
+/// Adds the signatures of `signers` that are required by the message. Signers
+/// without a key, like `NullSigner`, leave the signature they would replace
+pub fn sign_transaction(tx: &mut Transaction, signers: &[&dyn Signer]) -> Result<(), CliError> {
+    let message = tx.message_data();
+    let signer_count = tx.signatures.len();
+    for signer in signers {
+        let position = match tx.message.account_keys[..signer_count]
+            .iter()
+            .position(|key| *key == signer.pubkey())
+        {
+            Some(position) => position,
+            None => continue,
+        };
+        let signature = signer
+            .try_sign_message(&message)
+            .map_err(|e| CliError::Signing(e.to_string()))?;
+        if signature != Signature::default() {
+            tx.signatures[position] = signature;
+        }
+    }
+    Ok(())
+}
+
+/// Combines the signatures of copies of the same transaction
+pub fn merge_transactions(transactions: Vec<Transaction>) -> Result<Transaction, CliError> {
+    let mut transactions = transactions.into_iter();
+    let mut merged = transactions
+        .next()
+        .ok_or_else(|| CliError::InvalidTransaction("nothing to merge".to_string()))?;
+    check_signatures(&merged)?;
+
+    for tx in transactions {
+        if tx.message != merged.message {
+            return Err(CliError::MessageMismatch);
+        }
+        check_signatures(&tx)?;
+        for (merged_signature, signature) in merged.signatures.iter_mut().zip(tx.signatures) {
+            if signature != Signature::default() {
+                *merged_signature = signature;
+            }
+        }
+    }
+    Ok(merged)
+}
+
+/// Fails on a signature that is present but doesn't sign the message
+pub fn check_signatures(tx: &Transaction) -> Result<(), CliError> {
+    let results = tx.verify_with_results();
+    for ((pubkey, signature), valid) in tx
+        .message
+        .account_keys
+        .iter()
+        .zip(tx.signatures.iter())
+        .zip(results)
+    {
+        if *signature != Signature::default() && !valid {
+            return Err(CliError::InvalidSignature(*pubkey));
+        }
+    }
+    Ok(())
+}
+
+/// Signatures present in the transaction and the keys still to sign
+pub fn sign_only_output(tx: &Transaction) -> Result<TransactionOutput, CliError> {
+    let (present, absent): (Vec<_>, Vec<_>) = tx
+        .message
+        .account_keys
+        .iter()
+        .zip(tx.signatures.iter())
+        .partition(|(_, signature)| **signature != Signature::default());
+
+    Ok(TransactionOutput::SignOnly {
+        blockhash: tx.message.recent_blockhash.to_string(),
+        signers: present
+            .iter()
+            .map(|(pubkey, signature)| format!("{}={}", pubkey, signature))
+            .collect(),
+        absent_signers: absent
+            .iter()
+            .map(|(pubkey, _)| pubkey.to_string())
+            .collect(),
+        transaction: encode_transaction(tx)?,
+    })
+}
+
+/// First key that hasn't signed the transaction yet
+pub fn first_absent_signer(tx: &Transaction) -> Option<Pubkey> {
+    tx.message
+        .account_keys
+        .iter()
+        .zip(tx.signatures.iter())
+        .find(|(_, signature)| **signature == Signature::default())
+        .map(|(pubkey, _)| *pubkey)
+}
@@ -0,0 +1,144 @@
+//! Results of the commands, printed as JSON or for humans
+
+use serde::Serialize;
+use solana_token::state::PoolMode;
+use std::fmt;
+
+/// Result of building a transaction
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TransactionOutput {
+    /// The transaction was processed by the cluster
+    Sent { signature: String },
+    /// The transaction was signed offline, `transaction` is the base58 encoded
+    /// transaction to be completed by `absent_signers`
+    SignOnly {
+        blockhash: String,
+        signers: Vec<String>,
+        absent_signers: Vec<String>,
+        transaction: String,
+    },
+}
+
+/// State of a pool created by `init-pool`
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PoolStateOutput {
+    pub vault: String,
+    pub admin: String,
+    pub token_x_mint: String,
+    pub mode: PoolMode,
+    pub unwrap_delay: u32,
+    pub pending_unwrap: u64,
+    pub subscription_deadline: i64,
+    pub maturity: i64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum CommandOutput {
+    InitPool {
+        pool_mint: String,
+        pool_mint_authority: String,
+        #[serde(flatten)]
+        transaction: TransactionOutput,
+    },
+    Transaction(TransactionOutput),
+    Pool {
+        pool_mint: String,
+        pool_mint_authority: String,
+        supply: u64,
+        decimals: u8,
+        /// `None` for pools created off-chain
+        #[serde(flatten)]
+        state: Option<PoolStateOutput>,
+    },
+    Vault {
+        pool_mint: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        wallet_x: Option<String>,
+        vault: String,
+        balance: u64,
+    },
+}
+
+impl fmt::Display for TransactionOutput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransactionOutput::Sent { signature } => writeln!(f, "Signature: {}", signature),
+            TransactionOutput::SignOnly {
+                blockhash,
+                signers,
+                absent_signers,
+                transaction,
+            } => {
+                writeln!(f, "Blockhash: {}", blockhash)?;
+                if !signers.is_empty() {
+                    writeln!(f, "Signers (Pubkey=Signature):")?;
+                    for signer in signers {
+                        writeln!(f, "  {}", signer)?;
+                    }
+                }
+                if !absent_signers.is_empty() {
+                    writeln!(f, "Absent Signers (Pubkey):")?;
+                    for signer in absent_signers {
+                        writeln!(f, "  {}", signer)?;
+                    }
+                }
+                writeln!(f, "Transaction: {}", transaction)
+            }
+        }
+    }
+}
+
+impl fmt::Display for CommandOutput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandOutput::InitPool {
+                pool_mint,
+                pool_mint_authority,
+                transaction,
+            } => {
+                writeln!(f, "Pool mint: {}", pool_mint)?;
+                writeln!(f, "Pool mint authority: {}", pool_mint_authority)?;
+                write!(f, "{}", transaction)
+            }
+            CommandOutput::Transaction(transaction) => write!(f, "{}", transaction),
+            CommandOutput::Pool {
+                pool_mint,
+                pool_mint_authority,
+                supply,
+                decimals,
+                state,
+            } => {
+                writeln!(f, "Pool mint: {}", pool_mint)?;
+                writeln!(f, "Pool mint authority: {}", pool_mint_authority)?;
+                writeln!(f, "Supply: {}", supply)?;
+                writeln!(f, "Decimals: {}", decimals)?;
+                if let Some(state) = state {
+                    writeln!(f, "Vault: {}", state.vault)?;
+                    writeln!(f, "Admin: {}", state.admin)?;
+                    writeln!(f, "Token X mint: {}", state.token_x_mint)?;
+                    writeln!(f, "Mode: {:?}", state.mode)?;
+                    writeln!(f, "Unwrap delay: {}s", state.unwrap_delay)?;
+                    writeln!(f, "Pending unwrap: {}", state.pending_unwrap)?;
+                    writeln!(f, "Subscription deadline: {}", state.subscription_deadline)?;
+                    writeln!(f, "Maturity: {}", state.maturity)?;
+                }
+                Ok(())
+            }
+            CommandOutput::Vault {
+                pool_mint,
+                wallet_x,
+                vault,
+                balance,
+            } => {
+                writeln!(f, "Pool mint: {}", pool_mint)?;
+                if let Some(wallet_x) = wallet_x {
+                    writeln!(f, "Wallet X: {}", wallet_x)?;
+                }
+                writeln!(f, "Vault: {}", vault)?;
+                writeln!(f, "Balance: {}", balance)
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! JSON RPC access to a cluster
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use solana_sdk::transport::TransportError;
+use solana_token_client::{ClientError, ClientTransport};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How long `process_transaction` waits for the confirmation
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// `ClientTransport` over the JSON RPC API of a validator, at `confirmed` commitment
+pub struct RpcTransport {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl RpcTransport {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+
+    async fn send(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let mut response: Value = self
+            .client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(rpc_error)?
+            .json()
+            .await
+            .map_err(rpc_error)?;
+
+        if let Some(error) = response.get("error") {
+            return Err(rpc_error(format!("{} failed: {}", method, error)));
+        }
+        Ok(response["result"].take())
+    }
+}
+
+fn rpc_error<E: ToString>(e: E) -> ClientError {
+    ClientError::Transport(TransportError::Custom(e.to_string()))
+}
+
+fn invalid_response(method: &str) -> ClientError {
+    rpc_error(format!("Unexpected {} response", method))
+}
+
+#[async_trait]
+impl ClientTransport for RpcTransport {
+    async fn get_account(&mut self, address: Pubkey) -> Result<Option<Account>, ClientError> {
+        let result = self
+            .send(
+                "getAccountInfo",
+                json!([address.to_string(), {"encoding": "base64", "commitment": "confirmed"}]),
+            )
+            .await?;
+        let value = &result["value"];
+        if value.is_null() {
+            return Ok(None);
+        }
+
+        let parse = || -> Option<Account> {
+            Some(Account {
+                lamports: value["lamports"].as_u64()?,
+                data: base64::decode(value["data"][0].as_str()?).ok()?,
+                owner: Pubkey::from_str(value["owner"].as_str()?).ok()?,
+                executable: value["executable"].as_bool()?,
+                rent_epoch: value["rentEpoch"].as_u64()?,
+            })
+        };
+        parse()
+            .map(Some)
+            .ok_or_else(|| invalid_response("getAccountInfo"))
+    }
+
+    async fn get_latest_blockhash(&mut self) -> Result<Hash, ClientError> {
+        let result = self
+            .send("getLatestBlockhash", json!([{"commitment": "confirmed"}]))
+            .await?;
+        result["value"]["blockhash"]
+            .as_str()
+            .and_then(|blockhash| Hash::from_str(blockhash).ok())
+            .ok_or_else(|| invalid_response("getLatestBlockhash"))
+    }
+
+    async fn process_transaction(&mut self, transaction: Transaction) -> Result<(), ClientError> {
+        let signature = transaction.signatures[0];
+        let encoded = base64::encode(bincode::serialize(&transaction).map_err(rpc_error)?);
+        let result = self
+            .send(
+                "sendTransaction",
+                json!([encoded, {"encoding": "base64", "preflightCommitment": "confirmed"}]),
+            )
+            .await?;
+        if result.as_str().and_then(|s| Signature::from_str(s).ok()) != Some(signature) {
+            return Err(invalid_response("sendTransaction"));
+        }
+
+        let started = std::time::Instant::now();
+        while started.elapsed() < CONFIRMATION_TIMEOUT {
+            let result = self
+                .send("getSignatureStatuses", json!([[signature.to_string()]]))
+                .await?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    let error: TransactionError = serde_json::from_value(status["err"].clone())
+                        .map_err(|_| invalid_response("getSignatureStatuses"))?;
+                    return Err(TransportError::TransactionError(error).into());
+                }
+                if matches!(
+                    status["confirmationStatus"].as_str(),
+                    Some("confirmed") | Some("finalized")
+                ) {
+                    return Ok(());
+                }
+            }
+            tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+        }
+        Err(rpc_error(format!(
+            "Transaction {} was not confirmed in {} seconds",
+            signature,
+            CONFIRMATION_TIMEOUT.as_secs()
+        )))
+    }
+}
@@ -0,0 +1,652 @@
+#[path = "../../tests/utils/metadata.rs"]
+mod metadata;
+
+use clap::Parser;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+use solana_program::system_program;
+use solana_program_test::*;
+use solana_sdk::hash::Hash;
+use solana_sdk::nonce::state::{State, Versions};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::signer::null_signer::NullSigner;
+use solana_sdk::transaction::Transaction;
+use solana_token::processor::Processor;
+use solana_token::state::PoolMode;
+use solana_token_cli::output::{PoolStateOutput, TransactionOutput};
+use solana_token_cli::{
+    process_command, Args, CliError, Command, CommandOutput, Config, OutputFormat, PoolModeArg,
+};
+use solana_token_client::{ClientError, WrapperClient};
+
+fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
+
+fn config(fee_payer: Box<dyn Signer>, owner: Box<dyn Signer>) -> Config {
+    Config {
+        fee_payer,
+        owner,
+        program_id: solana_token::id(),
+        output: OutputFormat::Json,
+        sign_only: false,
+        blockhash: None,
+        nonce: None,
+        nonce_authority: Box::new(NullSigner::new(&Pubkey::default())),
+        presigners: vec![],
+    }
+}
+
+/// Test of the program, with the stand-in of the metadata program
+fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new(
+        "solana_token",
+        solana_token::id(),
+        processor!(Processor::process_instruction),
+    );
+    program_test.add_builtin_program(
+        "metadata",
+        solana_token::metadata::id(),
+        processor!(metadata::process_metadata).unwrap(),
+    );
+    program_test
+}
+
+async fn setup() -> (WrapperClient<BanksClient>, Keypair) {
+    let (banks_client, payer, _) = program_test().start().await;
+    (WrapperClient::new(banks_client), payer)
+}
+
+fn init_pool(token_x_mint: Pubkey) -> Command {
+    Command::InitPool {
+        token_x_mint,
+        pool_mint_keypair: None,
+        name: "Wrapped X".to_string(),
+        symbol: "WX".to_string(),
+        uri: String::new(),
+        mode: PoolModeArg::Fixed,
+        unwrap_delay: 0,
+        subscription_deadline: 0,
+        maturity: 0,
+    }
+}
+
+/// Creates the token X mint and a wallet of `owner` holding `amount`
+async fn create_token_x(
+    client: &mut WrapperClient<BanksClient>,
+    payer: &Keypair,
+    owner: &Pubkey,
+    amount: u64,
+) -> (Pubkey, Pubkey) {
+    let mint = Keypair::new();
+    let wallet = Keypair::new();
+    let rent = client.transport().get_rent().await.unwrap();
+    client
+        .process_instructions(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &payer.pubkey(),
+                    None,
+                    0,
+                )
+                .unwrap(),
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &wallet.pubkey(),
+                    rent.minimum_balance(spl_token::state::Account::LEN),
+                    spl_token::state::Account::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_account(
+                    &spl_token::id(),
+                    &wallet.pubkey(),
+                    &mint.pubkey(),
+                    owner,
+                )
+                .unwrap(),
+                spl_token::instruction::mint_to(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &wallet.pubkey(),
+                    &payer.pubkey(),
+                    &[],
+                    amount,
+                )
+                .unwrap(),
+            ],
+            &payer.pubkey(),
+            &[payer, &mint, &wallet],
+        )
+        .await
+        .unwrap();
+    (mint.pubkey(), wallet.pubkey())
+}
+
+#[test]
+fn parse_args() {
+    let pool_mint = Pubkey::new_unique();
+    let wallet_x = Pubkey::new_unique();
+    let args = Args::try_parse_from([
+        "solana-token-cli",
+        "unwrap",
+        "--wallet-x",
+        &wallet_x.to_string(),
+        "--pool-mint",
+        &pool_mint.to_string(),
+        "10",
+        "--sign-only",
+        "--blockhash",
+        &Hash::default().to_string(),
+        "--output",
+        "json",
+    ])
+    .unwrap();
+
+    assert!(args.sign_only);
+    assert_eq!(args.blockhash, Some(Hash::default()));
+    assert_eq!(args.output, OutputFormat::Json);
+    assert_eq!(
+        args.command,
+        Command::Unwrap {
+            wallet_x,
+            pool_mint,
+            wallet_y: None,
+            off_chain_pool: false,
+            amount: 10,
+        }
+    );
+
+    let token_x_mint = Pubkey::new_unique();
+    let args = Args::try_parse_from([
+        "solana-token-cli",
+        "init-pool",
+        "--token-x-mint",
+        &token_x_mint.to_string(),
+        "--name",
+        "Wrapped X",
+        "--symbol",
+        "WX",
+        "--mode",
+        "shares",
+        "--unwrap-delay",
+        "60",
+        "--maturity",
+        "1800000000",
+    ])
+    .unwrap();
+    assert_eq!(
+        args.command,
+        Command::InitPool {
+            token_x_mint,
+            pool_mint_keypair: None,
+            name: "Wrapped X".to_string(),
+            symbol: "WX".to_string(),
+            uri: String::new(),
+            mode: PoolModeArg::Shares,
+            unwrap_delay: 60,
+            subscription_deadline: 0,
+            maturity: 1_800_000_000,
+        }
+    );
+}
+
+#[test]
+fn sign_only_requires_blockhash() {
+    let pool_mint = Pubkey::new_unique().to_string();
+    let wallet_x = Pubkey::new_unique().to_string();
+    let unwrap = [
+        "solana-token-cli",
+        "unwrap",
+        "--wallet-x",
+        &wallet_x,
+        "--pool-mint",
+        &pool_mint,
+        "10",
+        "--sign-only",
+    ];
+
+    let error = Args::try_parse_from(unwrap).unwrap_err();
+    assert_eq!(error.kind(), clap::ErrorKind::MissingRequiredArgument);
+}
+
+#[test]
+fn keypair_read_only_to_sign() {
+    let pool_mint = Pubkey::new_unique().to_string();
+    let wallet_x = Pubkey::new_unique().to_string();
+    let keypair = ["--keypair", "/nonexistent/id.json"];
+
+    let show_pool = Args::try_parse_from(
+        [
+            &["solana-token-cli"][..],
+            &keypair,
+            &["show-pool", &pool_mint],
+        ]
+        .concat(),
+    )
+    .unwrap();
+    let config = Config::from_args(&show_pool).unwrap();
+    assert_eq!(config.program_id, solana_token::id());
+
+    let unwrap = Args::try_parse_from(
+        [
+            &["solana-token-cli"][..],
+            &keypair,
+            &[
+                "unwrap",
+                "--wallet-x",
+                &wallet_x,
+                "--pool-mint",
+                &pool_mint,
+                "10",
+            ],
+        ]
+        .concat(),
+    )
+    .unwrap();
+    assert!(matches!(
+        Config::from_args(&unwrap),
+        Err(CliError::InvalidKeypair(path, _)) if path == keypair[1]
+    ));
+}
+
+#[tokio::test]
+async fn init_pool_wrap_and_unwrap() {
+    let amount = 100;
+    let (mut client, payer) = setup().await;
+    let config = config(
+        Box::new(clone_keypair(&payer)),
+        Box::new(clone_keypair(&payer)),
+    );
+    let (token_x_mint, wallet_x) =
+        create_token_x(&mut client, &payer, &payer.pubkey(), amount).await;
+
+    let pool_mint = match process_command(&mut client, &config, init_pool(token_x_mint))
+        .await
+        .unwrap()
+    {
+        CommandOutput::InitPool { pool_mint, .. } => pool_mint.parse::<Pubkey>().unwrap(),
+        output => panic!("unexpected output {:?}", output),
+    };
+
+    process_command(
+        &mut client,
+        &config,
+        Command::Wrap {
+            wallet_x,
+            token_x_mint,
+            pool_mint,
+            wallet_y: None,
+            off_chain_pool: false,
+            amount,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        process_command(&mut client, &config, Command::ShowPool { pool_mint })
+            .await
+            .unwrap(),
+        CommandOutput::Pool {
+            pool_mint: pool_mint.to_string(),
+            pool_mint_authority: client.pool(&pool_mint).to_string(),
+            supply: amount,
+            decimals: 0,
+            state: Some(PoolStateOutput {
+                vault: client.pool_vault(&pool_mint).to_string(),
+                admin: payer.pubkey().to_string(),
+                token_x_mint: token_x_mint.to_string(),
+                mode: PoolMode::Fixed,
+                unwrap_delay: 0,
+                pending_unwrap: 0,
+                subscription_deadline: 0,
+                maturity: 0,
+            }),
+        }
+    );
+
+    process_command(
+        &mut client,
+        &config,
+        Command::Unwrap {
+            wallet_x,
+            pool_mint,
+            wallet_y: None,
+            off_chain_pool: false,
+            amount: 30,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        process_command(
+            &mut client,
+            &config,
+            Command::ShowVault {
+                pool_mint,
+                wallet_x: None,
+            }
+        )
+        .await
+        .unwrap(),
+        CommandOutput::Vault {
+            pool_mint: pool_mint.to_string(),
+            wallet_x: None,
+            vault: client.pool_vault(&pool_mint).to_string(),
+            balance: amount - 30,
+        }
+    );
+}
+
+#[tokio::test]
+async fn wrap_off_chain_pool() {
+    let amount = 100;
+    let (mut client, payer) = setup().await;
+    let config = config(
+        Box::new(clone_keypair(&payer)),
+        Box::new(clone_keypair(&payer)),
+    );
+    let (token_x_mint, wallet_x) =
+        create_token_x(&mut client, &payer, &payer.pubkey(), amount).await;
+    let pool_mint = Keypair::new();
+    let rent = client.transport().get_rent().await.unwrap();
+    client
+        .process_instructions(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &pool_mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint(
+                    &spl_token::id(),
+                    &pool_mint.pubkey(),
+                    &client.pool_mint_authority(&pool_mint.pubkey()),
+                    None,
+                    0,
+                )
+                .unwrap(),
+            ],
+            &payer.pubkey(),
+            &[&payer, &pool_mint],
+        )
+        .await
+        .unwrap();
+    let pool_mint = pool_mint.pubkey();
+
+    process_command(
+        &mut client,
+        &config,
+        Command::Wrap {
+            wallet_x,
+            token_x_mint,
+            pool_mint,
+            wallet_y: None,
+            off_chain_pool: true,
+            amount,
+        },
+    )
+    .await
+    .unwrap();
+    assert!(matches!(
+        process_command(
+            &mut client,
+            &config,
+            Command::ShowVault {
+                pool_mint,
+                wallet_x: None,
+            }
+        )
+        .await,
+        Err(CliError::Client(ClientError::UserWalletRequired(_)))
+    ));
+    assert_eq!(
+        process_command(
+            &mut client,
+            &config,
+            Command::ShowVault {
+                pool_mint,
+                wallet_x: Some(wallet_x),
+            }
+        )
+        .await
+        .unwrap(),
+        CommandOutput::Vault {
+            pool_mint: pool_mint.to_string(),
+            wallet_x: Some(wallet_x.to_string()),
+            vault: client.pool_wallet_x(&wallet_x).to_string(),
+            balance: amount,
+        }
+    );
+}
+
+#[tokio::test]
+async fn sign_only_without_owner_keypair() {
+    let (mut client, payer) = setup().await;
+    let owner = Keypair::new();
+    let mut config = config(
+        Box::new(clone_keypair(&payer)),
+        Box::new(NullSigner::new(&owner.pubkey())),
+    );
+    let command = || Command::Unwrap {
+        wallet_x: Pubkey::new_unique(),
+        pool_mint: Pubkey::new_unique(),
+        wallet_y: None,
+        off_chain_pool: false,
+        amount: 1,
+    };
+
+    assert!(matches!(
+        process_command(&mut client, &config, command()).await,
+        Err(CliError::MissingSignature(pubkey)) if pubkey == owner.pubkey()
+    ));
+
+    config.sign_only = true;
+    let output = process_command(&mut client, &config, command())
+        .await
+        .unwrap();
+    let (signers, absent_signers, transaction) = match output {
+        CommandOutput::Transaction(TransactionOutput::SignOnly {
+            signers,
+            absent_signers,
+            transaction,
+            ..
+        }) => (signers, absent_signers, transaction),
+        output => panic!("unexpected output {:?}", output),
+    };
+    assert_eq!(signers.len(), 1);
+    assert!(signers[0].starts_with(&payer.pubkey().to_string()));
+    assert_eq!(absent_signers, vec![owner.pubkey().to_string()]);
+
+    let mut tx: Transaction =
+        bincode::deserialize(&bs58::decode(transaction).into_vec().unwrap()).unwrap();
+    tx.try_partial_sign(&[&owner], tx.message.recent_blockhash)
+        .unwrap();
+    tx.verify().unwrap();
+}
+
+fn unsigned_transaction(output: CommandOutput) -> (String, Vec<String>) {
+    match output {
+        CommandOutput::Transaction(TransactionOutput::SignOnly {
+            transaction,
+            absent_signers,
+            ..
+        }) => (transaction, absent_signers),
+        output => panic!("unexpected output {:?}", output),
+    }
+}
+
+async fn nonce_blockhash(client: &mut WrapperClient<BanksClient>, nonce: &Pubkey) -> Hash {
+    let account = client
+        .transport()
+        .get_account(*nonce)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, system_program::id());
+    match bincode::deserialize(&account.data).unwrap() {
+        Versions::Current(state) => match *state {
+            State::Initialized(data) => data.blockhash,
+            State::Uninitialized => panic!("nonce is not initialized"),
+        },
+    }
+}
+
+#[tokio::test]
+async fn merge_offline_signatures_with_nonce() {
+    let amount = 100;
+    let mut context = program_test().start_with_context().await;
+    let mut client = WrapperClient::new(context.banks_client.clone());
+    let fee_payer = clone_keypair(&context.payer);
+    let owner = Keypair::new();
+    let nonce = Keypair::new();
+
+    let rent = client.transport().get_rent().await.unwrap();
+    let mut instructions = system_instruction::create_nonce_account(
+        &fee_payer.pubkey(),
+        &nonce.pubkey(),
+        &fee_payer.pubkey(),
+        rent.minimum_balance(State::size()),
+    );
+    instructions.push(system_instruction::transfer(
+        &fee_payer.pubkey(),
+        &owner.pubkey(),
+        1_000_000_000,
+    ));
+    client
+        .process_instructions(&instructions, &fee_payer.pubkey(), &[&fee_payer, &nonce])
+        .await
+        .unwrap();
+    let (token_x_mint, wallet_x) =
+        create_token_x(&mut client, &fee_payer, &owner.pubkey(), amount).await;
+    let pool_mint = match process_command(
+        &mut client,
+        &config(
+            Box::new(clone_keypair(&fee_payer)),
+            Box::new(clone_keypair(&fee_payer)),
+        ),
+        init_pool(token_x_mint),
+    )
+    .await
+    .unwrap()
+    {
+        CommandOutput::InitPool { pool_mint, .. } => pool_mint.parse::<Pubkey>().unwrap(),
+        output => panic!("unexpected output {:?}", output),
+    };
+    // A nonce can only be advanced once the blockhash it stores is no longer the latest
+    context.warp_to_slot(10).unwrap();
+    let durable_nonce = nonce_blockhash(&mut client, &nonce.pubkey()).await;
+
+    // Unsigned transaction built without any keypair
+    let mut online = config(
+        Box::new(NullSigner::new(&fee_payer.pubkey())),
+        Box::new(NullSigner::new(&owner.pubkey())),
+    );
+    online.sign_only = true;
+    online.nonce = Some(nonce.pubkey());
+    online.nonce_authority = Box::new(NullSigner::new(&fee_payer.pubkey()));
+    let (unsigned, absent_signers) = unsigned_transaction(
+        process_command(
+            &mut client,
+            &online,
+            Command::Wrap {
+                wallet_x,
+                token_x_mint,
+                pool_mint,
+                wallet_y: None,
+                off_chain_pool: false,
+                amount,
+            },
+        )
+        .await
+        .unwrap(),
+    );
+    assert_eq!(
+        absent_signers,
+        vec![fee_payer.pubkey().to_string(), owner.pubkey().to_string()]
+    );
+
+    // Each key signs on its own machine
+    let mut fee_payer_config = config(
+        Box::new(clone_keypair(&fee_payer)),
+        Box::new(NullSigner::new(&owner.pubkey())),
+    );
+    fee_payer_config.nonce_authority = Box::new(clone_keypair(&fee_payer));
+    let owner_config = config(
+        Box::new(NullSigner::new(&fee_payer.pubkey())),
+        Box::new(clone_keypair(&owner)),
+    );
+    let (signed_by_fee_payer, absent_signers) = unsigned_transaction(
+        process_command(
+            &mut client,
+            &fee_payer_config,
+            Command::Sign {
+                transaction: unsigned.clone(),
+            },
+        )
+        .await
+        .unwrap(),
+    );
+    assert_eq!(absent_signers, vec![owner.pubkey().to_string()]);
+    let signed_by_owner = process_command(
+        &mut client,
+        &owner_config,
+        Command::Sign {
+            transaction: unsigned,
+        },
+    )
+    .await
+    .unwrap();
+    let owner_file = std::env::temp_dir().join(format!("{}.json", owner.pubkey()));
+    std::fs::write(
+        &owner_file,
+        serde_json::to_string(&signed_by_owner).unwrap(),
+    )
+    .unwrap();
+
+    let (merged, absent_signers) = unsigned_transaction(
+        process_command(
+            &mut client,
+            &online,
+            Command::Merge {
+                transactions: vec![
+                    signed_by_fee_payer,
+                    owner_file.to_str().unwrap().to_string(),
+                ],
+            },
+        )
+        .await
+        .unwrap(),
+    );
+    std::fs::remove_file(owner_file).unwrap();
+    assert!(absent_signers.is_empty());
+
+    process_command(
+        &mut client,
+        &online,
+        Command::Send {
+            transaction: merged,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        client.vault_balance(&pool_mint, None).await.unwrap(),
+        amount
+    );
+    assert_ne!(
+        nonce_blockhash(&mut client, &nonce.pubkey()).await,
+        durable_nonce
+    );
+}
@@ -0,0 +1,245 @@
+//! Client for the program instructions
+
+use crate::error::ClientError;
+use crate::transport::ClientTransport;
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use solana_token::state::{Pool, PoolMode};
+use solana_token::{find_pool_address, find_program_address, instruction};
+
+/// State of a pool mint
+#[derive(Debug, PartialEq)]
+pub struct PoolInfo {
+    pub pool_mint: Pubkey,
+    pub pool_mint_authority: Pubkey,
+    pub supply: u64,
+    pub decimals: u8,
+    /// State of the pool if it was created by `InitializePool`, `None` for pools
+    /// created off-chain
+    pub state: Option<PoolState>,
+}
+
+/// State of a pool created by `InitializePool`, which is the pool mint authority
+#[derive(Debug, PartialEq)]
+pub struct PoolState {
+    /// Token X account of the pool
+    pub vault: Pubkey,
+    pub admin: Pubkey,
+    pub token_x_mint: Pubkey,
+    pub mode: PoolMode,
+    pub unwrap_delay: u32,
+    pub pending_unwrap: u64,
+    pub subscription_deadline: i64,
+    pub maturity: i64,
+}
+
+/// Client for the wrapper program over any `ClientTransport`
+pub struct WrapperClient<T> {
+    transport: T,
+    program_id: Pubkey,
+}
+
+impl<T: ClientTransport> WrapperClient<T> {
+    pub fn new(transport: T) -> Self {
+        Self::with_program_id(transport, solana_token::id())
+    }
+
+    pub fn with_program_id(transport: T, program_id: Pubkey) -> Self {
+        Self {
+            transport,
+            program_id,
+        }
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    pub fn transport(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Address of the program authority minting `pool_mint`
+    pub fn pool_mint_authority(&self, pool_mint: &Pubkey) -> Pubkey {
+        find_program_address(&self.program_id, pool_mint).0
+    }
+
+    /// Address of the pool wallet holding token X wrapped from `user_wallet_x`
+    pub fn pool_wallet_x(&self, user_wallet_x: &Pubkey) -> Pubkey {
+        find_program_address(&self.program_id, user_wallet_x).0
+    }
+
+    /// Address of the state of the pool created by `InitializePool` for `pool_mint`
+    pub fn pool(&self, pool_mint: &Pubkey) -> Pubkey {
+        find_pool_address(&self.program_id, pool_mint).0
+    }
+
+    /// Address of the vault holding the token X of the pool created by `InitializePool`
+    /// for `pool_mint`
+    pub fn pool_vault(&self, pool_mint: &Pubkey) -> Pubkey {
+        find_program_address(&self.program_id, &self.pool(pool_mint)).0
+    }
+
+    /// Signs the instructions with a fresh blockhash and processes them
+    pub async fn process_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        signers: &[&dyn Signer],
+    ) -> Result<(), ClientError> {
+        let blockhash = self.transport.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(payer),
+            &signers.to_vec(),
+            blockhash,
+        );
+        self.transport.process_transaction(tx).await
+    }
+
+    /// Changes `amount` of token X into pool tokens, `authority` owns both wallets and pays fees
+    pub async fn wrap(
+        &mut self,
+        authority: &dyn Signer,
+        user_wallet_x: &Pubkey,
+        token_x_mint: &Pubkey,
+        user_wallet_y: &Pubkey,
+        pool_mint: &Pubkey,
+        amount: u64,
+    ) -> Result<(), ClientError> {
+        let ix = instruction::change_x_to_y(
+            &self.program_id,
+            &authority.pubkey(),
+            user_wallet_x,
+            token_x_mint,
+            user_wallet_y,
+            pool_mint,
+            amount,
+        );
+        self.process_instructions(&[ix], &authority.pubkey(), &[authority])
+            .await
+    }
+
+    /// Changes `amount` of pool tokens back into token X, `authority` owns both wallets
+    /// and pays fees
+    pub async fn unwrap(
+        &mut self,
+        authority: &dyn Signer,
+        user_wallet_x: &Pubkey,
+        user_wallet_y: &Pubkey,
+        pool_mint: &Pubkey,
+        amount: u64,
+    ) -> Result<(), ClientError> {
+        let ix = instruction::change_y_to_x(
+            &self.program_id,
+            &authority.pubkey(),
+            user_wallet_x,
+            user_wallet_y,
+            pool_mint,
+            amount,
+        );
+        self.process_instructions(&[ix], &authority.pubkey(), &[authority])
+            .await
+    }
+
+    /// Reads the pool mint, and the pool state if the mint was created by
+    /// `InitializePool`. Fails if the mint isn't minted by the program
+    pub async fn pool_info(&mut self, pool_mint: &Pubkey) -> Result<PoolInfo, ClientError> {
+        let account = self
+            .transport
+            .get_account(*pool_mint)
+            .await?
+            .ok_or(ClientError::AccountNotFound(*pool_mint))?;
+        if account.owner != spl_token::id() {
+            return Err(ClientError::InvalidAccountData(*pool_mint));
+        }
+        let mint = spl_token::state::Mint::unpack(&account.data)
+            .map_err(|_| ClientError::InvalidAccountData(*pool_mint))?;
+
+        let pool = self.pool(pool_mint);
+        let (pool_mint_authority, state) = if mint.mint_authority == COption::Some(pool) {
+            (pool, Some(self.pool_state(&pool, pool_mint).await?))
+        } else {
+            let pool_mint_authority = self.pool_mint_authority(pool_mint);
+            if mint.mint_authority != COption::Some(pool_mint_authority) {
+                return Err(ClientError::InvalidPoolMint(*pool_mint));
+            }
+            (pool_mint_authority, None)
+        };
+
+        Ok(PoolInfo {
+            pool_mint: *pool_mint,
+            pool_mint_authority,
+            supply: mint.supply,
+            decimals: mint.decimals,
+            state,
+        })
+    }
+
+    async fn pool_state(
+        &mut self,
+        pool: &Pubkey,
+        pool_mint: &Pubkey,
+    ) -> Result<PoolState, ClientError> {
+        let account = self
+            .transport
+            .get_account(*pool)
+            .await?
+            .ok_or(ClientError::AccountNotFound(*pool))?;
+        if account.owner != self.program_id {
+            return Err(ClientError::InvalidAccountData(*pool));
+        }
+        let state = Pool::unpack(&account.data)
+            .ok()
+            .filter(|state| state.pool_mint == *pool_mint)
+            .ok_or(ClientError::InvalidAccountData(*pool))?;
+
+        Ok(PoolState {
+            vault: self.pool_vault(pool_mint),
+            admin: state.admin,
+            token_x_mint: state.token_x_mint,
+            mode: state.mode,
+            unwrap_delay: state.unwrap_delay,
+            pending_unwrap: state.pending_unwrap,
+            subscription_deadline: state.subscription_deadline,
+            maturity: state.maturity,
+        })
+    }
+
+    /// Account holding the token X of `pool_mint`: the vault of a pool created by
+    /// `InitializePool`, or else the pool wallet of `user_wallet_x`
+    pub async fn vault(
+        &mut self,
+        pool_mint: &Pubkey,
+        user_wallet_x: Option<&Pubkey>,
+    ) -> Result<Pubkey, ClientError> {
+        match (self.pool_info(pool_mint).await?.state, user_wallet_x) {
+            (Some(state), _) => Ok(state.vault),
+            (None, Some(user_wallet_x)) => Ok(self.pool_wallet_x(user_wallet_x)),
+            (None, None) => Err(ClientError::UserWalletRequired(*pool_mint)),
+        }
+    }
+
+    /// Token X held by the account returned by `vault`, zero before the first wrap
+    pub async fn vault_balance(
+        &mut self,
+        pool_mint: &Pubkey,
+        user_wallet_x: Option<&Pubkey>,
+    ) -> Result<u64, ClientError> {
+        let vault = self.vault(pool_mint, user_wallet_x).await?;
+        match self.transport.get_account(vault).await? {
+            Some(account) if account.owner == spl_token::id() => {
+                spl_token::state::Account::unpack(&account.data)
+                    .map(|account| account.amount)
+                    .map_err(|_| ClientError::InvalidAccountData(vault))
+            }
+            Some(account) if account.data.is_empty() => Ok(0),
+            Some(_) => Err(ClientError::InvalidAccountData(vault)),
+            None => Ok(0),
+        }
+    }
+}
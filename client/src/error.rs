@@ -0,0 +1,56 @@
+//! Error types
+
+use num_traits::FromPrimitive;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
+use solana_sdk::transport::TransportError;
+use solana_token::error::CrateError;
+use thiserror::Error;
+
+/// Errors that may be returned by the client
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The transaction couldn't be sent or failed
+    #[error("Transport error: {0}")]
+    Transport(#[from] TransportError),
+    /// The account doesn't exist
+    #[error("Account {0} not found")]
+    AccountNotFound(Pubkey),
+    /// The account data can't be unpacked into the expected state
+    #[error("Account {0} has unexpected data")]
+    InvalidAccountData(Pubkey),
+    /// The pool mint authority isn't the program address derived from the mint
+    #[error("Pool mint {0} is not controlled by the program")]
+    InvalidPoolMint(Pubkey),
+    /// The pool was created off-chain, so its token X is held per user wallet
+    #[error("Pool mint {0} holds token X per user wallet, a user wallet is required")]
+    UserWalletRequired(Pubkey),
+}
+
+impl From<std::io::Error> for ClientError {
+    fn from(e: std::io::Error) -> Self {
+        ClientError::Transport(e.into())
+    }
+}
+
+impl ClientError {
+    /// Error returned by the wrapper program, if that is why the transaction failed
+    pub fn crate_error(&self) -> Option<CrateError> {
+        match self {
+            ClientError::Transport(TransportError::TransactionError(error)) => crate_error(error),
+            _ => None,
+        }
+    }
+}
+
+/// Converts `InstructionError(_, Custom(code))` back into the wrapper error, codes
+/// of other programs, like the token program, give `None`
+pub fn crate_error(error: &TransactionError) -> Option<CrateError> {
+    match error {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            CrateError::from_u32(*code)
+        }
+        _ => None,
+    }
+}
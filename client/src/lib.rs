@@ -0,0 +1,9 @@
+//! Client for the token wrapper program
+
+pub mod client;
+pub mod error;
+pub mod transport;
+
+pub use client::{PoolInfo, PoolState, WrapperClient};
+pub use error::{crate_error, ClientError};
+pub use transport::ClientTransport;
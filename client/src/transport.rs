@@ -0,0 +1,37 @@
+//! Cluster access used by the client
+
+use crate::error::ClientError;
+use async_trait::async_trait;
+use solana_banks_client::BanksClient;
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+
+/// Calls made by `WrapperClient`, modelled after `BanksClient`
+#[async_trait]
+pub trait ClientTransport {
+    /// Returns the account at `address`, `None` if it doesn't exist
+    async fn get_account(&mut self, address: Pubkey) -> Result<Option<Account>, ClientError>;
+
+    /// Returns a blockhash to sign transactions with
+    async fn get_latest_blockhash(&mut self) -> Result<Hash, ClientError>;
+
+    /// Sends the transaction and waits until it is processed
+    async fn process_transaction(&mut self, transaction: Transaction) -> Result<(), ClientError>;
+}
+
+#[async_trait]
+impl ClientTransport for BanksClient {
+    async fn get_account(&mut self, address: Pubkey) -> Result<Option<Account>, ClientError> {
+        Ok(BanksClient::get_account(self, address).await?)
+    }
+
+    async fn get_latest_blockhash(&mut self) -> Result<Hash, ClientError> {
+        Ok(BanksClient::get_latest_blockhash(self).await?)
+    }
+
+    async fn process_transaction(&mut self, transaction: Transaction) -> Result<(), ClientError> {
+        Ok(BanksClient::process_transaction(self, transaction).await?)
+    }
+}
@@ -0,0 +1,433 @@
+#[path = "../../tests/utils/metadata.rs"]
+mod metadata;
+
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+use solana_program_test::*;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::TransactionError;
+use solana_token::error::CrateError;
+use solana_token::instruction;
+use solana_token::processor::Processor;
+use solana_token::state::{PoolMode, SHARES_DECIMALS_OFFSET, VIRTUAL_SHARES};
+use solana_token_client::{crate_error, ClientError, PoolInfo, PoolState, WrapperClient};
+
+struct Setup {
+    client: WrapperClient<BanksClient>,
+    payer: Keypair,
+    token_x_mint: Keypair,
+    pool_mint: Keypair,
+    user: Keypair,
+    user_wallet_x: Keypair,
+    user_wallet_y: Keypair,
+}
+
+async fn create_mint(
+    client: &mut WrapperClient<BanksClient>,
+    payer: &Keypair,
+    mint: &Keypair,
+    authority: &Pubkey,
+) {
+    let rent = client.transport().get_rent().await.unwrap();
+    client
+        .process_instructions(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    authority,
+                    None,
+                    0,
+                )
+                .unwrap(),
+            ],
+            &payer.pubkey(),
+            &[payer, mint],
+        )
+        .await
+        .unwrap();
+}
+
+async fn create_token_account(
+    client: &mut WrapperClient<BanksClient>,
+    payer: &Keypair,
+    account: &Keypair,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) {
+    let rent = client.transport().get_rent().await.unwrap();
+    client
+        .process_instructions(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &account.pubkey(),
+                    rent.minimum_balance(spl_token::state::Account::LEN),
+                    spl_token::state::Account::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_account(
+                    &spl_token::id(),
+                    &account.pubkey(),
+                    mint,
+                    owner,
+                )
+                .unwrap(),
+            ],
+            &payer.pubkey(),
+            &[payer, account],
+        )
+        .await
+        .unwrap();
+}
+
+async fn setup(amount: u64) -> Setup {
+    let mut program_test = ProgramTest::new(
+        "solana_token",
+        solana_token::id(),
+        processor!(Processor::process_instruction),
+    );
+    program_test.add_builtin_program(
+        "metadata",
+        solana_token::metadata::id(),
+        processor!(metadata::process_metadata).unwrap(),
+    );
+    let (banks_client, payer, _) = program_test.start().await;
+    let mut client = WrapperClient::new(banks_client);
+
+    let token_x_mint = Keypair::new();
+    let pool_mint = Keypair::new();
+    let user = Keypair::new();
+    let user_wallet_x = Keypair::new();
+    let user_wallet_y = Keypair::new();
+
+    create_mint(&mut client, &payer, &token_x_mint, &payer.pubkey()).await;
+    let pool_mint_authority = client.pool_mint_authority(&pool_mint.pubkey());
+    create_mint(&mut client, &payer, &pool_mint, &pool_mint_authority).await;
+    create_token_account(
+        &mut client,
+        &payer,
+        &user_wallet_x,
+        &token_x_mint.pubkey(),
+        &user.pubkey(),
+    )
+    .await;
+    create_token_account(
+        &mut client,
+        &payer,
+        &user_wallet_y,
+        &pool_mint.pubkey(),
+        &user.pubkey(),
+    )
+    .await;
+    client
+        .process_instructions(
+            &[
+                system_instruction::transfer(&payer.pubkey(), &user.pubkey(), 1_000_000_000),
+                spl_token::instruction::mint_to(
+                    &spl_token::id(),
+                    &token_x_mint.pubkey(),
+                    &user_wallet_x.pubkey(),
+                    &payer.pubkey(),
+                    &[],
+                    amount,
+                )
+                .unwrap(),
+            ],
+            &payer.pubkey(),
+            &[&payer],
+        )
+        .await
+        .unwrap();
+
+    Setup {
+        client,
+        payer,
+        token_x_mint,
+        pool_mint,
+        user,
+        user_wallet_x,
+        user_wallet_y,
+    }
+}
+
+#[tokio::test]
+async fn wrap_and_unwrap() {
+    let amount = 100;
+    let Setup {
+        mut client,
+        token_x_mint,
+        pool_mint,
+        user,
+        user_wallet_x,
+        user_wallet_y,
+        ..
+    } = setup(amount).await;
+
+    assert_eq!(
+        client
+            .vault_balance(&pool_mint.pubkey(), Some(&user_wallet_x.pubkey()))
+            .await
+            .unwrap(),
+        0
+    );
+
+    assert!(matches!(
+        client.vault_balance(&pool_mint.pubkey(), None).await,
+        Err(ClientError::UserWalletRequired(_))
+    ));
+
+    client
+        .wrap(
+            &user,
+            &user_wallet_x.pubkey(),
+            &token_x_mint.pubkey(),
+            &user_wallet_y.pubkey(),
+            &pool_mint.pubkey(),
+            amount,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        client
+            .vault_balance(&pool_mint.pubkey(), Some(&user_wallet_x.pubkey()))
+            .await
+            .unwrap(),
+        amount
+    );
+    assert_eq!(
+        client.pool_info(&pool_mint.pubkey()).await.unwrap(),
+        PoolInfo {
+            pool_mint: pool_mint.pubkey(),
+            pool_mint_authority: client.pool_mint_authority(&pool_mint.pubkey()),
+            supply: amount,
+            decimals: 0,
+            state: None,
+        }
+    );
+
+    client
+        .unwrap(
+            &user,
+            &user_wallet_x.pubkey(),
+            &user_wallet_y.pubkey(),
+            &pool_mint.pubkey(),
+            amount,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        client
+            .vault_balance(&pool_mint.pubkey(), Some(&user_wallet_x.pubkey()))
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        client.pool_info(&pool_mint.pubkey()).await.unwrap().supply,
+        0
+    );
+}
+
+#[tokio::test]
+async fn pool_info_rejects_foreign_mint() {
+    let Setup {
+        mut client,
+        payer,
+        token_x_mint,
+        ..
+    } = setup(0).await;
+
+    assert!(matches!(
+        client.pool_info(&token_x_mint.pubkey()).await,
+        Err(ClientError::InvalidPoolMint(_))
+    ));
+    assert!(matches!(
+        client.pool_info(&payer.pubkey()).await,
+        Err(ClientError::InvalidAccountData(_))
+    ));
+    assert!(matches!(
+        client.pool_info(&Pubkey::new_unique()).await,
+        Err(ClientError::AccountNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn pool_info_reads_pool_state() {
+    let amount = 100;
+    let Setup {
+        mut client,
+        payer,
+        token_x_mint,
+        user,
+        user_wallet_x,
+        ..
+    } = setup(amount).await;
+    let pool_mint = Keypair::new();
+    let ix = instruction::initialize_pool(
+        client.program_id(),
+        &payer.pubkey(),
+        &pool_mint.pubkey(),
+        &token_x_mint.pubkey(),
+        "Wrapped X",
+        "WX",
+        "https://example.com/pool.json",
+        PoolMode::Shares,
+        60,
+        4_000_000_000,
+        4_100_000_000,
+    );
+    client
+        .process_instructions(&[ix], &payer.pubkey(), &[&payer, &pool_mint])
+        .await
+        .unwrap();
+    let user_wallet_y = Keypair::new();
+    create_token_account(
+        &mut client,
+        &payer,
+        &user_wallet_y,
+        &pool_mint.pubkey(),
+        &user.pubkey(),
+    )
+    .await;
+    let ix = instruction::change_x_to_y_pool(
+        client.program_id(),
+        &user.pubkey(),
+        &user_wallet_x.pubkey(),
+        &token_x_mint.pubkey(),
+        &user_wallet_y.pubkey(),
+        &pool_mint.pubkey(),
+        amount,
+    );
+    client
+        .process_instructions(&[ix], &user.pubkey(), &[&user])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        client.pool_info(&pool_mint.pubkey()).await.unwrap(),
+        PoolInfo {
+            pool_mint: pool_mint.pubkey(),
+            pool_mint_authority: client.pool(&pool_mint.pubkey()),
+            supply: amount * VIRTUAL_SHARES,
+            decimals: SHARES_DECIMALS_OFFSET,
+            state: Some(PoolState {
+                vault: client.pool_vault(&pool_mint.pubkey()),
+                admin: payer.pubkey(),
+                token_x_mint: token_x_mint.pubkey(),
+                mode: PoolMode::Shares,
+                unwrap_delay: 60,
+                pending_unwrap: 0,
+                subscription_deadline: 4_000_000_000,
+                maturity: 4_100_000_000,
+            }),
+        }
+    );
+    assert_eq!(
+        client
+            .vault_balance(&pool_mint.pubkey(), None)
+            .await
+            .unwrap(),
+        amount
+    );
+}
+
+#[tokio::test]
+async fn wrap_fails_without_tokens() {
+    let amount = 100;
+    let Setup {
+        mut client,
+        token_x_mint,
+        pool_mint,
+        user,
+        user_wallet_x,
+        user_wallet_y,
+        ..
+    } = setup(amount).await;
+
+    let error = client
+        .wrap(
+            &user,
+            &user_wallet_x.pubkey(),
+            &token_x_mint.pubkey(),
+            &user_wallet_y.pubkey(),
+            &pool_mint.pubkey(),
+            amount + 1,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error.crate_error(), Some(CrateError::NotEnoughTokens));
+}
+
+#[tokio::test]
+async fn unwrap_fails_with_empty_pool_wallet() {
+    let amount = 100;
+    let Setup {
+        mut client,
+        token_x_mint,
+        pool_mint,
+        user,
+        user_wallet_x,
+        user_wallet_y,
+        ..
+    } = setup(amount).await;
+    client
+        .wrap(
+            &user,
+            &user_wallet_x.pubkey(),
+            &token_x_mint.pubkey(),
+            &user_wallet_y.pubkey(),
+            &pool_mint.pubkey(),
+            amount,
+        )
+        .await
+        .unwrap();
+
+    let error = client
+        .unwrap(
+            &user,
+            &user_wallet_x.pubkey(),
+            &user_wallet_y.pubkey(),
+            &pool_mint.pubkey(),
+            amount + 1,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.crate_error(),
+        Some(CrateError::InsufficientPoolBalance)
+    );
+}
+
+#[test]
+fn crate_error_ignores_other_programs() {
+    let instruction_error = |error| TransactionError::InstructionError(0, error);
+
+    assert_eq!(
+        crate_error(&instruction_error(InstructionError::Custom(
+            CrateError::InvalidPoolAuthority as u32
+        ))),
+        Some(CrateError::InvalidPoolAuthority)
+    );
+    // spl_token::error::TokenError::InsufficientFunds
+    assert_eq!(
+        crate_error(&instruction_error(InstructionError::Custom(1))),
+        None
+    );
+    assert_eq!(
+        crate_error(&instruction_error(
+            InstructionError::MissingRequiredSignature
+        )),
+        None
+    );
+    assert_eq!(crate_error(&TransactionError::AccountNotFound), None);
+}
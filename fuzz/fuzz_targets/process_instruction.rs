@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use solana_token_fuzz::{run, FuzzInput};
+
+fuzz_target!(|input: FuzzInput| run(input));
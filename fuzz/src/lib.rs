@@ -0,0 +1,749 @@
+//! Fuzzing harness of `Processor::process_instruction`
+//!
+//! Instructions run against accounts built in memory, cross-program invocations are executed
+//! by syscall stubs enforcing the signer, writable and ownership rules of the runtime.
+//! `Clock::get` returns the clock sysvar among the accounts, or the default clock.
+//! Besides panics and arithmetic overflows, a successful instruction fails the harness if it
+//! debits a token account without the signature of its owner or delegate, or if pool wallets
+//! and pool mint supplies don't change by the same amount. Vaults of pools created by
+//! `InitializePool` are pool wallets, escrow accounts of vestings hold pool tokens and are not.
+//! The token X pending in withdrawal tickets is taken out of the vaults for that sum.
+//! Pools in `Shares` mode are left out of that sum, instead the token X per pool token of
+//! their rate must not decrease. `Skim` changes no supply and must leave the vaults it debits
+//! with at least the supply and the pending token X.
+//! The metadata program is a stub accepting any instruction.
+
+mod runtime;
+
+use arbitrary::Arbitrary;
+use runtime::Snapshot;
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use solana_program::hash::hashv;
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{system_program, sysvar};
+use solana_token::instruction::{ChangeDirection, ContractInstruction};
+use solana_token::processor::Processor;
+use solana_token::state::{Pool, PoolMode, Ticket, Vesting, VIRTUAL_ASSETS, VIRTUAL_SHARES};
+use solana_token::{find_pool_address, find_program_address, find_ticket_address};
+use spl_token::state::{Account, AccountState, Mint};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Number of distinct user keys, small enough for accounts to refer to each other
+const USERS: u8 = 4;
+const MAX_ACCOUNTS: usize = 16;
+
+#[derive(Arbitrary, Debug)]
+pub struct FuzzInput {
+    pub instruction: FuzzInstruction,
+    pub accounts: Vec<FuzzAccount>,
+    /// Accounts of the instruction, as indices into `accounts`
+    pub account_indices: Vec<u8>,
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum FuzzInstruction {
+    ChangeXtoY {
+        amount: u64,
+    },
+    ChangeYtoX {
+        amount: u64,
+    },
+    Quote {
+        amount: u64,
+        y_to_x: bool,
+    },
+    VerifySolvency,
+    ChangeXtoYExactOut {
+        amount_out: u64,
+        maximum_in: u64,
+    },
+    ChangeYtoXExactOut {
+        amount_out: u64,
+        maximum_in: u64,
+    },
+    ChangeXtoYAll,
+    ChangeYtoXAll,
+    WrapWithVesting {
+        amount: u64,
+        cliff_ts: i64,
+        end_ts: i64,
+    },
+    ClaimVested,
+    RevokeVesting,
+    InitializePool {
+        name: String,
+        symbol: String,
+        uri: String,
+        shares: bool,
+        unwrap_delay: u32,
+        subscription_deadline: i64,
+        maturity: i64,
+    },
+    UpdatePoolMetadata {
+        name: String,
+        symbol: String,
+        uri: String,
+    },
+    Skim,
+    RequestUnwrap {
+        amount: u64,
+        nonce: u64,
+    },
+    ClaimUnwrap,
+    Raw(Vec<u8>),
+}
+
+impl FuzzInstruction {
+    fn data(&self) -> Vec<u8> {
+        match *self {
+            Self::ChangeXtoY { amount } => ContractInstruction::ChangeXtoY { amount }.pack(),
+            Self::ChangeYtoX { amount } => ContractInstruction::ChangeYtoX { amount }.pack(),
+            Self::Quote { amount, y_to_x } => ContractInstruction::Quote {
+                amount,
+                direction: if y_to_x {
+                    ChangeDirection::YtoX
+                } else {
+                    ChangeDirection::XtoY
+                },
+            }
+            .pack(),
+            Self::VerifySolvency => ContractInstruction::VerifySolvency.pack(),
+            Self::ChangeXtoYExactOut {
+                amount_out,
+                maximum_in,
+            } => ContractInstruction::ChangeXtoYExactOut {
+                amount_out,
+                maximum_in,
+            }
+            .pack(),
+            Self::ChangeYtoXExactOut {
+                amount_out,
+                maximum_in,
+            } => ContractInstruction::ChangeYtoXExactOut {
+                amount_out,
+                maximum_in,
+            }
+            .pack(),
+            Self::ChangeXtoYAll => ContractInstruction::ChangeXtoYAll.pack(),
+            Self::ChangeYtoXAll => ContractInstruction::ChangeYtoXAll.pack(),
+            Self::WrapWithVesting {
+                amount,
+                cliff_ts,
+                end_ts,
+            } => ContractInstruction::WrapWithVesting {
+                amount,
+                cliff_ts,
+                end_ts,
+            }
+            .pack(),
+            Self::ClaimVested => ContractInstruction::ClaimVested.pack(),
+            Self::RevokeVesting => ContractInstruction::RevokeVesting.pack(),
+            Self::InitializePool {
+                ref name,
+                ref symbol,
+                ref uri,
+                shares,
+                unwrap_delay,
+                subscription_deadline,
+                maturity,
+            } => ContractInstruction::InitializePool {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: uri.clone(),
+                mode: pool_mode(shares),
+                unwrap_delay,
+                subscription_deadline,
+                maturity,
+            }
+            .pack(),
+            Self::UpdatePoolMetadata {
+                ref name,
+                ref symbol,
+                ref uri,
+            } => ContractInstruction::UpdatePoolMetadata {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: uri.clone(),
+            }
+            .pack(),
+            Self::Skim => ContractInstruction::Skim.pack(),
+            Self::RequestUnwrap { amount, nonce } => {
+                ContractInstruction::RequestUnwrap { amount, nonce }.pack()
+            }
+            Self::ClaimUnwrap => ContractInstruction::ClaimUnwrap.pack(),
+            Self::Raw(ref data) => data.clone(),
+        }
+    }
+}
+
+#[derive(Arbitrary, Clone, Copy, Debug)]
+pub enum Key {
+    User(u8),
+    /// Program address derived from a user key, as pool wallets, escrow accounts and pool mint
+    /// authorities
+    Derived(u8),
+    /// Associated token account of a user for a mint
+    Associated {
+        wallet: u8,
+        mint: u8,
+    },
+    /// State of the pool created by `InitializePool` for a user key as pool mint
+    Pool(u8),
+    /// Vault of the pool created for a user key as pool mint
+    Vault(u8),
+    /// Withdrawal ticket of a user in the pool created for a user key as pool mint
+    Ticket {
+        pool_mint: u8,
+        owner: u8,
+        nonce: u8,
+    },
+    Program,
+    SystemProgram,
+    TokenProgram,
+    AssociatedTokenProgram,
+    MetadataProgram,
+    Rent,
+    Clock,
+    Other([u8; 32]),
+}
+
+impl Key {
+    fn pubkey(self) -> Pubkey {
+        match self {
+            Self::User(index) => user(index),
+            Self::Derived(index) => find_program_address(&solana_token::id(), &user(index)).0,
+            Self::Associated { wallet, mint } => {
+                spl_associated_token_account::get_associated_token_address(
+                    &user(wallet),
+                    &user(mint),
+                )
+            }
+            Self::Pool(index) => find_pool_address(&solana_token::id(), &user(index)).0,
+            Self::Vault(index) => {
+                let pool = find_pool_address(&solana_token::id(), &user(index)).0;
+                find_program_address(&solana_token::id(), &pool).0
+            }
+            Self::Ticket {
+                pool_mint,
+                owner,
+                nonce,
+            } => {
+                let pool = find_pool_address(&solana_token::id(), &user(pool_mint)).0;
+                find_ticket_address(&solana_token::id(), &pool, &user(owner), nonce.into()).0
+            }
+            Self::Program => solana_token::id(),
+            Self::SystemProgram => system_program::id(),
+            Self::TokenProgram => spl_token::id(),
+            Self::AssociatedTokenProgram => spl_associated_token_account::id(),
+            Self::MetadataProgram => solana_token::metadata::id(),
+            Self::Rent => sysvar::rent::id(),
+            Self::Clock => sysvar::clock::id(),
+            Self::Other(bytes) => Pubkey::new_from_array(bytes),
+        }
+    }
+}
+
+/// User keys are points on the curve, like the keys of keypairs
+fn user(index: u8) -> Pubkey {
+    static USERS_KEYS: OnceLock<Vec<Pubkey>> = OnceLock::new();
+    USERS_KEYS.get_or_init(|| {
+        (0..USERS)
+            .map(|index| {
+                (0u32..)
+                    .map(|nonce| {
+                        Pubkey::new_from_array(
+                            hashv(&[b"user", &[index], &nonce.to_le_bytes()]).to_bytes(),
+                        )
+                    })
+                    .find(Pubkey::is_on_curve)
+                    .unwrap()
+            })
+            .collect()
+    })[(index % USERS) as usize]
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum Data {
+    Empty,
+    Mint {
+        mint_authority: Option<Key>,
+        supply: u64,
+        is_initialized: bool,
+    },
+    Token {
+        mint: Key,
+        owner: Key,
+        amount: u64,
+        delegate: Option<(Key, u64)>,
+        frozen: bool,
+    },
+    Rent,
+    Clock {
+        unix_timestamp: i64,
+    },
+    Vesting {
+        pool_mint: Key,
+        grantor: Key,
+        beneficiary: Key,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64,
+        total: u64,
+        claimed: u64,
+        escrow_bump: u8,
+    },
+    Pool {
+        admin: Key,
+        pool_mint: Key,
+        token_x_mint: Key,
+        shares: bool,
+        unwrap_delay: u32,
+        pending_unwrap: u64,
+        subscription_deadline: i64,
+        maturity: i64,
+        bump: u8,
+        vault_bump: u8,
+    },
+    Ticket {
+        owner: Key,
+        pool: Key,
+        amount: u64,
+        unlock_ts: i64,
+    },
+    Raw(Vec<u8>),
+}
+
+impl Data {
+    fn bytes(&self) -> Vec<u8> {
+        match *self {
+            Self::Empty => vec![],
+            Self::Mint {
+                mint_authority,
+                supply,
+                is_initialized,
+            } => {
+                let mut data = vec![0; Mint::LEN];
+                Mint {
+                    mint_authority: mint_authority.map(Key::pubkey).into(),
+                    supply,
+                    decimals: 0,
+                    is_initialized,
+                    freeze_authority: COption::None,
+                }
+                .pack_into_slice(&mut data);
+                data
+            }
+            Self::Token {
+                mint,
+                owner,
+                amount,
+                delegate,
+                frozen,
+            } => {
+                let mut data = vec![0; Account::LEN];
+                Account {
+                    mint: mint.pubkey(),
+                    owner: owner.pubkey(),
+                    amount,
+                    delegate: delegate.map(|(key, _)| key.pubkey()).into(),
+                    state: if frozen {
+                        AccountState::Frozen
+                    } else {
+                        AccountState::Initialized
+                    },
+                    is_native: COption::None,
+                    delegated_amount: delegate.map_or(0, |(_, amount)| amount),
+                    close_authority: COption::None,
+                }
+                .pack_into_slice(&mut data);
+                data
+            }
+            Self::Rent => bincode::serialize(&Rent::default()).unwrap(),
+            Self::Clock { unix_timestamp } => bincode::serialize(&Clock {
+                unix_timestamp,
+                ..Clock::default()
+            })
+            .unwrap(),
+            Self::Vesting {
+                pool_mint,
+                grantor,
+                beneficiary,
+                start_ts,
+                cliff_ts,
+                end_ts,
+                total,
+                claimed,
+                escrow_bump,
+            } => {
+                let mut data = vec![0; Vesting::LEN];
+                Vesting {
+                    is_initialized: true,
+                    pool_mint: pool_mint.pubkey(),
+                    grantor: grantor.pubkey(),
+                    beneficiary: beneficiary.pubkey(),
+                    start_ts,
+                    cliff_ts,
+                    end_ts,
+                    total,
+                    claimed,
+                    escrow_bump,
+                }
+                .pack_into_slice(&mut data);
+                data
+            }
+            Self::Pool {
+                admin,
+                pool_mint,
+                token_x_mint,
+                shares,
+                unwrap_delay,
+                pending_unwrap,
+                subscription_deadline,
+                maturity,
+                bump,
+                vault_bump,
+            } => {
+                let mut data = vec![0; Pool::LEN];
+                Pool {
+                    is_initialized: true,
+                    admin: admin.pubkey(),
+                    pool_mint: pool_mint.pubkey(),
+                    token_x_mint: token_x_mint.pubkey(),
+                    mode: pool_mode(shares),
+                    unwrap_delay,
+                    pending_unwrap,
+                    subscription_deadline,
+                    maturity,
+                    bump,
+                    vault_bump,
+                }
+                .pack_into_slice(&mut data);
+                data
+            }
+            Self::Ticket {
+                owner,
+                pool,
+                amount,
+                unlock_ts,
+            } => {
+                let mut data = vec![0; Ticket::LEN];
+                Ticket {
+                    is_initialized: true,
+                    owner: owner.pubkey(),
+                    pool: pool.pubkey(),
+                    amount,
+                    unlock_ts,
+                }
+                .pack_into_slice(&mut data);
+                data
+            }
+            Self::Raw(ref data) => data.clone(),
+        }
+    }
+}
+
+fn pool_mode(shares: bool) -> PoolMode {
+    if shares {
+        PoolMode::Shares
+    } else {
+        PoolMode::Fixed
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct FuzzAccount {
+    pub key: Key,
+    pub owner: Key,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub lamports: u64,
+    pub data: Data,
+}
+
+/// Memory of an account, laid out as the runtime serializes accounts for programs: the data
+/// length, the data and room for the data to grow
+struct AccountMemory {
+    key: Pubkey,
+    owner: Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+    executable: bool,
+    lamports: u64,
+    buffer: Vec<u64>,
+    data_len: usize,
+}
+
+impl AccountMemory {
+    fn new(account: &FuzzAccount) -> Self {
+        let key = account.key.pubkey();
+        let data = account.data.bytes();
+        let mut buffer = vec![0u64; 1 + (data.len() + MAX_PERMITTED_DATA_INCREASE).div_ceil(8)];
+        buffer[0] = data.len() as u64;
+        bytes_mut(&mut buffer)[8..8 + data.len()].copy_from_slice(&data);
+        Self {
+            key,
+            owner: account.owner.pubkey(),
+            // Program addresses and programs can't sign a transaction
+            is_signer: account.is_signer && key.is_on_curve(),
+            is_writable: account.is_writable,
+            executable: matches!(
+                account.key,
+                Key::Program
+                    | Key::SystemProgram
+                    | Key::TokenProgram
+                    | Key::AssociatedTokenProgram
+                    | Key::MetadataProgram
+            ),
+            lamports: account.lamports,
+            buffer,
+            data_len: data.len(),
+        }
+    }
+
+    fn info(&mut self) -> AccountInfo<'_> {
+        let data_len = self.data_len;
+        AccountInfo::new(
+            &self.key,
+            self.is_signer,
+            self.is_writable,
+            &mut self.lamports,
+            &mut bytes_mut(&mut self.buffer)[8..8 + data_len],
+            &self.owner,
+            self.executable,
+            0,
+        )
+    }
+}
+
+fn bytes_mut(buffer: &mut [u64]) -> &mut [u8] {
+    // SAFETY: any bytes are a valid u8, and the length covers the same memory
+    unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, buffer.len() * 8) }
+}
+
+pub fn run(input: FuzzInput) {
+    let mut memories: Vec<AccountMemory> = vec![];
+    for account in input.accounts.iter().take(MAX_ACCOUNTS) {
+        let memory = AccountMemory::new(account);
+        // The runtime passes one account for each key
+        if memories.iter().all(|other| other.key != memory.key) {
+            memories.push(memory);
+        }
+    }
+    if memories.is_empty() {
+        return;
+    }
+    let infos: Vec<AccountInfo> = memories.iter_mut().map(AccountMemory::info).collect();
+    let accounts: Vec<AccountInfo> = input
+        .account_indices
+        .iter()
+        .take(MAX_ACCOUNTS)
+        .map(|index| infos[*index as usize % infos.len()].clone())
+        .collect();
+
+    let before: Vec<Snapshot> = infos.iter().map(Snapshot::new).collect();
+    let program_id = solana_token::id();
+    let data = input.instruction.data();
+    let result = runtime::execute(&program_id, &infos, || {
+        Processor::process_instruction(&program_id, &accounts, &data)
+    });
+    if result.is_ok() {
+        let skim = matches!(
+            ContractInstruction::unpack(&data),
+            Ok(ContractInstruction::Skim)
+        );
+        check_token_moves(&infos, &before, skim);
+    }
+}
+
+/// Token changes of a successful instruction
+fn check_token_moves(infos: &[AccountInfo], before: &[Snapshot], skim: bool) {
+    let signers: Vec<Pubkey> = infos
+        .iter()
+        .filter(|info| info.is_signer)
+        .map(|info| *info.key)
+        .collect();
+    // Escrow accounts are derived from vesting accounts, told apart from pool states by length
+    let escrows: Vec<Pubkey> = infos
+        .iter()
+        .filter(|info| *info.owner == solana_token::id() && info.data_len() == Vesting::LEN)
+        .map(|info| find_program_address(&solana_token::id(), info.key).0)
+        .collect();
+    let shares_pools = shares_pools(infos);
+    let in_shares_pool = |key: &Pubkey| {
+        shares_pools
+            .iter()
+            .any(|(_, pool_mint, vault)| key == pool_mint || key == vault)
+    };
+    let mut pool_wallets_change = 0i128;
+    for (info, before) in infos.iter().zip(before) {
+        let pending = |data: &[u8]| match pool_state(info.key, info.owner, data) {
+            Some(pool) if pool.mode == PoolMode::Fixed => pool.pending_unwrap as i128,
+            _ => 0,
+        };
+        pool_wallets_change -= pending(&info.data.borrow()) - pending(&before.data);
+    }
+    let mut supplies_change = HashMap::<Pubkey, i128>::new();
+    for (info, before) in infos.iter().zip(before) {
+        let after = Snapshot::new(info);
+        if let Some(account) = token_account(&after) {
+            let before_amount = token_account(before).map_or(0, |before| before.amount);
+            let change = account.amount as i128 - before_amount as i128;
+            // Pool wallets are owned by their own program address
+            if account.owner == *info.key && !escrows.contains(info.key) {
+                if !in_shares_pool(info.key) {
+                    pool_wallets_change += change;
+                }
+            } else if change < 0 && account.owner != *info.key {
+                let delegate_signed = matches!(account.delegate, COption::Some(delegate) if signers.contains(&delegate));
+                assert!(
+                    signers.contains(&account.owner) || delegate_signed,
+                    "{} was debited {} without a signature of its owner",
+                    info.key,
+                    -change
+                );
+            }
+        }
+        if let (Some(before), Some(after)) = (mint(before), mint(&after)) {
+            if in_shares_pool(info.key) {
+                continue;
+            }
+            *supplies_change.entry(*info.key).or_default() +=
+                after.supply as i128 - before.supply as i128;
+        }
+    }
+    let supply_change: i128 = supplies_change.values().sum();
+    if skim {
+        assert_eq!(supply_change, 0, "skim changed a pool mint supply");
+        check_skimmed_vaults(infos, before);
+        return;
+    }
+    assert_eq!(
+        pool_wallets_change, supply_change,
+        "pool wallets and pool mint supply changed by different amounts"
+    );
+    for (pool, pool_mint, vault) in &shares_pools {
+        check_share_value(infos, before, pool, pool_mint, vault);
+    }
+}
+
+/// State of the pool created by `InitializePool` in an account of the program
+fn pool_state(key: &Pubkey, owner: &Pubkey, data: &[u8]) -> Option<Pool> {
+    if *owner != solana_token::id() || data.len() != Pool::LEN {
+        return None;
+    }
+    let pool = Pool::unpack(data).ok()?;
+    (find_pool_address(&solana_token::id(), &pool.pool_mint).0 == *key).then_some(pool)
+}
+
+/// Pool states, pool mints and vaults of the pools in `Shares` mode among the accounts
+fn shares_pools(infos: &[AccountInfo]) -> Vec<(Pubkey, Pubkey, Pubkey)> {
+    infos
+        .iter()
+        .filter_map(|info| {
+            let pool = pool_state(info.key, info.owner, &info.data.borrow())?;
+            (pool.mode == PoolMode::Shares).then(|| {
+                let vault = find_program_address(&solana_token::id(), info.key).0;
+                (*info.key, pool.pool_mint, vault)
+            })
+        })
+        .collect()
+}
+
+/// Token X per pool token at the rate of a pool in `Shares` mode doesn't decrease. The
+/// token X pending in withdrawal tickets isn't part of the rate
+fn check_share_value(
+    infos: &[AccountInfo],
+    before: &[Snapshot],
+    pool: &Pubkey,
+    pool_mint: &Pubkey,
+    vault: &Pubkey,
+) {
+    let find = |key: &Pubkey| {
+        let index = infos.iter().position(|info| info.key == key)?;
+        Some((&before[index], Snapshot::new(&infos[index])))
+    };
+    let (
+        Some((pool_before, pool_after)),
+        Some((mint_before, mint_after)),
+        Some((vault_before, vault_after)),
+    ) = (find(pool), find(pool_mint), find(vault))
+    else {
+        return;
+    };
+    let supply = |snapshot| mint(snapshot).map_or(0, |mint| mint.supply) as u128;
+    let assets = |vault: &Snapshot, pool: &Snapshot| {
+        let amount = token_account(vault).map_or(0, |account| account.amount);
+        let pending = Pool::unpack(&pool.data).map_or(0, |pool| pool.pending_unwrap);
+        amount.saturating_sub(pending) as u128
+    };
+    let (supply_before, supply_after) = (supply(mint_before), supply(&mint_after));
+    let (assets_before, assets_after) = (
+        assets(vault_before, pool_before),
+        assets(&vault_after, &pool_after),
+    );
+    let virtual_shares = VIRTUAL_SHARES as u128;
+    let virtual_assets = VIRTUAL_ASSETS as u128;
+    assert!(
+        (assets_after + virtual_assets) * (supply_before + virtual_shares)
+            >= (assets_before + virtual_assets) * (supply_after + virtual_shares),
+        "share value of {} dropped: {} for {} became {} for {}",
+        pool_mint,
+        assets_before,
+        supply_before,
+        assets_after,
+        supply_after
+    );
+}
+
+/// Vaults debited by `Skim` still hold the supply of their pool mint
+fn check_skimmed_vaults(infos: &[AccountInfo], before: &[Snapshot]) {
+    for (info, before) in infos.iter().zip(before) {
+        let (before, after) = match (token_account(before), token_account(&Snapshot::new(info))) {
+            (Some(before), Some(after)) if after.amount < before.amount => (before, after),
+            _ => continue,
+        };
+        let pool = infos
+            .iter()
+            .filter(|pool_info| *pool_info.owner == solana_token::id())
+            .filter(|pool_info| {
+                find_program_address(&solana_token::id(), pool_info.key).0 == *info.key
+            })
+            .find_map(|pool_info| Pool::unpack(&pool_info.data.borrow()).ok())
+            .unwrap_or_else(|| panic!("skim debited {}, which is not a vault", info.key));
+        let supply = infos
+            .iter()
+            .find(|mint_info| *mint_info.key == pool.pool_mint)
+            .and_then(|mint_info| mint(&Snapshot::new(mint_info)))
+            .expect("skim without the pool mint")
+            .supply;
+        assert!(
+            after.amount as u128 >= supply as u128 + pool.pending_unwrap as u128,
+            "skim left {} of {} in the vault {} for a supply of {} and {} pending",
+            after.amount,
+            before.amount,
+            info.key,
+            supply,
+            pool.pending_unwrap
+        );
+    }
+}
+
+fn token_account(snapshot: &Snapshot) -> Option<Account> {
+    if snapshot.owner != spl_token::id() {
+        return None;
+    }
+    Account::unpack(&snapshot.data).ok()
+}
+
+fn mint(snapshot: &Snapshot) -> Option<Mint> {
+    if snapshot.owner != spl_token::id() {
+        return None;
+    }
+    Mint::unpack(&snapshot.data).ok()
+}
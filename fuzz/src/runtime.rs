@@ -0,0 +1,304 @@
+//! Syscall stubs running cross-program invocations in memory, with the checks of the runtime
+
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS};
+use solana_program::instruction::Instruction;
+use solana_program::program_error::ProgramError;
+use solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction::{SystemError, SystemInstruction};
+use solana_program::system_program;
+use solana_program::sysvar::Sysvar;
+use std::collections::HashMap;
+use std::sync::{Mutex, Once};
+
+/// State of an account when it was last verified
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+}
+
+impl Snapshot {
+    pub fn new(info: &AccountInfo) -> Self {
+        Self {
+            lamports: info.lamports(),
+            data: info.data.borrow().to_vec(),
+            owner: *info.owner,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Runtime {
+    /// Programs being executed, the caller of the next invocation last
+    stack: Vec<Pubkey>,
+    snapshots: HashMap<Pubkey, Snapshot>,
+    /// An invocation failed, which fails the whole instruction
+    failed: bool,
+    /// Clock sysvar among the accounts, read by `Clock::get`
+    clock: Clock,
+}
+
+static RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
+
+fn with_runtime<T>(f: impl FnOnce(&mut Runtime) -> T) -> T {
+    f(RUNTIME
+        .lock()
+        .unwrap()
+        .as_mut()
+        .expect("no instruction is executed"))
+}
+
+/// Runs `program_id` as the top-level program, fails if any of its invocations failed
+pub fn execute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    process: impl FnOnce() -> ProgramResult,
+) -> ProgramResult {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(Stubs));
+    });
+
+    *RUNTIME.lock().unwrap() = Some(Runtime {
+        stack: vec![*program_id],
+        clock: accounts
+            .iter()
+            .find_map(|info| Clock::from_account_info(info).ok())
+            .unwrap_or_default(),
+        ..Runtime::default()
+    });
+    for info in accounts {
+        with_runtime(|runtime| runtime.snapshots.insert(*info.key, Snapshot::new(info)));
+    }
+    let result = process().and_then(|()| {
+        if with_runtime(|runtime| runtime.failed) {
+            return Err(ProgramError::Custom(u32::MAX));
+        }
+        verify(program_id, accounts);
+        Ok(())
+    });
+    *RUNTIME.lock().unwrap() = None;
+    result
+}
+
+/// Panics if `program_id` changed accounts against the rules of the runtime since their last
+/// verification, as the runtime would abort the transaction
+fn verify(program_id: &Pubkey, accounts: &[AccountInfo]) {
+    let mut before_lamports = 0u128;
+    let mut after_lamports = 0u128;
+    let mut verified = vec![];
+    for info in accounts {
+        if verified.contains(info.key) {
+            continue;
+        }
+        verified.push(*info.key);
+        let after = Snapshot::new(info);
+        let before =
+            match with_runtime(|runtime| runtime.snapshots.insert(*info.key, after.clone())) {
+                Some(before) => before,
+                None => continue,
+            };
+        before_lamports += before.lamports as u128;
+        after_lamports += after.lamports as u128;
+        if before == after {
+            continue;
+        }
+        assert!(
+            info.is_writable,
+            "{} modified the read-only account {}",
+            program_id, info.key
+        );
+        let owned = before.owner == *program_id;
+        assert!(
+            owned || before.data == after.data,
+            "{} modified the data of {} owned by {}",
+            program_id,
+            info.key,
+            before.owner
+        );
+        assert!(
+            owned || before.owner == after.owner,
+            "{} changed the owner of {} owned by {}",
+            program_id,
+            info.key,
+            before.owner
+        );
+        assert!(
+            owned || before.lamports <= after.lamports,
+            "{} debited {} owned by {}",
+            program_id,
+            info.key,
+            before.owner
+        );
+    }
+    assert_eq!(
+        before_lamports, after_lamports,
+        "{} did not balance lamports",
+        program_id
+    );
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_compute_units(&self) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = with_runtime(|runtime| runtime.clock.clone());
+        // SAFETY: `Clock::get` passes the address of a `Clock`
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let caller = with_runtime(|runtime| *runtime.stack.last().unwrap());
+        verify(&caller, account_infos);
+
+        let result = invoke(&caller, instruction, account_infos, signers_seeds);
+        if result.is_err() {
+            with_runtime(|runtime| runtime.failed = true);
+        }
+        result
+    }
+}
+
+fn invoke(
+    caller: &Pubkey,
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let mut accounts = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let program_signed = signers_seeds
+            .iter()
+            .any(|seeds| Pubkey::create_program_address(seeds, caller) == Ok(meta.pubkey));
+        if meta.is_signer && !info.is_signer && !program_signed {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if meta.is_writable && !info.is_writable {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut info = info.clone();
+        info.is_signer = meta.is_signer;
+        info.is_writable = meta.is_writable;
+        accounts.push(info);
+    }
+
+    let program_id = instruction.program_id;
+    with_runtime(|runtime| runtime.stack.push(program_id));
+    let result = if program_id == spl_token::id() {
+        spl_token::processor::Processor::process(&program_id, &accounts, &instruction.data)
+    } else if program_id == spl_associated_token_account::id() {
+        spl_associated_token_account::processor::process_instruction(
+            &program_id,
+            &accounts,
+            &instruction.data,
+        )
+    } else if program_id == system_program::id() {
+        process_system_instruction(&accounts, &instruction.data)
+    } else if program_id == solana_token::metadata::id() {
+        // Metadata accounts hold no tokens, only the signers of the wrapper are checked
+        Ok(())
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    };
+    with_runtime(|runtime| runtime.stack.pop());
+    result?;
+
+    verify(&program_id, &accounts);
+    Ok(())
+}
+
+/// The system program instructions used by the wrapper and the associated token program
+fn process_system_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let account = |index: usize| {
+        accounts
+            .get(index)
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    };
+    match bincode::deserialize(data).map_err(|_| ProgramError::InvalidInstructionData)? {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            if account(1)?.lamports() > 0 {
+                return Err(ProgramError::Custom(
+                    SystemError::AccountAlreadyInUse as u32,
+                ));
+            }
+            allocate(account(1)?, space)?;
+            assign(account(1)?, &owner)?;
+            transfer(account(0)?, account(1)?, lamports)
+        }
+        SystemInstruction::Assign { owner } => assign(account(0)?, &owner),
+        SystemInstruction::Transfer { lamports } => transfer(account(0)?, account(1)?, lamports),
+        SystemInstruction::Allocate { space } => allocate(account(0)?, space),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn allocate(info: &AccountInfo, space: u64) -> ProgramResult {
+    if !info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !info.data_is_empty() || info.owner != &system_program::id() {
+        return Err(ProgramError::Custom(
+            SystemError::AccountAlreadyInUse as u32,
+        ));
+    }
+    if space as usize > MAX_PERMITTED_DATA_INCREASE {
+        return Err(ProgramError::InvalidArgument);
+    }
+    info.realloc(space as usize, true)
+}
+
+fn assign(info: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if info.owner == owner {
+        return Ok(());
+    }
+    if !info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    info.assign(owner);
+    Ok(())
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !from.data_is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if from.lamports() < lamports {
+        return Err(ProgramError::Custom(
+            SystemError::ResultWithNegativeLamports as u32,
+        ));
+    }
+    **from.try_borrow_mut_lamports()? -= lamports;
+    let to_lamports = to
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::InvalidArgument)?;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+    Ok(())
+}
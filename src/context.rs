@@ -0,0 +1,1109 @@
+//! Accounts of each instruction, parsed and validated before processing
+
+use crate::error::CrateError;
+use crate::instruction::{
+    AccountSpec, CHANGE_X_TO_Y_ACCOUNTS, CHANGE_Y_TO_X_ACCOUNTS, CLAIM_UNWRAP_ACCOUNTS,
+    INITIALIZE_POOL_ACCOUNTS, QUOTE_ACCOUNTS, REQUEST_UNWRAP_ACCOUNTS, SKIM_ACCOUNTS,
+    UPDATE_POOL_METADATA_ACCOUNTS, VERIFY_SOLVENCY_ACCOUNTS, WRAP_WITH_VESTING_ACCOUNTS,
+};
+use crate::metadata;
+use crate::state::{Pool, Vesting};
+use crate::{find_pool_address, find_program_address, find_ticket_address, POOL_SEED};
+use solana_program::account_info::{next_account_info, AccountInfo};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::{system_program, sysvar};
+
+/// Next account, checked against the signer and writable flags of its spec
+fn next_account<'a, 'b>(
+    iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    spec: &AccountSpec,
+) -> Result<&'a AccountInfo<'b>, ProgramError> {
+    let info = next_account_info(iter)?;
+    check_flags(info, spec)?;
+    Ok(info)
+}
+
+/// Next account if any is left, for a trailing optional spec
+fn next_optional_account<'a, 'b>(
+    iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    spec: &AccountSpec,
+) -> Result<Option<&'a AccountInfo<'b>>, ProgramError> {
+    match iter.len() {
+        0 => Ok(None),
+        _ => next_account(iter, spec).map(Some),
+    }
+}
+
+fn check_flags(info: &AccountInfo, spec: &AccountSpec) -> ProgramResult {
+    if spec.signer && !info.is_signer {
+        msg!("Error: {} must sign", spec.name);
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if spec.writable && !info.is_writable {
+        msg!("Error: {} must be writable", spec.name);
+        return Err(CrateError::AccountNotWritable.into());
+    }
+    Ok(())
+}
+
+fn check_address(
+    info: &AccountInfo,
+    spec: &AccountSpec,
+    expected: &Pubkey,
+    error: CrateError,
+) -> ProgramResult {
+    if info.key != expected {
+        msg!("Error: {} must be {}", spec.name, expected);
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+fn check_owner(
+    info: &AccountInfo,
+    spec: &AccountSpec,
+    owners: &[Pubkey],
+    error: CrateError,
+) -> ProgramResult {
+    if !owners.contains(info.owner) {
+        msg!("Error: {} is owned by {}", spec.name, info.owner);
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Fails if an account is passed for two roles
+fn check_unique(accounts: &[(&AccountInfo, &AccountSpec)]) -> ProgramResult {
+    for (i, (info, spec)) in accounts.iter().enumerate() {
+        if let Some((_, other)) = accounts[i + 1..]
+            .iter()
+            .find(|(other_info, _)| other_info.key == info.key)
+        {
+            msg!("Error: {} is also passed as {}", spec.name, other.name);
+            return Err(CrateError::DuplicateAccount.into());
+        }
+    }
+    Ok(())
+}
+
+/// Unpacks a pool state created by `InitializePool` and checks its address with
+/// the seed bump it stores, derived from `pool_mint` if given, else from the pool
+/// mint it stores
+fn unpack_pool(
+    program_id: &Pubkey,
+    pool: &AccountInfo,
+    spec: &AccountSpec,
+    pool_mint: Option<&Pubkey>,
+) -> Result<Pool, ProgramError> {
+    // Without a state to read the bump from, it can't be at the address of a pool
+    check_owner(pool, spec, &[*program_id], CrateError::InvalidPoolAuthority)?;
+    let pool_state = Pool::unpack(&pool.data.borrow()).map_err(|_| CrateError::ExpectedPool)?;
+    let pool_mint = pool_mint.unwrap_or(&pool_state.pool_mint);
+    let expected_pool = Pubkey::create_program_address(
+        &[POOL_SEED, &pool_mint.to_bytes(), &[pool_state.bump]],
+        program_id,
+    )
+    .map_err(|_| CrateError::InvalidPoolAuthority)?;
+    check_address(pool, spec, &expected_pool, CrateError::InvalidPoolAuthority)?;
+    Ok(pool_state)
+}
+
+/// Checks the vault of a pool state with the seed bump the state stores, returned
+fn check_vault(
+    program_id: &Pubkey,
+    pool: &AccountInfo,
+    pool_state: &Pool,
+    vault: &AccountInfo,
+    spec: &AccountSpec,
+) -> Result<u8, ProgramError> {
+    let expected_vault = Pubkey::create_program_address(
+        &[&pool.key.to_bytes(), &[pool_state.vault_bump]],
+        program_id,
+    )
+    .map_err(|_| CrateError::InvalidPoolWalletAddress)?;
+    check_address(
+        vault,
+        spec,
+        &expected_vault,
+        CrateError::InvalidPoolWalletAddress,
+    )?;
+    Ok(pool_state.vault_bump)
+}
+
+/// Checks the pool wallet, which is the vault of the pool state if any, else the
+/// program address derived from the source account. Returns its seed bump
+fn check_pool_wallet(
+    program_id: &Pubkey,
+    pool: Option<&AccountInfo>,
+    pool_state: Option<&Pool>,
+    user_wallet_x: &AccountInfo,
+    pool_wallet_x: &AccountInfo,
+    spec: &AccountSpec,
+) -> Result<u8, ProgramError> {
+    if let (Some(pool), Some(pool_state)) = (pool, pool_state) {
+        return check_vault(program_id, pool, pool_state, pool_wallet_x, spec);
+    }
+    let (expected_pool_wallet_x, bump) = find_program_address(program_id, user_wallet_x.key);
+    check_address(
+        pool_wallet_x,
+        spec,
+        &expected_pool_wallet_x,
+        CrateError::InvalidPoolWalletAddress,
+    )?;
+    Ok(bump)
+}
+
+/// Checks the pool authority of `pool_mint`, which is the pool state for the pools
+/// created by `InitializePool`, the only pool authorities owned by the program.
+/// Returns the pool state if so, unpacked, and the seed bump of the pool authority
+#[allow(clippy::type_complexity)]
+fn check_pool_authority<'a, 'b>(
+    program_id: &Pubkey,
+    pool_mint: &AccountInfo,
+    pool_mint_authority: &'a AccountInfo<'b>,
+    spec: &AccountSpec,
+) -> Result<(Option<&'a AccountInfo<'b>>, Option<Pool>, u8), ProgramError> {
+    if pool_mint_authority.owner == program_id {
+        let pool_state = unpack_pool(program_id, pool_mint_authority, spec, Some(pool_mint.key))?;
+        let bump = pool_state.bump;
+        return Ok((Some(pool_mint_authority), Some(pool_state), bump));
+    }
+    // Pools created off-chain have no state to store the bump
+    let (expected_pool_mint_authority, bump) = find_program_address(program_id, pool_mint.key);
+    check_address(
+        pool_mint_authority,
+        spec,
+        &expected_pool_mint_authority,
+        CrateError::InvalidPoolAuthority,
+    )?;
+    Ok((None, None, bump))
+}
+
+/// Accounts of `ChangeXtoY` before the optional associated token account program
+const CHANGE_X_TO_Y_REQUIRED_ACCOUNTS: usize = CHANGE_X_TO_Y_ACCOUNTS.len() - 1;
+
+/// Accounts of `ChangeXtoY`
+pub struct ChangeXtoYAccounts<'a, 'b> {
+    pub user_wallets_authority: &'a AccountInfo<'b>,
+    pub user_wallet_x: &'a AccountInfo<'b>,
+    pub token_x_mint: &'a AccountInfo<'b>,
+    pub user_wallet_y: &'a AccountInfo<'b>,
+    pub pool_mint: &'a AccountInfo<'b>,
+    pub pool_mint_authority: &'a AccountInfo<'b>,
+    pub pool_wallet_x: &'a AccountInfo<'b>,
+    pub rent: &'a AccountInfo<'b>,
+    pub system_program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub associated_token_program: Option<&'a AccountInfo<'b>>,
+    /// The pool authority, for pools created by `InitializePool`
+    pub pool: Option<&'a AccountInfo<'b>>,
+    /// State of `pool`
+    pub pool_state: Option<Pool>,
+    pub pool_mint_authority_bump: u8,
+    pub pool_wallet_x_bump: u8,
+}
+
+impl<'a, 'b> ChangeXtoYAccounts<'a, 'b> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [authority_spec, wallet_x_spec, mint_x_spec, wallet_y_spec, pool_mint_spec, pool_authority_spec, pool_wallet_spec, rent_spec, system_spec, token_spec, associated_spec] =
+            &CHANGE_X_TO_Y_ACCOUNTS;
+        let iter = &mut accounts.iter();
+        let user_wallets_authority = next_account(iter, authority_spec)?;
+        let user_wallet_x = next_account(iter, wallet_x_spec)?;
+        let token_x_mint = next_account(iter, mint_x_spec)?;
+        let user_wallet_y = next_account(iter, wallet_y_spec)?;
+        let pool_mint = next_account(iter, pool_mint_spec)?;
+        let pool_mint_authority = next_account(iter, pool_authority_spec)?;
+        let pool_wallet_x = next_account(iter, pool_wallet_spec)?;
+        let rent = next_account(iter, rent_spec)?;
+        let system_program = next_account(iter, system_spec)?;
+        let token_program = next_account(iter, token_spec)?;
+        let associated_token_program = next_optional_account(iter, associated_spec)?;
+
+        check_owner(
+            user_wallet_x,
+            wallet_x_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedAccount,
+        )?;
+        check_owner(
+            token_x_mint,
+            mint_x_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedMint,
+        )?;
+        // A missing destination is created as the associated token account
+        check_owner(
+            user_wallet_y,
+            wallet_y_spec,
+            &[spl_token::id(), system_program::id()],
+            CrateError::ExpectedAccount,
+        )?;
+        check_owner(
+            pool_mint,
+            pool_mint_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedMint,
+        )?;
+
+        let (pool, pool_state, pool_mint_authority_bump) = check_pool_authority(
+            program_id,
+            pool_mint,
+            pool_mint_authority,
+            pool_authority_spec,
+        )?;
+        let pool_wallet_x_bump = check_pool_wallet(
+            program_id,
+            pool,
+            pool_state.as_ref(),
+            user_wallet_x,
+            pool_wallet_x,
+            pool_wallet_spec,
+        )?;
+        check_owner(
+            pool_wallet_x,
+            pool_wallet_spec,
+            &[spl_token::id(), system_program::id()],
+            CrateError::PoolWalletInUse,
+        )?;
+
+        check_address(
+            rent,
+            rent_spec,
+            &sysvar::rent::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        check_address(
+            system_program,
+            system_spec,
+            &system_program::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        check_address(
+            token_program,
+            token_spec,
+            &spl_token::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        if let Some(associated_token_program) = associated_token_program {
+            check_address(
+                associated_token_program,
+                associated_spec,
+                &spl_associated_token_account::id(),
+                CrateError::IncorrectProgramAccount,
+            )?;
+        }
+
+        check_unique(&[
+            (user_wallets_authority, authority_spec),
+            (user_wallet_x, wallet_x_spec),
+            (token_x_mint, mint_x_spec),
+            (user_wallet_y, wallet_y_spec),
+            (pool_mint, pool_mint_spec),
+            (pool_wallet_x, pool_wallet_spec),
+        ])?;
+
+        Ok(Self {
+            user_wallets_authority,
+            user_wallet_x,
+            token_x_mint,
+            user_wallet_y,
+            pool_mint,
+            pool_mint_authority,
+            pool_wallet_x,
+            rent,
+            system_program,
+            token_program,
+            associated_token_program,
+            pool,
+            pool_state,
+            pool_mint_authority_bump,
+            pool_wallet_x_bump,
+        })
+    }
+
+    /// Key seeding the pool wallet: the pool state for pools created by
+    /// `InitializePool`, else the source account
+    pub fn pool_wallet_x_seed(&self) -> &Pubkey {
+        self.pool.map_or(self.user_wallet_x.key, |pool| pool.key)
+    }
+
+    /// Parses the accounts of `ChangeXtoY` without the optional one, for the
+    /// instructions taking more accounts after them
+    pub fn parse_required(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let required = accounts
+            .get(..CHANGE_X_TO_Y_REQUIRED_ACCOUNTS)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        Self::parse(program_id, required)
+    }
+
+    /// Checks the authority can pay for an account created by the instruction
+    pub fn check_payer(&self) -> ProgramResult {
+        let [authority_spec, ..] = &CHANGE_X_TO_Y_ACCOUNTS;
+        check_flags(
+            self.user_wallets_authority,
+            &AccountSpec {
+                writable: true,
+                ..*authority_spec
+            },
+        )
+    }
+
+    /// Associated token account program, required to create the destination
+    pub fn associated_token_program(&self) -> Result<&'a AccountInfo<'b>, ProgramError> {
+        self.associated_token_program.ok_or_else(|| {
+            let [.., associated_spec] = &CHANGE_X_TO_Y_ACCOUNTS;
+            msg!(
+                "Error: {} is needed to create the destination",
+                associated_spec.name
+            );
+            ProgramError::NotEnoughAccountKeys
+        })
+    }
+}
+
+/// Accounts of `ChangeYtoX`
+pub struct ChangeYtoXAccounts<'a, 'b> {
+    pub user_wallets_authority: &'a AccountInfo<'b>,
+    pub user_wallet_x: &'a AccountInfo<'b>,
+    pub user_wallet_y: &'a AccountInfo<'b>,
+    pub pool_mint: &'a AccountInfo<'b>,
+    pub pool_wallet_x: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    /// Pool state, for pools created by `InitializePool`
+    pub pool: Option<&'a AccountInfo<'b>>,
+    /// State of `pool`
+    pub pool_state: Option<Pool>,
+    pub pool_wallet_x_bump: u8,
+}
+
+impl<'a, 'b> ChangeYtoXAccounts<'a, 'b> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [authority_spec, wallet_x_spec, wallet_y_spec, pool_mint_spec, pool_wallet_spec, token_spec, pool_spec] =
+            &CHANGE_Y_TO_X_ACCOUNTS;
+        let iter = &mut accounts.iter();
+        let user_wallets_authority = next_account(iter, authority_spec)?;
+        let user_wallet_x = next_account(iter, wallet_x_spec)?;
+        let user_wallet_y = next_account(iter, wallet_y_spec)?;
+        let pool_mint = next_account(iter, pool_mint_spec)?;
+        let pool_wallet_x = next_account(iter, pool_wallet_spec)?;
+        let token_program = next_account(iter, token_spec)?;
+        let pool = next_optional_account(iter, pool_spec)?;
+
+        check_owner(
+            user_wallet_x,
+            wallet_x_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedAccount,
+        )?;
+        check_owner(
+            user_wallet_y,
+            wallet_y_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedAccount,
+        )?;
+        check_owner(
+            pool_mint,
+            pool_mint_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedMint,
+        )?;
+        let pool_state = pool
+            .map(|pool| unpack_pool(program_id, pool, pool_spec, Some(pool_mint.key)))
+            .transpose()?;
+        let pool_wallet_x_bump = check_pool_wallet(
+            program_id,
+            pool,
+            pool_state.as_ref(),
+            user_wallet_x,
+            pool_wallet_x,
+            pool_wallet_spec,
+        )?;
+        check_owner(
+            pool_wallet_x,
+            pool_wallet_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedAccount,
+        )?;
+        check_address(
+            token_program,
+            token_spec,
+            &spl_token::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+
+        check_unique(&[
+            (user_wallets_authority, authority_spec),
+            (user_wallet_x, wallet_x_spec),
+            (user_wallet_y, wallet_y_spec),
+            (pool_mint, pool_mint_spec),
+            (pool_wallet_x, pool_wallet_spec),
+        ])?;
+
+        Ok(Self {
+            user_wallets_authority,
+            user_wallet_x,
+            user_wallet_y,
+            pool_mint,
+            pool_wallet_x,
+            token_program,
+            pool,
+            pool_state,
+            pool_wallet_x_bump,
+        })
+    }
+}
+
+/// Accounts of `Quote`
+pub struct QuoteAccounts<'a, 'b> {
+    pub user_wallet_x: &'a AccountInfo<'b>,
+    pub pool_mint: &'a AccountInfo<'b>,
+    pub pool_mint_authority: &'a AccountInfo<'b>,
+    pub pool_wallet_x: &'a AccountInfo<'b>,
+    /// The pool authority, for pools created by `InitializePool`
+    pub pool: Option<&'a AccountInfo<'b>>,
+    /// State of `pool`
+    pub pool_state: Option<Pool>,
+}
+
+impl<'a, 'b> QuoteAccounts<'a, 'b> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [wallet_x_spec, pool_mint_spec, pool_authority_spec, pool_wallet_spec] =
+            &QUOTE_ACCOUNTS;
+        let iter = &mut accounts.iter();
+        let user_wallet_x = next_account(iter, wallet_x_spec)?;
+        let pool_mint = next_account(iter, pool_mint_spec)?;
+        let pool_mint_authority = next_account(iter, pool_authority_spec)?;
+        let pool_wallet_x = next_account(iter, pool_wallet_spec)?;
+
+        check_owner(
+            user_wallet_x,
+            wallet_x_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedAccount,
+        )?;
+        check_owner(
+            pool_mint,
+            pool_mint_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedMint,
+        )?;
+        let (pool, pool_state, _) = check_pool_authority(
+            program_id,
+            pool_mint,
+            pool_mint_authority,
+            pool_authority_spec,
+        )?;
+        check_pool_wallet(
+            program_id,
+            pool,
+            pool_state.as_ref(),
+            user_wallet_x,
+            pool_wallet_x,
+            pool_wallet_spec,
+        )?;
+
+        Ok(Self {
+            user_wallet_x,
+            pool_mint,
+            pool_mint_authority,
+            pool_wallet_x,
+            pool,
+            pool_state,
+        })
+    }
+}
+
+/// Accounts of `VerifySolvency`
+pub struct VerifySolvencyAccounts<'a, 'b> {
+    pub pool: &'a AccountInfo<'b>,
+    pub pool_mint: &'a AccountInfo<'b>,
+    pub vault: &'a AccountInfo<'b>,
+    /// State of `pool`
+    pub pool_state: Pool,
+}
+
+impl<'a, 'b> VerifySolvencyAccounts<'a, 'b> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [pool_spec, pool_mint_spec, vault_spec] = &VERIFY_SOLVENCY_ACCOUNTS;
+        let iter = &mut accounts.iter();
+        let pool = next_account(iter, pool_spec)?;
+        let pool_mint = next_account(iter, pool_mint_spec)?;
+        let vault = next_account(iter, vault_spec)?;
+
+        check_owner(
+            pool_mint,
+            pool_mint_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedMint,
+        )?;
+        let pool_state = unpack_pool(program_id, pool, pool_spec, Some(pool_mint.key))?;
+        check_vault(program_id, pool, &pool_state, vault, vault_spec)?;
+
+        Ok(Self {
+            pool,
+            pool_mint,
+            vault,
+            pool_state,
+        })
+    }
+}
+
+/// Accounts of `WrapWithVesting` following the accounts of `ChangeXtoY`
+pub struct WrapWithVestingAccounts<'a, 'b> {
+    pub vesting: &'a AccountInfo<'b>,
+    pub beneficiary: &'a AccountInfo<'b>,
+    pub clock: &'a AccountInfo<'b>,
+    pub escrow_bump: u8,
+}
+
+impl<'a, 'b> WrapWithVestingAccounts<'a, 'b> {
+    /// Parses the accounts left after `change_x_to_y`, parsed from the same list
+    pub fn parse(
+        program_id: &Pubkey,
+        change_x_to_y: &ChangeXtoYAccounts<'a, 'b>,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [grantor_spec, wallet_x_spec, mint_x_spec, escrow_spec, pool_mint_spec, _, pool_wallet_spec, _, _, _, vesting_spec, beneficiary_spec, clock_spec] =
+            &WRAP_WITH_VESTING_ACCOUNTS;
+        // The grantor pays for the vesting and escrow accounts
+        check_flags(change_x_to_y.user_wallets_authority, grantor_spec)?;
+        let iter = &mut accounts[CHANGE_X_TO_Y_REQUIRED_ACCOUNTS..].iter();
+        let vesting = next_account(iter, vesting_spec)?;
+        let beneficiary = next_account(iter, beneficiary_spec)?;
+        let clock = next_account(iter, clock_spec)?;
+
+        let (expected_escrow, escrow_bump) = find_program_address(program_id, vesting.key);
+        check_address(
+            change_x_to_y.user_wallet_y,
+            escrow_spec,
+            &expected_escrow,
+            CrateError::InvalidEscrowAddress,
+        )?;
+        check_address(
+            clock,
+            clock_spec,
+            &sysvar::clock::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+
+        check_unique(&[
+            (change_x_to_y.user_wallets_authority, grantor_spec),
+            (change_x_to_y.user_wallet_x, wallet_x_spec),
+            (change_x_to_y.token_x_mint, mint_x_spec),
+            (change_x_to_y.user_wallet_y, escrow_spec),
+            (change_x_to_y.pool_mint, pool_mint_spec),
+            (change_x_to_y.pool_wallet_x, pool_wallet_spec),
+            (vesting, vesting_spec),
+        ])?;
+
+        Ok(Self {
+            vesting,
+            beneficiary,
+            clock,
+            escrow_bump,
+        })
+    }
+}
+
+/// Accounts of `ClaimVested` and `RevokeVesting`
+pub struct VestingAccounts<'a, 'b> {
+    /// Beneficiary or grantor
+    pub authority: &'a AccountInfo<'b>,
+    pub vesting: &'a AccountInfo<'b>,
+    pub escrow: &'a AccountInfo<'b>,
+    pub destination: &'a AccountInfo<'b>,
+    pub clock: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    /// State of `vesting`
+    pub vesting_state: Vesting,
+}
+
+impl<'a, 'b> VestingAccounts<'a, 'b> {
+    /// Parses the accounts described by `specs`, `CLAIM_VESTED_ACCOUNTS` or
+    /// `REVOKE_VESTING_ACCOUNTS`
+    pub fn parse(
+        program_id: &Pubkey,
+        specs: &[AccountSpec; 6],
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [authority_spec, vesting_spec, escrow_spec, destination_spec, clock_spec, token_spec] =
+            specs;
+        let iter = &mut accounts.iter();
+        let authority = next_account(iter, authority_spec)?;
+        let vesting = next_account(iter, vesting_spec)?;
+        let escrow = next_account(iter, escrow_spec)?;
+        let destination = next_account(iter, destination_spec)?;
+        let clock = next_account(iter, clock_spec)?;
+        let token_program = next_account(iter, token_spec)?;
+
+        check_owner(
+            vesting,
+            vesting_spec,
+            &[*program_id],
+            CrateError::ExpectedVesting,
+        )?;
+        let vesting_state =
+            Vesting::unpack(&vesting.data.borrow()).map_err(|_| CrateError::ExpectedVesting)?;
+        let expected_escrow = Pubkey::create_program_address(
+            &[&vesting.key.to_bytes(), &[vesting_state.escrow_bump]],
+            program_id,
+        )
+        .map_err(|_| CrateError::InvalidEscrowAddress)?;
+        check_address(
+            escrow,
+            escrow_spec,
+            &expected_escrow,
+            CrateError::InvalidEscrowAddress,
+        )?;
+        check_owner(
+            destination,
+            destination_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedAccount,
+        )?;
+        check_address(
+            clock,
+            clock_spec,
+            &sysvar::clock::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        check_address(
+            token_program,
+            token_spec,
+            &spl_token::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+
+        check_unique(&[
+            (vesting, vesting_spec),
+            (escrow, escrow_spec),
+            (destination, destination_spec),
+        ])?;
+
+        Ok(Self {
+            authority,
+            vesting,
+            escrow,
+            destination,
+            clock,
+            token_program,
+            vesting_state,
+        })
+    }
+}
+
+/// Accounts of `InitializePool`
+pub struct InitializePoolAccounts<'a, 'b> {
+    pub admin: &'a AccountInfo<'b>,
+    pub pool_mint: &'a AccountInfo<'b>,
+    pub pool: &'a AccountInfo<'b>,
+    pub vault: &'a AccountInfo<'b>,
+    pub token_x_mint: &'a AccountInfo<'b>,
+    pub metadata: &'a AccountInfo<'b>,
+    pub metadata_program: &'a AccountInfo<'b>,
+    pub rent: &'a AccountInfo<'b>,
+    pub system_program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    pub pool_bump: u8,
+    pub vault_bump: u8,
+}
+
+impl<'a, 'b> InitializePoolAccounts<'a, 'b> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [admin_spec, pool_mint_spec, pool_spec, vault_spec, mint_x_spec, metadata_spec, metadata_program_spec, rent_spec, system_spec, token_spec] =
+            &INITIALIZE_POOL_ACCOUNTS;
+        let iter = &mut accounts.iter();
+        let admin = next_account(iter, admin_spec)?;
+        let pool_mint = next_account(iter, pool_mint_spec)?;
+        let pool = next_account(iter, pool_spec)?;
+        let vault = next_account(iter, vault_spec)?;
+        let token_x_mint = next_account(iter, mint_x_spec)?;
+        let metadata = next_account(iter, metadata_spec)?;
+        let metadata_program = next_account(iter, metadata_program_spec)?;
+        let rent = next_account(iter, rent_spec)?;
+        let system_program = next_account(iter, system_spec)?;
+        let token_program = next_account(iter, token_spec)?;
+
+        let (expected_pool, pool_bump) = find_pool_address(program_id, pool_mint.key);
+        check_address(
+            pool,
+            pool_spec,
+            &expected_pool,
+            CrateError::InvalidPoolAuthority,
+        )?;
+        let (expected_vault, vault_bump) = find_program_address(program_id, pool.key);
+        check_address(
+            vault,
+            vault_spec,
+            &expected_vault,
+            CrateError::InvalidPoolWalletAddress,
+        )?;
+        check_owner(
+            token_x_mint,
+            mint_x_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedMint,
+        )?;
+        // The pool state signs the metadata instruction
+        check_address(
+            metadata_program,
+            metadata_program_spec,
+            &metadata::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        check_address(
+            rent,
+            rent_spec,
+            &sysvar::rent::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        check_address(
+            system_program,
+            system_spec,
+            &system_program::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        check_address(
+            token_program,
+            token_spec,
+            &spl_token::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+
+        check_unique(&[
+            (admin, admin_spec),
+            (pool_mint, pool_mint_spec),
+            (token_x_mint, mint_x_spec),
+            (metadata, metadata_spec),
+        ])?;
+
+        Ok(Self {
+            admin,
+            pool_mint,
+            pool,
+            vault,
+            token_x_mint,
+            metadata,
+            metadata_program,
+            rent,
+            system_program,
+            token_program,
+            pool_bump,
+            vault_bump,
+        })
+    }
+}
+
+/// Accounts of `UpdatePoolMetadata`
+pub struct UpdatePoolMetadataAccounts<'a, 'b> {
+    pub admin: &'a AccountInfo<'b>,
+    pub pool: &'a AccountInfo<'b>,
+    pub metadata: &'a AccountInfo<'b>,
+    pub metadata_program: &'a AccountInfo<'b>,
+    /// State of `pool`
+    pub pool_state: Pool,
+}
+
+impl<'a, 'b> UpdatePoolMetadataAccounts<'a, 'b> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [admin_spec, pool_spec, metadata_spec, metadata_program_spec] =
+            &UPDATE_POOL_METADATA_ACCOUNTS;
+        let iter = &mut accounts.iter();
+        let admin = next_account(iter, admin_spec)?;
+        let pool = next_account(iter, pool_spec)?;
+        let metadata = next_account(iter, metadata_spec)?;
+        let metadata_program = next_account(iter, metadata_program_spec)?;
+
+        let pool_state = unpack_pool(program_id, pool, pool_spec, None)?;
+        // The pool state signs the metadata instruction
+        check_address(
+            metadata_program,
+            metadata_program_spec,
+            &metadata::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+
+        Ok(Self {
+            admin,
+            pool,
+            metadata,
+            metadata_program,
+            pool_state,
+        })
+    }
+}
+
+/// Accounts of `Skim`
+pub struct SkimAccounts<'a, 'b> {
+    pub admin: &'a AccountInfo<'b>,
+    pub pool: &'a AccountInfo<'b>,
+    pub pool_mint: &'a AccountInfo<'b>,
+    pub vault: &'a AccountInfo<'b>,
+    pub treasury: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    /// State of `pool`
+    pub pool_state: Pool,
+    pub vault_bump: u8,
+}
+
+impl<'a, 'b> SkimAccounts<'a, 'b> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [admin_spec, pool_spec, pool_mint_spec, vault_spec, treasury_spec, token_spec] =
+            &SKIM_ACCOUNTS;
+        let iter = &mut accounts.iter();
+        let admin = next_account(iter, admin_spec)?;
+        let pool = next_account(iter, pool_spec)?;
+        let pool_mint = next_account(iter, pool_mint_spec)?;
+        let vault = next_account(iter, vault_spec)?;
+        let treasury = next_account(iter, treasury_spec)?;
+        let token_program = next_account(iter, token_spec)?;
+
+        check_owner(
+            pool_mint,
+            pool_mint_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedMint,
+        )?;
+        let pool_state = unpack_pool(program_id, pool, pool_spec, Some(pool_mint.key))?;
+        let vault_bump = check_vault(program_id, pool, &pool_state, vault, vault_spec)?;
+        check_address(
+            token_program,
+            token_spec,
+            &spl_token::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+
+        check_unique(&[(vault, vault_spec), (treasury, treasury_spec)])?;
+
+        Ok(Self {
+            admin,
+            pool,
+            pool_mint,
+            vault,
+            treasury,
+            token_program,
+            pool_state,
+            vault_bump,
+        })
+    }
+}
+
+/// Accounts of `RequestUnwrap`
+pub struct RequestUnwrapAccounts<'a, 'b> {
+    pub owner: &'a AccountInfo<'b>,
+    pub user_wallet_y: &'a AccountInfo<'b>,
+    pub pool_mint: &'a AccountInfo<'b>,
+    pub pool: &'a AccountInfo<'b>,
+    pub vault: &'a AccountInfo<'b>,
+    pub ticket: &'a AccountInfo<'b>,
+    pub clock: &'a AccountInfo<'b>,
+    pub rent: &'a AccountInfo<'b>,
+    pub system_program: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    /// State of `pool`
+    pub pool_state: Pool,
+    pub ticket_bump: u8,
+}
+
+impl<'a, 'b> RequestUnwrapAccounts<'a, 'b> {
+    /// Parses the accounts, the withdrawal ticket being derived from `nonce`
+    pub fn parse(
+        program_id: &Pubkey,
+        nonce: u64,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [owner_spec, wallet_y_spec, pool_mint_spec, pool_spec, vault_spec, ticket_spec, clock_spec, rent_spec, system_spec, token_spec] =
+            &REQUEST_UNWRAP_ACCOUNTS;
+        let iter = &mut accounts.iter();
+        let owner = next_account(iter, owner_spec)?;
+        let user_wallet_y = next_account(iter, wallet_y_spec)?;
+        let pool_mint = next_account(iter, pool_mint_spec)?;
+        let pool = next_account(iter, pool_spec)?;
+        let vault = next_account(iter, vault_spec)?;
+        let ticket = next_account(iter, ticket_spec)?;
+        let clock = next_account(iter, clock_spec)?;
+        let rent = next_account(iter, rent_spec)?;
+        let system_program = next_account(iter, system_spec)?;
+        let token_program = next_account(iter, token_spec)?;
+
+        check_owner(
+            user_wallet_y,
+            wallet_y_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedAccount,
+        )?;
+        check_owner(
+            pool_mint,
+            pool_mint_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedMint,
+        )?;
+        let pool_state = unpack_pool(program_id, pool, pool_spec, Some(pool_mint.key))?;
+        check_vault(program_id, pool, &pool_state, vault, vault_spec)?;
+        check_owner(
+            vault,
+            vault_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedAccount,
+        )?;
+        let (expected_ticket, ticket_bump) =
+            find_ticket_address(program_id, pool.key, owner.key, nonce);
+        check_address(
+            ticket,
+            ticket_spec,
+            &expected_ticket,
+            CrateError::ExpectedTicket,
+        )?;
+        check_address(
+            clock,
+            clock_spec,
+            &sysvar::clock::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        check_address(
+            rent,
+            rent_spec,
+            &sysvar::rent::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        check_address(
+            system_program,
+            system_spec,
+            &system_program::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        check_address(
+            token_program,
+            token_spec,
+            &spl_token::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+
+        check_unique(&[(owner, owner_spec), (user_wallet_y, wallet_y_spec)])?;
+
+        Ok(Self {
+            owner,
+            user_wallet_y,
+            pool_mint,
+            pool,
+            vault,
+            ticket,
+            clock,
+            rent,
+            system_program,
+            token_program,
+            pool_state,
+            ticket_bump,
+        })
+    }
+}
+
+/// Accounts of `ClaimUnwrap`
+pub struct ClaimUnwrapAccounts<'a, 'b> {
+    pub owner: &'a AccountInfo<'b>,
+    pub destination: &'a AccountInfo<'b>,
+    pub pool: &'a AccountInfo<'b>,
+    pub vault: &'a AccountInfo<'b>,
+    pub ticket: &'a AccountInfo<'b>,
+    pub clock: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+    /// State of `pool`
+    pub pool_state: Pool,
+    pub vault_bump: u8,
+}
+
+impl<'a, 'b> ClaimUnwrapAccounts<'a, 'b> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let [owner_spec, destination_spec, pool_spec, vault_spec, ticket_spec, clock_spec, token_spec] =
+            &CLAIM_UNWRAP_ACCOUNTS;
+        let iter = &mut accounts.iter();
+        let owner = next_account(iter, owner_spec)?;
+        let destination = next_account(iter, destination_spec)?;
+        let pool = next_account(iter, pool_spec)?;
+        let vault = next_account(iter, vault_spec)?;
+        let ticket = next_account(iter, ticket_spec)?;
+        let clock = next_account(iter, clock_spec)?;
+        let token_program = next_account(iter, token_spec)?;
+
+        check_owner(
+            destination,
+            destination_spec,
+            &[spl_token::id()],
+            CrateError::ExpectedAccount,
+        )?;
+        // The processor checks the pool against the ticket, created for a pool state
+        check_owner(pool, pool_spec, &[*program_id], CrateError::ExpectedPool)?;
+        let pool_state = Pool::unpack(&pool.data.borrow()).map_err(|_| CrateError::ExpectedPool)?;
+        let vault_bump = check_vault(program_id, pool, &pool_state, vault, vault_spec)?;
+        check_owner(
+            ticket,
+            ticket_spec,
+            &[*program_id],
+            CrateError::ExpectedTicket,
+        )?;
+        check_address(
+            clock,
+            clock_spec,
+            &sysvar::clock::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+        check_address(
+            token_program,
+            token_spec,
+            &spl_token::id(),
+            CrateError::IncorrectProgramAccount,
+        )?;
+
+        check_unique(&[
+            (owner, owner_spec),
+            (destination, destination_spec),
+            (pool, pool_spec),
+            (vault, vault_spec),
+            (ticket, ticket_spec),
+        ])?;
+
+        Ok(Self {
+            owner,
+            destination,
+            pool,
+            vault,
+            ticket,
+            clock,
+            token_program,
+            pool_state,
+            vault_bump,
+        })
+    }
+}
@@ -4,106 +4,122 @@ use solana_program::program_error::PrintProgramError;
 use solana_program::{decode_error::DecodeError, msg, program_error::ProgramError};
 use thiserror::Error;
 
-/// Errors that may be returned by the TokenSwap program.
-#[derive(Clone, Debug, Eq, Error, FromPrimitive, PartialEq)]
+/// Errors that may be returned by the wrapper program.
+///
+/// Codes are fixed and must never be reused, new errors take the next free code.
+/// They start at 100 because errors of the token program invoked by the wrapper
+/// are returned with the same instruction index and use codes below 100.
+#[derive(Clone, Copy, Debug, Eq, Error, FromPrimitive, PartialEq)]
 pub enum CrateError {
-    // 0.
-    /// The account cannot be initialized because it is already being used.
-    #[error("Swap account already in use")]
-    AlreadyInUse,
-    #[error("Deposit amount too large")]
-    NotEnoughTokens,
-    /// The program address provided doesn't match the value generated by the program.
-    #[error("Invalid program address generated from bump seed and key")]
-    InvalidProgramAddress,
-    /// The owner of the input isn't set to the program address generated by the program.
-    #[error("Input account owner is not the program address")]
-    InvalidOwner,
-    /// The owner of the pool token output is set to the program address generated by the program.
-    #[error("Output pool account owner cannot be the program address")]
-    InvalidOutputOwner,
-    /// The deserialization of the account returned something besides State::Mint.
-    #[error("Deserialized account is not an SPL Token mint")]
-    ExpectedMint,
+    /// The instruction data can't be decoded
+    #[error("Invalid instruction")]
+    InvalidInstruction = 100,
+    /// The return data is missing or was set by another program
+    #[error("The return data is missing or was set by another program")]
+    InvalidReturnData = 101,
+    /// The pool mint or token X mint isn't an SPL Token mint
+    #[error("Account is not an SPL Token mint")]
+    ExpectedMint = 102,
+    /// The account isn't an initialized SPL Token account
+    #[error("Account is not an SPL Token account")]
+    ExpectedAccount = 103,
+    /// The pool mint isn't minted by the pool authority
+    #[error("Pool mint authority is not the pool authority")]
+    InvalidPoolMintAuthority = 104,
 
-    // 5.
-    /// The deserialization of the account returned something besides State::Account.
-    #[error("Deserialized account is not an SPL Token account")]
-    ExpectedAccount,
-    /// The input token account is empty.
-    #[error("Input token account empty")]
-    EmptySupply,
-    /// The pool token mint has a non-zero supply.
-    #[error("Pool token mint has a non-zero supply")]
-    InvalidSupply,
-    /// The provided token account has a delegate.
-    #[error("Token account has a delegate")]
-    InvalidDelegate,
-    /// The input token is invalid for swap.
-    #[error("InvalidInput")]
-    InvalidInput,
+    /// The pool authority isn't the program address derived from the pool mint
+    #[error("Pool authority is not derived from the pool mint")]
+    InvalidPoolAuthority = 105,
+    /// The pool wallet isn't the program address derived from the user's token X wallet,
+    /// or from the pool state for pools created by `InitializePool`
+    #[error("Pool wallet is not derived from the user's token X wallet or the pool state")]
+    InvalidPoolWalletAddress = 106,
+    /// The pool wallet address is taken by an account the program can't use
+    #[error("Pool wallet address is used by another account")]
+    PoolWalletInUse = 107,
+    /// The pool wallet holds another token than token X
+    #[error("Pool wallet mint is not the token X mint")]
+    IncorrectTokenXMint = 108,
+    /// The user's pool token wallet holds another token than the pool mint
+    #[error("User wallet mint is not the pool mint")]
+    IncorrectPoolMint = 109,
 
-    // 10.
-    /// Address of the provided swap token account is incorrect.
-    #[error("Address of the provided swap token account is incorrect")]
-    IncorrectSwapAccount,
-    /// Address of the provided pool token mint is incorrect
-    #[error("Address of the provided pool token mint is incorrect")]
-    IncorrectPoolMint,
-    /// The output token is invalid for swap.
-    #[error("InvalidOutput")]
-    InvalidOutput,
-    /// General calculation failure due to overflow or underflow
-    #[error("General calculation failure due to overflow or underflow")]
-    CalculationFailure,
-    /// Invalid instruction number passed in.
-    #[error("Invalid instruction")]
-    InvalidInstruction,
+    /// The user's wallets aren't owned by the transfer authority
+    #[error("User wallet is not owned by the transfer authority")]
+    InvalidWalletOwner = 110,
+    /// The destination is a new account, but not the associated token account of the authority
+    #[error("Destination is not the associated token account of the authority")]
+    InvalidAssociatedAccount = 111,
+    /// The user's source wallet holds less than the amount
+    #[error("Not enough tokens in the source wallet")]
+    NotEnoughTokens = 112,
+    /// The pool wallet holds less token X than the amount
+    #[error("Not enough tokens in the pool wallet")]
+    InsufficientPoolBalance = 113,
+    /// An account the instruction writes to is passed read-only
+    #[error("Account must be writable")]
+    AccountNotWritable = 114,
+
+    /// A program or sysvar account isn't the expected one
+    #[error("Incorrect program or sysvar account")]
+    IncorrectProgramAccount = 115,
+    /// The same account is passed for two roles
+    #[error("Account is passed for two roles")]
+    DuplicateAccount = 116,
+    /// The vault of the pool holds less token X than it owes
+    #[error("Pool is insolvent")]
+    Insolvent = 117,
+    /// An exact output change costs more than the maximum input
+    #[error("Required input exceeds the maximum")]
+    MaximumInExceeded = 118,
+    /// The vesting cliff is before the start or after the end
+    #[error("Vesting cliff must be between the start and the end")]
+    InvalidVestingSchedule = 119,
 
-    // 15.
-    /// Swap input token accounts have the same mint
-    #[error("Swap input token accounts have the same mint")]
-    RepeatedMint,
-    /// Swap instruction exceeds desired slippage limit
-    #[error("Swap instruction exceeds desired slippage limit")]
-    ExceededSlippage,
-    /// The provided token account has a close authority.
-    #[error("Token account has a close authority")]
-    InvalidCloseAuthority,
-    /// The pool token mint has a freeze authority.
-    #[error("Pool token mint has a freeze authority")]
-    InvalidFreezeAuthority,
-    /// The pool fee token account is incorrect
-    #[error("Pool fee token account incorrect")]
-    IncorrectFeeAccount,
+    /// The account isn't an initialized vesting account of the program
+    #[error("Account is not a vesting account")]
+    ExpectedVesting = 120,
+    /// The escrow account isn't the program address derived from the vesting account
+    #[error("Escrow account is not derived from the vesting account")]
+    InvalidEscrowAddress = 121,
+    /// The signer isn't the beneficiary or grantor recorded in the vesting account
+    #[error("Signer is not the vesting beneficiary or grantor")]
+    InvalidVestingAuthority = 122,
+    /// The account isn't the state of a pool created by `InitializePool`
+    #[error("Account is not a pool state")]
+    ExpectedPool = 123,
+    /// The signer isn't the admin recorded in the pool state
+    #[error("Signer is not the pool admin")]
+    InvalidPoolAdmin = 124,
 
-    // 20.
-    /// Given pool token amount results in zero trading tokens
-    #[error("Given pool token amount results in zero trading tokens")]
-    ZeroTradingTokens,
-    /// The fee calculation failed due to overflow, underflow, or unexpected 0
-    #[error("Fee calculation failed due to overflow, underflow, or unexpected 0")]
-    FeeCalculationFailure,
-    /// ConversionFailure
-    #[error("Conversion to u64 failed with an overflow or underflow")]
-    ConversionFailure,
-    /// The provided fee does not match the program owner's constraints
-    #[error("The provided fee does not match the program owner's constraints")]
-    InvalidFee,
-    /// The provided token program does not match the token program expected by the swap
-    #[error("The provided token program does not match the token program expected by the swap")]
-    IncorrectTokenProgramId,
+    /// The instruction isn't available in the mode of the pool
+    #[error("Instruction is not available in this pool mode")]
+    InvalidPoolMode = 125,
+    /// The amount changes into no token at the rate of the pool
+    #[error("Amount is too small to change at the pool rate")]
+    ZeroAmountOut = 126,
+    /// The amount changed at the rate of the pool doesn't fit in a token amount
+    #[error("Amount overflows at the pool rate")]
+    AmountOverflow = 127,
+    /// The pool has an unwrap delay, its pool tokens are unwrapped by `RequestUnwrap`
+    #[error("Pool unwraps through withdrawal tickets")]
+    UnwrapDelayed = 128,
+    /// The account isn't a withdrawal ticket of the pool
+    #[error("Account is not a withdrawal ticket of the pool")]
+    ExpectedTicket = 129,
 
-    // 25.
-    /// The provided curve type is not supported by the program owner
-    #[error("The provided curve type is not supported by the program owner")]
-    UnsupportedCurveType,
-    /// The provided curve parameters are invalid
-    #[error("The provided curve parameters are invalid")]
-    InvalidCurve,
-    /// The operation cannot be performed on the given curve
-    #[error("The operation cannot be performed on the given curve")]
-    UnsupportedCurveOperation,
+    /// The unlock time of the withdrawal ticket isn't reached
+    #[error("Withdrawal ticket is still locked")]
+    TicketLocked = 130,
+    /// The signer isn't the owner recorded in the withdrawal ticket
+    #[error("Signer is not the ticket owner")]
+    InvalidTicketOwner = 131,
+    /// The subscription deadline of the pool has passed
+    #[error("Pool subscription is closed")]
+    SubscriptionClosed = 132,
+    /// The maturity of the pool isn't reached
+    #[error("Pool has not matured")]
+    NotMatured = 133,
 }
 impl From<CrateError> for ProgramError {
     fn from(e: CrateError) -> Self {
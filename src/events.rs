@@ -0,0 +1,106 @@
+//! Events logged by the program with `sol_log_data`
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::log::sol_log_data;
+use solana_program::pubkey::Pubkey;
+
+/// Prefix of the log line produced by `sol_log_data`
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Token X was wrapped into pool tokens
+#[derive(Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct WrapEvent {
+    pub pool_mint: Pubkey,
+    pub pool_wallet_x: Pubkey,
+    pub owner: Pubkey,
+    /// Token X taken from the user
+    pub amount_in: u64,
+    /// Pool tokens minted to the user
+    pub amount_out: u64,
+    /// Fee taken on the change, always 0 as the program charges none yet
+    pub fee: u64,
+    /// Balance of the pool wallet after the transfer
+    pub pool_wallet_x_balance: u64,
+}
+
+/// Pool tokens were unwrapped back into token X
+#[derive(Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct UnwrapEvent {
+    pub pool_mint: Pubkey,
+    pub pool_wallet_x: Pubkey,
+    pub owner: Pubkey,
+    /// Pool tokens burned from the user
+    pub amount_in: u64,
+    /// Token X sent to the user
+    pub amount_out: u64,
+    /// Fee taken on the change, always 0 as the program charges none yet
+    pub fee: u64,
+    /// Balance of the pool wallet after the transfer
+    pub pool_wallet_x_balance: u64,
+}
+
+/// Backing of the pool tokens of a pool by its vault, logged by `VerifySolvency`
+#[derive(Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct SolvencyEvent {
+    pub pool_mint: Pubkey,
+    pub token_x_mint: Pubkey,
+    /// Pool tokens in circulation
+    pub supply: u64,
+    /// Token X held by the vault
+    pub vault_balance: u64,
+    /// Token X of the vault owed to the withdrawal tickets not claimed yet
+    pub pending_unwrap: u64,
+    /// Vault balance not owed to the tickets over supply in basis points, `None`
+    /// when nothing is minted
+    pub ratio_bps: Option<u64>,
+}
+
+/// Events supported by the program
+#[derive(Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub enum ContractEvent {
+    Wrap(WrapEvent),
+    Unwrap(UnwrapEvent),
+    Solvency(SolvencyEvent),
+}
+
+impl ContractEvent {
+    /// Writes the event to the program log
+    pub fn emit(&self) {
+        sol_log_data(&[&self.try_to_vec().unwrap()]);
+    }
+}
+
+/// Collects the events logged by `program_id` from transaction log messages.
+/// Data logged by other programs, including ones invoked by this program, is skipped.
+pub fn decode_events(program_id: &Pubkey, logs: &[String]) -> Vec<ContractEvent> {
+    let program_id = program_id.to_string();
+    let mut invoke_stack: Vec<&str> = vec![];
+    let mut events = vec![];
+
+    for log in logs {
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            if invoke_stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            let fields: Result<Vec<_>, _> = data.split(' ').map(base64::decode).collect();
+            if let Some(event) = fields
+                .ok()
+                .and_then(|fields| ContractEvent::try_from_slice(&fields.concat()).ok())
+            {
+                events.push(event);
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split(' ');
+            let id = words.next().unwrap_or_default();
+            match words.next() {
+                Some("invoke") => invoke_stack.push(id),
+                Some("success") | Some("failed:") => {
+                    invoke_stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
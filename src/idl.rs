@@ -0,0 +1,344 @@
+//! Interface description of the program, checked in as `idl/solana_token.json`
+
+use crate::error::CrateError;
+use crate::instruction::{AccountSpec, ChangeDirection, ContractInstruction, INSTRUCTION_VERSION};
+use crate::state::PoolMode;
+use num_traits::FromPrimitive;
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Idl {
+    pub version: &'static str,
+    pub name: &'static str,
+    pub address: String,
+    /// First byte of the instruction data
+    pub instruction_version: u8,
+    pub instructions: Vec<IdlInstruction>,
+    /// Accounts owned by the program
+    pub accounts: Vec<IdlTypeDef>,
+    /// Program addresses the instructions expect, derived from seed accounts or from
+    /// constant seeds, quoted
+    pub pdas: Vec<IdlPda>,
+    pub types: Vec<IdlTypeDef>,
+    pub errors: Vec<IdlError>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct IdlInstruction {
+    pub name: &'static str,
+    /// Tag following the version byte
+    pub discriminant: u8,
+    pub accounts: &'static [AccountSpec],
+    pub args: Vec<IdlField>,
+    pub returns: Option<&'static str>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct IdlField {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct IdlPda {
+    pub name: &'static str,
+    pub seeds: Vec<&'static str>,
+    pub description: &'static str,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum IdlTypeDef {
+    Struct {
+        name: &'static str,
+        fields: Vec<IdlField>,
+    },
+    Enum {
+        name: &'static str,
+        variants: Vec<&'static str>,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct IdlError {
+    pub code: u32,
+    pub name: String,
+    pub msg: String,
+}
+
+/// One value of every instruction, with a distinct value in every argument
+pub fn instruction_variants() -> Vec<ContractInstruction> {
+    vec![
+        ContractInstruction::ChangeXtoY { amount: 1 },
+        ContractInstruction::ChangeYtoX { amount: 1 },
+        ContractInstruction::Quote {
+            amount: 1,
+            direction: ChangeDirection::YtoX,
+        },
+        ContractInstruction::VerifySolvency,
+        ContractInstruction::ChangeXtoYExactOut {
+            amount_out: 1,
+            maximum_in: 2,
+        },
+        ContractInstruction::ChangeYtoXExactOut {
+            amount_out: 1,
+            maximum_in: 2,
+        },
+        ContractInstruction::ChangeXtoYAll,
+        ContractInstruction::ChangeYtoXAll,
+        ContractInstruction::WrapWithVesting {
+            amount: 1,
+            cliff_ts: 2,
+            end_ts: 3,
+        },
+        ContractInstruction::ClaimVested,
+        ContractInstruction::RevokeVesting,
+        ContractInstruction::InitializePool {
+            name: "name".to_string(),
+            symbol: "symbol".to_string(),
+            uri: "uri".to_string(),
+            mode: PoolMode::Shares,
+            unwrap_delay: 1,
+            subscription_deadline: 2,
+            maturity: 3,
+        },
+        ContractInstruction::UpdatePoolMetadata {
+            name: "name".to_string(),
+            symbol: "symbol".to_string(),
+            uri: "uri".to_string(),
+        },
+        ContractInstruction::Skim,
+        ContractInstruction::RequestUnwrap {
+            amount: 1,
+            nonce: 2,
+        },
+        ContractInstruction::ClaimUnwrap,
+    ]
+}
+
+/// Arguments of the instruction, in borsh order
+fn instruction_args(instruction: &ContractInstruction) -> Vec<IdlField> {
+    let field = |name, ty| IdlField { name, ty };
+    match instruction {
+        ContractInstruction::ChangeXtoY { amount: _ } => vec![field("amount", "u64")],
+        ContractInstruction::ChangeYtoX { amount: _ } => vec![field("amount", "u64")],
+        ContractInstruction::Quote {
+            amount: _,
+            direction: _,
+        } => vec![
+            field("amount", "u64"),
+            field("direction", "ChangeDirection"),
+        ],
+        ContractInstruction::VerifySolvency
+        | ContractInstruction::ChangeXtoYAll
+        | ContractInstruction::ChangeYtoXAll
+        | ContractInstruction::ClaimVested
+        | ContractInstruction::RevokeVesting
+        | ContractInstruction::Skim
+        | ContractInstruction::ClaimUnwrap => vec![],
+        ContractInstruction::WrapWithVesting {
+            amount: _,
+            cliff_ts: _,
+            end_ts: _,
+        } => vec![
+            field("amount", "u64"),
+            field("cliff_ts", "i64"),
+            field("end_ts", "i64"),
+        ],
+        ContractInstruction::ChangeXtoYExactOut {
+            amount_out: _,
+            maximum_in: _,
+        }
+        | ContractInstruction::ChangeYtoXExactOut {
+            amount_out: _,
+            maximum_in: _,
+        } => vec![field("amount_out", "u64"), field("maximum_in", "u64")],
+        ContractInstruction::InitializePool {
+            name: _,
+            symbol: _,
+            uri: _,
+            mode: _,
+            unwrap_delay: _,
+            subscription_deadline: _,
+            maturity: _,
+        } => vec![
+            field("name", "string"),
+            field("symbol", "string"),
+            field("uri", "string"),
+            field("mode", "PoolMode"),
+            field("unwrap_delay", "u32"),
+            field("subscription_deadline", "i64"),
+            field("maturity", "i64"),
+        ],
+        ContractInstruction::UpdatePoolMetadata {
+            name: _,
+            symbol: _,
+            uri: _,
+        } => vec![
+            field("name", "string"),
+            field("symbol", "string"),
+            field("uri", "string"),
+        ],
+        ContractInstruction::RequestUnwrap {
+            amount: _,
+            nonce: _,
+        } => vec![field("amount", "u64"), field("nonce", "u64")],
+    }
+}
+
+/// Type of the return data set by the instruction
+fn instruction_returns(instruction: &ContractInstruction) -> Option<&'static str> {
+    match instruction {
+        ContractInstruction::ChangeXtoY { .. }
+        | ContractInstruction::ChangeYtoX { .. }
+        | ContractInstruction::Quote { .. }
+        | ContractInstruction::ChangeXtoYExactOut { .. }
+        | ContractInstruction::ChangeYtoXExactOut { .. }
+        | ContractInstruction::ChangeXtoYAll
+        | ContractInstruction::ChangeYtoXAll
+        | ContractInstruction::WrapWithVesting { .. }
+        | ContractInstruction::RequestUnwrap { .. } => Some("ChangeResult"),
+        ContractInstruction::VerifySolvency
+        | ContractInstruction::ClaimVested
+        | ContractInstruction::RevokeVesting
+        | ContractInstruction::InitializePool { .. }
+        | ContractInstruction::UpdatePoolMetadata { .. }
+        | ContractInstruction::Skim
+        | ContractInstruction::ClaimUnwrap => None,
+    }
+}
+
+pub fn generate() -> Idl {
+    let field = |name, ty| IdlField { name, ty };
+    let instructions = instruction_variants()
+        .iter()
+        .map(|instruction| IdlInstruction {
+            name: instruction.name(),
+            discriminant: instruction.tag(),
+            accounts: instruction.accounts(),
+            args: instruction_args(instruction),
+            returns: instruction_returns(instruction),
+        })
+        .collect();
+
+    let errors = (0..u16::MAX as u32)
+        .filter_map(CrateError::from_u32)
+        .map(|error| IdlError {
+            code: error as u32,
+            name: format!("{:?}", error),
+            msg: error.to_string(),
+        })
+        .collect();
+
+    Idl {
+        version: env!("CARGO_PKG_VERSION"),
+        name: "solana_token",
+        address: crate::id().to_string(),
+        instruction_version: INSTRUCTION_VERSION,
+        instructions,
+        accounts: vec![
+            IdlTypeDef::Struct {
+                name: "Vesting",
+                fields: vec![
+                    field("is_initialized", "bool"),
+                    field("pool_mint", "publicKey"),
+                    field("grantor", "publicKey"),
+                    field("beneficiary", "publicKey"),
+                    field("start_ts", "i64"),
+                    field("cliff_ts", "i64"),
+                    field("end_ts", "i64"),
+                    field("total", "u64"),
+                    field("claimed", "u64"),
+                    field("escrow_bump", "u8"),
+                ],
+            },
+            IdlTypeDef::Struct {
+                name: "Pool",
+                fields: vec![
+                    field("is_initialized", "bool"),
+                    field("admin", "publicKey"),
+                    field("pool_mint", "publicKey"),
+                    field("token_x_mint", "publicKey"),
+                    field("mode", "PoolMode"),
+                    field("unwrap_delay", "u32"),
+                    field("pending_unwrap", "u64"),
+                    field("subscription_deadline", "i64"),
+                    field("maturity", "i64"),
+                    field("bump", "u8"),
+                    field("vault_bump", "u8"),
+                ],
+            },
+            IdlTypeDef::Struct {
+                name: "Ticket",
+                fields: vec![
+                    field("is_initialized", "bool"),
+                    field("owner", "publicKey"),
+                    field("pool", "publicKey"),
+                    field("amount", "u64"),
+                    field("unlock_ts", "i64"),
+                ],
+            },
+        ],
+        pdas: vec![
+            IdlPda {
+                name: "Pool authority",
+                seeds: vec!["Pool mint"],
+                description: "Mint authority of a pool mint created off-chain, holds no data. \
+                    The pool state for pools created by InitializePool",
+            },
+            IdlPda {
+                name: "Pool wallet (token X)",
+                seeds: vec!["Source account (token X)"],
+                description: "Token X account owning itself, holds the wrapped token X. \
+                    The vault for pools created by InitializePool",
+            },
+            IdlPda {
+                name: "Pool state",
+                seeds: vec!["\"pool\"", "Pool mint"],
+                description: "Pool account of a pool created by InitializePool, \
+                    mint authority of its pool mint and update authority of its metadata",
+            },
+            IdlPda {
+                name: "Vault (token X)",
+                seeds: vec!["Pool state"],
+                description: "Token X account owning itself, holds the token X wrapped \
+                    in a pool created by InitializePool",
+            },
+            IdlPda {
+                name: "Escrow account (pool token)",
+                seeds: vec!["Vesting account"],
+                description: "Pool token account owning itself, holds the vesting pool tokens",
+            },
+            IdlPda {
+                name: "Withdrawal ticket",
+                seeds: vec!["\"ticket\"", "Pool state", "Owner", "nonce"],
+                description: "Ticket account of RequestUnwrap, the nonce is little-endian u64",
+            },
+        ],
+        types: vec![
+            IdlTypeDef::Enum {
+                name: "ChangeDirection",
+                variants: vec!["XtoY", "YtoX"],
+            },
+            IdlTypeDef::Enum {
+                name: "PoolMode",
+                variants: vec!["Fixed", "Shares"],
+            },
+            IdlTypeDef::Struct {
+                name: "ChangeResult",
+                fields: vec![
+                    IdlField {
+                        name: "amount_in",
+                        ty: "u64",
+                    },
+                    IdlField {
+                        name: "amount_out",
+                        ty: "u64",
+                    },
+                ],
+            },
+        ],
+        errors,
+    }
+}
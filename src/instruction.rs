@@ -1,32 +1,642 @@
-use crate::find_program_address;
+use crate::error::CrateError;
+use crate::metadata;
+use crate::state::PoolMode;
+use crate::{find_pool_address, find_program_address, find_ticket_address};
 use borsh::{BorshDeserialize, BorshSerialize};
+use serde::Serialize;
 use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::get_return_data;
+use solana_program::program_error::ProgramError;
 use solana_program::pubkey::Pubkey;
 use solana_program::sysvar;
 
-/// Instructions supported by the program
-#[derive(Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+/// First byte of the instruction data, followed by the instruction tag and its
+/// Borsh encoded arguments. Legacy data starts with a Borsh enum tag, which is
+/// always below 128, so versions start from there
+pub const INSTRUCTION_VERSION: u8 = 128;
+
+const CHANGE_X_TO_Y_TAG: u8 = 0;
+const CHANGE_Y_TO_X_TAG: u8 = 1;
+const QUOTE_TAG: u8 = 2;
+const VERIFY_SOLVENCY_TAG: u8 = 3;
+const CHANGE_X_TO_Y_EXACT_OUT_TAG: u8 = 4;
+const CHANGE_Y_TO_X_EXACT_OUT_TAG: u8 = 5;
+const CHANGE_X_TO_Y_ALL_TAG: u8 = 6;
+const CHANGE_Y_TO_X_ALL_TAG: u8 = 7;
+const WRAP_WITH_VESTING_TAG: u8 = 8;
+const CLAIM_VESTED_TAG: u8 = 9;
+const REVOKE_VESTING_TAG: u8 = 10;
+const INITIALIZE_POOL_TAG: u8 = 11;
+const UPDATE_POOL_METADATA_TAG: u8 = 12;
+const SKIM_TAG: u8 = 13;
+const REQUEST_UNWRAP_TAG: u8 = 14;
+const CLAIM_UNWRAP_TAG: u8 = 15;
+
+/// Instructions supported by the program, encoded with `pack`. Tags are fixed
+/// and must never be reused, new instructions take the next free tag
+#[derive(Debug, PartialEq, Serialize)]
 pub enum ContractInstruction {
     /// Accounts:
-    /// [RS] User transfer authority
-    /// [W] Source account ( token X)
+    /// [RS] User transfer authority, writable when it pays for a new pool wallet
+    ///      or destination account
+    /// [W] Source account (token X)
     /// [R] Token X mint
-    /// [W] Destination account (pool token)
+    /// [W] Destination account (pool token), the user's associated token account
+    ///     is created when it doesn't exist yet
     /// [W] Pool mint
-    /// [R] Pool authority
-    /// [W] Pool wallet (token X)
+    /// [R] Pool authority, the pool state for pools created by `InitializePool`,
+    ///     before its subscription deadline
+    /// [W] Pool wallet (token X), the vault for pools created by `InitializePool`
     /// [R] Rent sysvar
     /// [R] System program
     /// [R] Token program id
+    /// Optional:
+    /// [R] Associated token account program id, to create the destination account
     ChangeXtoY { amount: u64 },
     /// Accounts:
     /// [RS] User transfer authority
-    /// [W] Destination account ( token X)
+    /// [W] Destination account (token X)
     /// [W] Source account (pool token)
     /// [W] Pool mint
-    /// [W] Pool wallet (token X)
+    /// [W] Pool wallet (token X), the vault for pools created by `InitializePool`
     /// [R] Token program id
+    /// Optional:
+    /// [R] Pool state, required for pools created by `InitializePool`, which must
+    ///     have no unwrap delay and have matured
     ChangeYtoX { amount: u64 },
+    /// Validates a change without moving tokens, the `ChangeResult` is set as return data
+    ///
+    /// Accounts:
+    /// [R] User account (token X)
+    /// [R] Pool mint
+    /// [R] Pool authority, the pool state for pools created by `InitializePool`
+    /// [R] Pool wallet (token X), the vault for pools created by `InitializePool`
+    Quote {
+        amount: u64,
+        direction: ChangeDirection,
+    },
+    /// Logs a `SolvencyEvent` of a pool created by `InitializePool` and fails with
+    /// `Insolvent` if its vault holds less token X than it owes: the supply of the
+    /// pool mint and the pending withdrawal tickets in `Fixed` mode, the pending
+    /// withdrawal tickets and some token X while pool tokens are in circulation in
+    /// `Shares` mode. Permissionless, to be simulated by monitors or invoked by other
+    /// programs as a guard. The pool wallets of the pools created off-chain aren't
+    /// tied to a pool mint and can't be verified
+    ///
+    /// Accounts:
+    /// [R] Pool state
+    /// [R] Pool mint
+    /// [R] Vault (token X)
+    VerifySolvency,
+    /// Same as `ChangeXtoY`, but mints exactly `amount_out` pool tokens and fails
+    /// if they cost more than `maximum_in` token X
+    ///
+    /// Accounts: same as `ChangeXtoY`
+    ChangeXtoYExactOut { amount_out: u64, maximum_in: u64 },
+    /// Same as `ChangeYtoX`, but sends exactly `amount_out` token X and fails if
+    /// they cost more than `maximum_in` pool tokens
+    ///
+    /// Accounts: same as `ChangeYtoX`
+    ChangeYtoXExactOut { amount_out: u64, maximum_in: u64 },
+    /// Same as `ChangeXtoY`, with the whole balance of the source account when
+    /// the instruction executes
+    ///
+    /// Accounts: same as `ChangeXtoY`
+    ChangeXtoYAll,
+    /// Same as `ChangeYtoX`, with the whole balance of the source account when
+    /// the instruction executes
+    ///
+    /// Accounts: same as `ChangeYtoX`
+    ChangeYtoXAll,
+    /// Wraps `amount` token X of the grantor into pool tokens locked in an escrow
+    /// account for the beneficiary. They vest linearly from now until `end_ts`,
+    /// nothing is claimable before `cliff_ts`
+    ///
+    /// Accounts: same as `ChangeXtoY` without the optional account, with the
+    /// grantor as writable authority and the new escrow account (pool token) as
+    /// destination, followed by:
+    /// [WS] Vesting account, a new account
+    /// [R] Beneficiary
+    /// [R] Clock sysvar
+    WrapWithVesting {
+        amount: u64,
+        cliff_ts: i64,
+        end_ts: i64,
+    },
+    /// Sends the vested pool tokens not claimed yet to the destination
+    ///
+    /// Accounts:
+    /// [RS] Beneficiary
+    /// [W] Vesting account
+    /// [W] Escrow account (pool token)
+    /// [W] Destination account (pool token)
+    /// [R] Clock sysvar
+    /// [R] Token program id
+    ClaimVested,
+    /// Sends the pool tokens not vested yet to the destination, the vested ones
+    /// stay claimable by the beneficiary
+    ///
+    /// Accounts:
+    /// [RS] Grantor
+    /// [W] Vesting account
+    /// [W] Escrow account (pool token)
+    /// [W] Destination account (pool token)
+    /// [R] Clock sysvar
+    /// [R] Token program id
+    RevokeVesting,
+    /// Creates a pool managed by the program: the pool mint with the decimals of
+    /// token X, plus `SHARES_DECIMALS_OFFSET` in `Shares` mode, the pool state as
+    /// its pool authority, the vault holding the token X of the pool and the
+    /// metadata of the pool mint, updatable by the admin. Pool tokens are unwrapped
+    /// by `RequestUnwrap` and `ClaimUnwrap` when `unwrap_delay` isn't 0. For a
+    /// fixed-term pool, token X is wrapped until `subscription_deadline` and pool
+    /// tokens are unwrapped from `maturity`, unix timestamps that are 0 for none
+    ///
+    /// Accounts:
+    /// [WS] Admin, pays for the new accounts
+    /// [WS] Pool mint, a new account
+    /// [W] Pool state
+    /// [W] Vault (token X)
+    /// [R] Token X mint
+    /// [W] Metadata account, derived from the pool mint by the metadata program
+    /// [R] Metadata program
+    /// [R] Rent sysvar
+    /// [R] System program
+    /// [R] Token program id
+    InitializePool {
+        name: String,
+        symbol: String,
+        uri: String,
+        mode: PoolMode,
+        unwrap_delay: u32,
+        subscription_deadline: i64,
+        maturity: i64,
+    },
+    /// Replaces the name, symbol and URI in the metadata of the pool mint
+    ///
+    /// Accounts:
+    /// [RS] Admin
+    /// [R] Pool state
+    /// [W] Metadata account
+    /// [R] Metadata program
+    UpdatePoolMetadata {
+        name: String,
+        symbol: String,
+        uri: String,
+    },
+    /// Moves the token X of the vault that no pool token represents, such as tokens
+    /// transferred to the vault directly, to the treasury account. Not available in
+    /// `Shares` mode, where such tokens raise the value of the shares
+    ///
+    /// Accounts:
+    /// [RS] Admin
+    /// [R] Pool state
+    /// [R] Pool mint
+    /// [W] Vault (token X)
+    /// [W] Treasury account (token X)
+    /// [R] Token program id
+    Skim,
+    /// Burns `amount` pool tokens of a matured pool created by `InitializePool` for
+    /// a new withdrawal ticket `nonce` of the owner, claimable by `ClaimUnwrap` once
+    /// the unwrap delay of the pool has passed. The `ChangeResult` is set as return data
+    ///
+    /// Accounts:
+    /// [WS] Owner, pays for the withdrawal ticket
+    /// [W] Source account (pool token)
+    /// [W] Pool mint
+    /// [W] Pool state
+    /// [R] Vault (token X)
+    /// [W] Withdrawal ticket, a new account
+    /// [R] Clock sysvar
+    /// [R] Rent sysvar
+    /// [R] System program
+    /// [R] Token program id
+    RequestUnwrap { amount: u64, nonce: u64 },
+    /// Sends the token X of an unlocked withdrawal ticket to the destination and
+    /// closes the ticket
+    ///
+    /// Accounts:
+    /// [WS] Owner, receives the lamports of the withdrawal ticket
+    /// [W] Destination account (token X)
+    /// [W] Pool state
+    /// [W] Vault (token X)
+    /// [W] Withdrawal ticket
+    /// [R] Clock sysvar
+    /// [R] Token program id
+    ClaimUnwrap,
+}
+
+/// Role of an account in an instruction
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct AccountSpec {
+    pub name: &'static str,
+    pub writable: bool,
+    pub signer: bool,
+    /// Trailing account that may be left out
+    #[serde(skip_serializing_if = "is_false")]
+    pub optional: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+const fn account(name: &'static str, writable: bool, signer: bool) -> AccountSpec {
+    AccountSpec {
+        name,
+        writable,
+        signer,
+        optional: false,
+    }
+}
+
+const fn optional_account(name: &'static str, writable: bool, signer: bool) -> AccountSpec {
+    AccountSpec {
+        optional: true,
+        ..account(name, writable, signer)
+    }
+}
+
+/// Accounts of `ChangeXtoY`, in order. The authority must be writable when it
+/// pays for a new pool wallet or destination account, and the associated token
+/// account program is only needed to create the destination account
+pub const CHANGE_X_TO_Y_ACCOUNTS: [AccountSpec; 11] = [
+    account("User transfer authority", false, true),
+    account("Source account (token X)", true, false),
+    account("Token X mint", false, false),
+    account("Destination account (pool token)", true, false),
+    account("Pool mint", true, false),
+    account("Pool authority", false, false),
+    account("Pool wallet (token X)", true, false),
+    account("Rent sysvar", false, false),
+    account("System program", false, false),
+    account("Token program id", false, false),
+    optional_account("Associated token account program id", false, false),
+];
+
+/// Accounts of `ChangeYtoX`, in order. The pool state is only passed for pools
+/// created by `InitializePool`
+pub const CHANGE_Y_TO_X_ACCOUNTS: [AccountSpec; 7] = [
+    account("User transfer authority", false, true),
+    account("Destination account (token X)", true, false),
+    account("Source account (pool token)", true, false),
+    account("Pool mint", true, false),
+    account("Pool wallet (token X)", true, false),
+    account("Token program id", false, false),
+    optional_account("Pool state", false, false),
+];
+
+/// Accounts of `Quote`, in order
+pub const QUOTE_ACCOUNTS: [AccountSpec; 4] = [
+    account("User account (token X)", false, false),
+    account("Pool mint", false, false),
+    account("Pool authority", false, false),
+    account("Pool wallet (token X)", false, false),
+];
+
+/// Accounts of `VerifySolvency`, in order
+pub const VERIFY_SOLVENCY_ACCOUNTS: [AccountSpec; 3] = [
+    account("Pool state", false, false),
+    account("Pool mint", false, false),
+    account("Vault (token X)", false, false),
+];
+
+/// Accounts of `WrapWithVesting`, in order. The first ones are parsed as the
+/// required accounts of `ChangeXtoY`
+pub const WRAP_WITH_VESTING_ACCOUNTS: [AccountSpec; 13] = [
+    account("Grantor", true, true),
+    account("Source account (token X)", true, false),
+    account("Token X mint", false, false),
+    account("Escrow account (pool token)", true, false),
+    account("Pool mint", true, false),
+    account("Pool authority", false, false),
+    account("Pool wallet (token X)", true, false),
+    account("Rent sysvar", false, false),
+    account("System program", false, false),
+    account("Token program id", false, false),
+    account("Vesting account", true, true),
+    account("Beneficiary", false, false),
+    account("Clock sysvar", false, false),
+];
+
+/// Accounts of `ClaimVested`, in order
+pub const CLAIM_VESTED_ACCOUNTS: [AccountSpec; 6] = [
+    account("Beneficiary", false, true),
+    account("Vesting account", true, false),
+    account("Escrow account (pool token)", true, false),
+    account("Destination account (pool token)", true, false),
+    account("Clock sysvar", false, false),
+    account("Token program id", false, false),
+];
+
+/// Accounts of `RevokeVesting`, in order
+pub const REVOKE_VESTING_ACCOUNTS: [AccountSpec; 6] = [
+    account("Grantor", false, true),
+    account("Vesting account", true, false),
+    account("Escrow account (pool token)", true, false),
+    account("Destination account (pool token)", true, false),
+    account("Clock sysvar", false, false),
+    account("Token program id", false, false),
+];
+
+/// Accounts of `InitializePool`, in order
+pub const INITIALIZE_POOL_ACCOUNTS: [AccountSpec; 10] = [
+    account("Admin", true, true),
+    account("Pool mint", true, true),
+    account("Pool state", true, false),
+    account("Vault (token X)", true, false),
+    account("Token X mint", false, false),
+    account("Metadata account", true, false),
+    account("Metadata program", false, false),
+    account("Rent sysvar", false, false),
+    account("System program", false, false),
+    account("Token program id", false, false),
+];
+
+/// Accounts of `UpdatePoolMetadata`, in order
+pub const UPDATE_POOL_METADATA_ACCOUNTS: [AccountSpec; 4] = [
+    account("Admin", false, true),
+    account("Pool state", false, false),
+    account("Metadata account", true, false),
+    account("Metadata program", false, false),
+];
+
+/// Accounts of `Skim`, in order
+pub const SKIM_ACCOUNTS: [AccountSpec; 6] = [
+    account("Admin", false, true),
+    account("Pool state", false, false),
+    account("Pool mint", false, false),
+    account("Vault (token X)", true, false),
+    account("Treasury account (token X)", true, false),
+    account("Token program id", false, false),
+];
+
+/// Accounts of `RequestUnwrap`, in order
+pub const REQUEST_UNWRAP_ACCOUNTS: [AccountSpec; 10] = [
+    account("Owner", true, true),
+    account("Source account (pool token)", true, false),
+    account("Pool mint", true, false),
+    account("Pool state", true, false),
+    account("Vault (token X)", false, false),
+    account("Withdrawal ticket", true, false),
+    account("Clock sysvar", false, false),
+    account("Rent sysvar", false, false),
+    account("System program", false, false),
+    account("Token program id", false, false),
+];
+
+/// Accounts of `ClaimUnwrap`, in order
+pub const CLAIM_UNWRAP_ACCOUNTS: [AccountSpec; 7] = [
+    account("Owner", true, true),
+    account("Destination account (token X)", true, false),
+    account("Pool state", true, false),
+    account("Vault (token X)", true, false),
+    account("Withdrawal ticket", true, false),
+    account("Clock sysvar", false, false),
+    account("Token program id", false, false),
+];
+
+/// Borsh encoding of the instructions before the data was versioned
+#[derive(BorshDeserialize)]
+enum LegacyInstruction {
+    ChangeXtoY { amount: u64 },
+    ChangeYtoX { amount: u64 },
+}
+
+impl From<LegacyInstruction> for ContractInstruction {
+    fn from(instruction: LegacyInstruction) -> Self {
+        match instruction {
+            LegacyInstruction::ChangeXtoY { amount } => ContractInstruction::ChangeXtoY { amount },
+            LegacyInstruction::ChangeYtoX { amount } => ContractInstruction::ChangeYtoX { amount },
+        }
+    }
+}
+
+impl ContractInstruction {
+    /// Decodes versioned data, or the legacy encoding of `ChangeXtoY` and `ChangeYtoX`
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        match input {
+            [INSTRUCTION_VERSION, tag, args @ ..] => Self::unpack_args(*tag, args),
+            _ => LegacyInstruction::try_from_slice(input).map(Into::into),
+        }
+        .map_err(|_| CrateError::InvalidInstruction.into())
+    }
+
+    fn unpack_args(tag: u8, args: &[u8]) -> std::io::Result<Self> {
+        Ok(match tag {
+            CHANGE_X_TO_Y_TAG => ContractInstruction::ChangeXtoY {
+                amount: u64::try_from_slice(args)?,
+            },
+            CHANGE_Y_TO_X_TAG => ContractInstruction::ChangeYtoX {
+                amount: u64::try_from_slice(args)?,
+            },
+            QUOTE_TAG => {
+                let (amount, direction) = <(u64, ChangeDirection)>::try_from_slice(args)?;
+                ContractInstruction::Quote { amount, direction }
+            }
+            VERIFY_SOLVENCY_TAG if args.is_empty() => ContractInstruction::VerifySolvency,
+            CHANGE_X_TO_Y_ALL_TAG if args.is_empty() => ContractInstruction::ChangeXtoYAll,
+            CHANGE_Y_TO_X_ALL_TAG if args.is_empty() => ContractInstruction::ChangeYtoXAll,
+            WRAP_WITH_VESTING_TAG => {
+                let (amount, cliff_ts, end_ts) = <(u64, i64, i64)>::try_from_slice(args)?;
+                ContractInstruction::WrapWithVesting {
+                    amount,
+                    cliff_ts,
+                    end_ts,
+                }
+            }
+            CLAIM_VESTED_TAG if args.is_empty() => ContractInstruction::ClaimVested,
+            REVOKE_VESTING_TAG if args.is_empty() => ContractInstruction::RevokeVesting,
+            SKIM_TAG if args.is_empty() => ContractInstruction::Skim,
+            REQUEST_UNWRAP_TAG => {
+                let (amount, nonce) = <(u64, u64)>::try_from_slice(args)?;
+                ContractInstruction::RequestUnwrap { amount, nonce }
+            }
+            CLAIM_UNWRAP_TAG if args.is_empty() => ContractInstruction::ClaimUnwrap,
+            CHANGE_X_TO_Y_EXACT_OUT_TAG => {
+                let (amount_out, maximum_in) = <(u64, u64)>::try_from_slice(args)?;
+                ContractInstruction::ChangeXtoYExactOut {
+                    amount_out,
+                    maximum_in,
+                }
+            }
+            CHANGE_Y_TO_X_EXACT_OUT_TAG => {
+                let (amount_out, maximum_in) = <(u64, u64)>::try_from_slice(args)?;
+                ContractInstruction::ChangeYtoXExactOut {
+                    amount_out,
+                    maximum_in,
+                }
+            }
+            INITIALIZE_POOL_TAG => {
+                let (name, symbol, uri, mode, unwrap_delay, subscription_deadline, maturity) =
+                    <(String, String, String, PoolMode, u32, i64, i64)>::try_from_slice(args)?;
+                ContractInstruction::InitializePool {
+                    name,
+                    symbol,
+                    uri,
+                    mode,
+                    unwrap_delay,
+                    subscription_deadline,
+                    maturity,
+                }
+            }
+            UPDATE_POOL_METADATA_TAG => {
+                let (name, symbol, uri) = <(String, String, String)>::try_from_slice(args)?;
+                ContractInstruction::UpdatePoolMetadata { name, symbol, uri }
+            }
+            _ => return Err(std::io::ErrorKind::InvalidData.into()),
+        })
+    }
+
+    /// Encodes the instruction with the current version
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![INSTRUCTION_VERSION, self.tag()];
+        let args = match self {
+            ContractInstruction::ChangeXtoY { amount } => amount.try_to_vec(),
+            ContractInstruction::ChangeYtoX { amount } => amount.try_to_vec(),
+            ContractInstruction::Quote { amount, direction } => (amount, direction).try_to_vec(),
+            ContractInstruction::VerifySolvency
+            | ContractInstruction::ChangeXtoYAll
+            | ContractInstruction::ChangeYtoXAll
+            | ContractInstruction::ClaimVested
+            | ContractInstruction::RevokeVesting
+            | ContractInstruction::Skim
+            | ContractInstruction::ClaimUnwrap => Ok(vec![]),
+            ContractInstruction::WrapWithVesting {
+                amount,
+                cliff_ts,
+                end_ts,
+            } => (amount, cliff_ts, end_ts).try_to_vec(),
+            ContractInstruction::ChangeXtoYExactOut {
+                amount_out,
+                maximum_in,
+            }
+            | ContractInstruction::ChangeYtoXExactOut {
+                amount_out,
+                maximum_in,
+            } => (amount_out, maximum_in).try_to_vec(),
+            ContractInstruction::InitializePool {
+                name,
+                symbol,
+                uri,
+                mode,
+                unwrap_delay,
+                subscription_deadline,
+                maturity,
+            } => (
+                name,
+                symbol,
+                uri,
+                mode,
+                unwrap_delay,
+                subscription_deadline,
+                maturity,
+            )
+                .try_to_vec(),
+            ContractInstruction::RequestUnwrap { amount, nonce } => (amount, nonce).try_to_vec(),
+            ContractInstruction::UpdatePoolMetadata { name, symbol, uri } => {
+                (name, symbol, uri).try_to_vec()
+            }
+        };
+        data.extend(args.unwrap());
+        data
+    }
+
+    /// Tag following the version byte
+    pub fn tag(&self) -> u8 {
+        match self {
+            ContractInstruction::ChangeXtoY { .. } => CHANGE_X_TO_Y_TAG,
+            ContractInstruction::ChangeYtoX { .. } => CHANGE_Y_TO_X_TAG,
+            ContractInstruction::Quote { .. } => QUOTE_TAG,
+            ContractInstruction::VerifySolvency => VERIFY_SOLVENCY_TAG,
+            ContractInstruction::ChangeXtoYExactOut { .. } => CHANGE_X_TO_Y_EXACT_OUT_TAG,
+            ContractInstruction::ChangeYtoXExactOut { .. } => CHANGE_Y_TO_X_EXACT_OUT_TAG,
+            ContractInstruction::ChangeXtoYAll => CHANGE_X_TO_Y_ALL_TAG,
+            ContractInstruction::ChangeYtoXAll => CHANGE_Y_TO_X_ALL_TAG,
+            ContractInstruction::WrapWithVesting { .. } => WRAP_WITH_VESTING_TAG,
+            ContractInstruction::ClaimVested => CLAIM_VESTED_TAG,
+            ContractInstruction::RevokeVesting => REVOKE_VESTING_TAG,
+            ContractInstruction::InitializePool { .. } => INITIALIZE_POOL_TAG,
+            ContractInstruction::UpdatePoolMetadata { .. } => UPDATE_POOL_METADATA_TAG,
+            ContractInstruction::Skim => SKIM_TAG,
+            ContractInstruction::RequestUnwrap { .. } => REQUEST_UNWRAP_TAG,
+            ContractInstruction::ClaimUnwrap => CLAIM_UNWRAP_TAG,
+        }
+    }
+
+    /// Name of the instruction variant
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContractInstruction::ChangeXtoY { .. } => "ChangeXtoY",
+            ContractInstruction::ChangeYtoX { .. } => "ChangeYtoX",
+            ContractInstruction::Quote { .. } => "Quote",
+            ContractInstruction::VerifySolvency => "VerifySolvency",
+            ContractInstruction::ChangeXtoYExactOut { .. } => "ChangeXtoYExactOut",
+            ContractInstruction::ChangeYtoXExactOut { .. } => "ChangeYtoXExactOut",
+            ContractInstruction::ChangeXtoYAll => "ChangeXtoYAll",
+            ContractInstruction::ChangeYtoXAll => "ChangeYtoXAll",
+            ContractInstruction::WrapWithVesting { .. } => "WrapWithVesting",
+            ContractInstruction::ClaimVested => "ClaimVested",
+            ContractInstruction::RevokeVesting => "RevokeVesting",
+            ContractInstruction::InitializePool { .. } => "InitializePool",
+            ContractInstruction::UpdatePoolMetadata { .. } => "UpdatePoolMetadata",
+            ContractInstruction::Skim => "Skim",
+            ContractInstruction::RequestUnwrap { .. } => "RequestUnwrap",
+            ContractInstruction::ClaimUnwrap => "ClaimUnwrap",
+        }
+    }
+
+    /// Accounts expected by the instruction, as listed in its docs
+    pub fn accounts(&self) -> &'static [AccountSpec] {
+        match self {
+            ContractInstruction::ChangeXtoY { .. }
+            | ContractInstruction::ChangeXtoYExactOut { .. }
+            | ContractInstruction::ChangeXtoYAll => &CHANGE_X_TO_Y_ACCOUNTS,
+            ContractInstruction::ChangeYtoX { .. }
+            | ContractInstruction::ChangeYtoXExactOut { .. }
+            | ContractInstruction::ChangeYtoXAll => &CHANGE_Y_TO_X_ACCOUNTS,
+            ContractInstruction::Quote { .. } => &QUOTE_ACCOUNTS,
+            ContractInstruction::VerifySolvency => &VERIFY_SOLVENCY_ACCOUNTS,
+            ContractInstruction::WrapWithVesting { .. } => &WRAP_WITH_VESTING_ACCOUNTS,
+            ContractInstruction::ClaimVested => &CLAIM_VESTED_ACCOUNTS,
+            ContractInstruction::RevokeVesting => &REVOKE_VESTING_ACCOUNTS,
+            ContractInstruction::InitializePool { .. } => &INITIALIZE_POOL_ACCOUNTS,
+            ContractInstruction::UpdatePoolMetadata { .. } => &UPDATE_POOL_METADATA_ACCOUNTS,
+            ContractInstruction::Skim => &SKIM_ACCOUNTS,
+            ContractInstruction::RequestUnwrap { .. } => &REQUEST_UNWRAP_ACCOUNTS,
+            ContractInstruction::ClaimUnwrap => &CLAIM_UNWRAP_ACCOUNTS,
+        }
+    }
+}
+
+/// Direction of a quoted change
+#[derive(Clone, Copy, Debug, BorshDeserialize, BorshSerialize, PartialEq, Serialize)]
+pub enum ChangeDirection {
+    /// Token X to pool token, as in `ChangeXtoY`
+    XtoY,
+    /// Pool token to token X, as in `ChangeYtoX`
+    YtoX,
+}
+
+/// Return data of `ChangeXtoY`, `ChangeYtoX`, their variants, `WrapWithVesting`
+/// and `Quote`
+#[derive(Debug, BorshDeserialize, BorshSerialize, PartialEq, Serialize)]
+pub struct ChangeResult {
+    /// Tokens taken from the user
+    pub amount_in: u64,
+    /// Tokens minted or sent to the user
+    pub amount_out: u64,
+}
+
+/// Reads the `ChangeResult` of the last instruction invoked by a CPI caller,
+/// fails if the return data wasn't set by `program_id`
+pub fn get_change_result(program_id: &Pubkey) -> Result<ChangeResult, ProgramError> {
+    match get_return_data() {
+        Some((return_program_id, data)) if return_program_id == *program_id => {
+            Ok(ChangeResult::try_from_slice(&data)?)
+        }
+        _ => Err(CrateError::InvalidReturnData.into()),
+    }
 }
 
 pub fn change_x_to_y(
@@ -38,28 +648,141 @@ pub fn change_x_to_y(
     pool_mint_id: &Pubkey,
     amount: u64,
 ) -> Instruction {
-    let (pool_wallet_x_id, _) = find_program_address(program_id, user_wallet_x_id);
     let (pool_mint_authority_id, _) = find_program_address(program_id, pool_mint_id);
+    let (pool_wallet_x_id, _) = find_program_address(program_id, user_wallet_x_id);
+    change_x_to_y_with(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        token_x_mint_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        &pool_mint_authority_id,
+        &pool_wallet_x_id,
+        amount,
+    )
+}
+
+/// Same as `change_x_to_y`, for the pool created by `InitializePool` for
+/// `pool_mint_id`: the pool state is the pool authority and the vault the pool wallet
+pub fn change_x_to_y_pool(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (pool_id, _) = find_pool_address(program_id, pool_mint_id);
+    let (vault_id, _) = find_program_address(program_id, &pool_id);
+    change_x_to_y_with(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        token_x_mint_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        &pool_id,
+        &vault_id,
+        amount,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn change_x_to_y_with(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    pool_mint_authority_id: &Pubkey,
+    pool_wallet_x_id: &Pubkey,
+    amount: u64,
+) -> Instruction {
     let accounts = vec![
-        AccountMeta::new_readonly(*user_wallets_authority_id, true),
+        AccountMeta::new(*user_wallets_authority_id, true),
         AccountMeta::new(*user_wallet_x_id, false),
-        AccountMeta::new(*token_x_mint_id, false),
+        AccountMeta::new_readonly(*token_x_mint_id, false),
         AccountMeta::new(*user_wallet_y_id, false),
         AccountMeta::new(*pool_mint_id, false),
-        AccountMeta::new_readonly(pool_mint_authority_id, false),
-        AccountMeta::new(pool_wallet_x_id, false),
+        AccountMeta::new_readonly(*pool_mint_authority_id, false),
+        AccountMeta::new(*pool_wallet_x_id, false),
         AccountMeta::new_readonly(sysvar::rent::id(), false),
         AccountMeta::new_readonly(solana_program::system_program::id(), false),
         AccountMeta::new_readonly(spl_token::id(), false),
     ];
 
-    Instruction::new_with_borsh(
+    Instruction::new_with_bytes(
         *program_id,
-        &ContractInstruction::ChangeXtoY { amount },
+        &ContractInstruction::ChangeXtoY { amount }.pack(),
         accounts,
     )
 }
 
+/// Same as `change_x_to_y`, but pool tokens go to the associated token account of
+/// `user_wallets_authority_id`, which is created by the program if needed
+pub fn change_x_to_y_associated(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let user_wallet_y_id = spl_associated_token_account::get_associated_token_address(
+        user_wallets_authority_id,
+        pool_mint_id,
+    );
+
+    let mut instruction = change_x_to_y(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        token_x_mint_id,
+        &user_wallet_y_id,
+        pool_mint_id,
+        amount,
+    );
+    instruction.accounts.push(AccountMeta::new_readonly(
+        spl_associated_token_account::id(),
+        false,
+    ));
+    instruction
+}
+
+/// Same as `change_x_to_y_associated`, for the pool created by `InitializePool` for
+/// `pool_mint_id`
+pub fn change_x_to_y_associated_pool(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let user_wallet_y_id = spl_associated_token_account::get_associated_token_address(
+        user_wallets_authority_id,
+        pool_mint_id,
+    );
+
+    let mut instruction = change_x_to_y_pool(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        token_x_mint_id,
+        &user_wallet_y_id,
+        pool_mint_id,
+        amount,
+    );
+    instruction.accounts.push(AccountMeta::new_readonly(
+        spl_associated_token_account::id(),
+        false,
+    ));
+    instruction
+}
+
 pub fn change_y_to_x(
     program_id: &Pubkey,
     user_wallets_authority_id: &Pubkey,
@@ -69,19 +792,653 @@ pub fn change_y_to_x(
     amount: u64,
 ) -> Instruction {
     let (pool_wallet_x_id, _) = find_program_address(program_id, user_wallet_x_id);
+    change_y_to_x_with(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        &pool_wallet_x_id,
+        None,
+        amount,
+    )
+}
 
-    let accounts = vec![
+/// Same as `change_y_to_x`, for the pool created by `InitializePool` for
+/// `pool_mint_id`: the vault is the pool wallet and the pool state is passed
+pub fn change_y_to_x_pool(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (pool_id, _) = find_pool_address(program_id, pool_mint_id);
+    let (vault_id, _) = find_program_address(program_id, &pool_id);
+    change_y_to_x_with(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        &vault_id,
+        Some(&pool_id),
+        amount,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn change_y_to_x_with(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    pool_wallet_x_id: &Pubkey,
+    pool_id: Option<&Pubkey>,
+    amount: u64,
+) -> Instruction {
+    let mut accounts = vec![
         AccountMeta::new_readonly(*user_wallets_authority_id, true),
         AccountMeta::new(*user_wallet_x_id, false),
         AccountMeta::new(*user_wallet_y_id, false),
         AccountMeta::new(*pool_mint_id, false),
-        AccountMeta::new(pool_wallet_x_id, false),
+        AccountMeta::new(*pool_wallet_x_id, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    if let Some(pool_id) = pool_id {
+        accounts.push(AccountMeta::new_readonly(*pool_id, false));
+    }
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &ContractInstruction::ChangeYtoX { amount }.pack(),
+        accounts,
+    )
+}
+
+/// Same as `change_x_to_y`, but mints exactly `amount_out` pool tokens for at most
+/// `maximum_in` token X
+#[allow(clippy::too_many_arguments)]
+pub fn change_x_to_y_exact_out(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount_out: u64,
+    maximum_in: u64,
+) -> Instruction {
+    let mut instruction = change_x_to_y(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        token_x_mint_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        amount_out,
+    );
+    instruction.data = ContractInstruction::ChangeXtoYExactOut {
+        amount_out,
+        maximum_in,
+    }
+    .pack();
+    instruction
+}
+
+/// Same as `change_x_to_y_exact_out`, for the pool created by `InitializePool` for
+/// `pool_mint_id`
+#[allow(clippy::too_many_arguments)]
+pub fn change_x_to_y_exact_out_pool(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount_out: u64,
+    maximum_in: u64,
+) -> Instruction {
+    let mut instruction = change_x_to_y_pool(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        token_x_mint_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        amount_out,
+    );
+    instruction.data = ContractInstruction::ChangeXtoYExactOut {
+        amount_out,
+        maximum_in,
+    }
+    .pack();
+    instruction
+}
+
+/// Same as `change_y_to_x`, but sends exactly `amount_out` token X for at most
+/// `maximum_in` pool tokens
+pub fn change_y_to_x_exact_out(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount_out: u64,
+    maximum_in: u64,
+) -> Instruction {
+    let mut instruction = change_y_to_x(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        amount_out,
+    );
+    instruction.data = ContractInstruction::ChangeYtoXExactOut {
+        amount_out,
+        maximum_in,
+    }
+    .pack();
+    instruction
+}
+
+/// Same as `change_y_to_x_exact_out`, for the pool created by `InitializePool` for
+/// `pool_mint_id`
+pub fn change_y_to_x_exact_out_pool(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount_out: u64,
+    maximum_in: u64,
+) -> Instruction {
+    let mut instruction = change_y_to_x_pool(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        amount_out,
+    );
+    instruction.data = ContractInstruction::ChangeYtoXExactOut {
+        amount_out,
+        maximum_in,
+    }
+    .pack();
+    instruction
+}
+
+/// Same as `change_x_to_y`, with the whole balance of `user_wallet_x_id`
+pub fn change_x_to_y_all(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+) -> Instruction {
+    let mut instruction = change_x_to_y(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        token_x_mint_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        0,
+    );
+    instruction.data = ContractInstruction::ChangeXtoYAll.pack();
+    instruction
+}
+
+/// Same as `change_x_to_y_all`, for the pool created by `InitializePool` for
+/// `pool_mint_id`
+pub fn change_x_to_y_all_pool(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+) -> Instruction {
+    let mut instruction = change_x_to_y_pool(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        token_x_mint_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        0,
+    );
+    instruction.data = ContractInstruction::ChangeXtoYAll.pack();
+    instruction
+}
+
+/// Same as `change_y_to_x`, with the whole balance of `user_wallet_y_id`
+pub fn change_y_to_x_all(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+) -> Instruction {
+    let mut instruction = change_y_to_x(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        0,
+    );
+    instruction.data = ContractInstruction::ChangeYtoXAll.pack();
+    instruction
+}
+
+/// Same as `change_y_to_x_all`, for the pool created by `InitializePool` for
+/// `pool_mint_id`
+pub fn change_y_to_x_all_pool(
+    program_id: &Pubkey,
+    user_wallets_authority_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+) -> Instruction {
+    let mut instruction = change_y_to_x_pool(
+        program_id,
+        user_wallets_authority_id,
+        user_wallet_x_id,
+        user_wallet_y_id,
+        pool_mint_id,
+        0,
+    );
+    instruction.data = ContractInstruction::ChangeYtoXAll.pack();
+    instruction
+}
+
+/// Wraps token X into pool tokens vesting for `beneficiary_id`, the escrow account
+/// is derived from `vesting_id`, a new account signing the transaction
+#[allow(clippy::too_many_arguments)]
+pub fn wrap_with_vesting(
+    program_id: &Pubkey,
+    grantor_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    vesting_id: &Pubkey,
+    beneficiary_id: &Pubkey,
+    amount: u64,
+    cliff_ts: i64,
+    end_ts: i64,
+) -> Instruction {
+    let (escrow_id, _) = find_program_address(program_id, vesting_id);
+    let instruction = change_x_to_y(
+        program_id,
+        grantor_id,
+        user_wallet_x_id,
+        token_x_mint_id,
+        &escrow_id,
+        pool_mint_id,
+        amount,
+    );
+    with_vesting(
+        instruction,
+        amount,
+        vesting_id,
+        beneficiary_id,
+        cliff_ts,
+        end_ts,
+    )
+}
+
+/// Same as `wrap_with_vesting`, for the pool created by `InitializePool` for
+/// `pool_mint_id`
+#[allow(clippy::too_many_arguments)]
+pub fn wrap_with_vesting_pool(
+    program_id: &Pubkey,
+    grantor_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    vesting_id: &Pubkey,
+    beneficiary_id: &Pubkey,
+    amount: u64,
+    cliff_ts: i64,
+    end_ts: i64,
+) -> Instruction {
+    let (escrow_id, _) = find_program_address(program_id, vesting_id);
+    let instruction = change_x_to_y_pool(
+        program_id,
+        grantor_id,
+        user_wallet_x_id,
+        token_x_mint_id,
+        &escrow_id,
+        pool_mint_id,
+        amount,
+    );
+    with_vesting(
+        instruction,
+        amount,
+        vesting_id,
+        beneficiary_id,
+        cliff_ts,
+        end_ts,
+    )
+}
+
+/// Turns a `ChangeXtoY` of `amount` to the escrow of `vesting_id` into `WrapWithVesting`
+fn with_vesting(
+    mut instruction: Instruction,
+    amount: u64,
+    vesting_id: &Pubkey,
+    beneficiary_id: &Pubkey,
+    cliff_ts: i64,
+    end_ts: i64,
+) -> Instruction {
+    instruction.accounts.extend([
+        AccountMeta::new(*vesting_id, true),
+        AccountMeta::new_readonly(*beneficiary_id, false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+    ]);
+    instruction.data = ContractInstruction::WrapWithVesting {
+        amount,
+        cliff_ts,
+        end_ts,
+    }
+    .pack();
+    instruction
+}
+
+/// Sends the vested pool tokens of `vesting_id` to `destination_id`
+pub fn claim_vested(
+    program_id: &Pubkey,
+    beneficiary_id: &Pubkey,
+    vesting_id: &Pubkey,
+    destination_id: &Pubkey,
+) -> Instruction {
+    vesting_instruction(
+        program_id,
+        ContractInstruction::ClaimVested,
+        beneficiary_id,
+        vesting_id,
+        destination_id,
+    )
+}
+
+/// Sends the unvested pool tokens of `vesting_id` to `destination_id`
+pub fn revoke_vesting(
+    program_id: &Pubkey,
+    grantor_id: &Pubkey,
+    vesting_id: &Pubkey,
+    destination_id: &Pubkey,
+) -> Instruction {
+    vesting_instruction(
+        program_id,
+        ContractInstruction::RevokeVesting,
+        grantor_id,
+        vesting_id,
+        destination_id,
+    )
+}
+
+fn vesting_instruction(
+    program_id: &Pubkey,
+    instruction: ContractInstruction,
+    authority_id: &Pubkey,
+    vesting_id: &Pubkey,
+    destination_id: &Pubkey,
+) -> Instruction {
+    let (escrow_id, _) = find_program_address(program_id, vesting_id);
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority_id, true),
+        AccountMeta::new(*vesting_id, false),
+        AccountMeta::new(escrow_id, false),
+        AccountMeta::new(*destination_id, false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    Instruction::new_with_bytes(*program_id, &instruction.pack(), accounts)
+}
+
+pub fn quote(
+    program_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount: u64,
+    direction: ChangeDirection,
+) -> Instruction {
+    let (pool_wallet_x_id, _) = find_program_address(program_id, user_wallet_x_id);
+    let (pool_mint_authority_id, _) = find_program_address(program_id, pool_mint_id);
+    quote_with(
+        program_id,
+        user_wallet_x_id,
+        pool_mint_id,
+        &pool_mint_authority_id,
+        &pool_wallet_x_id,
+        amount,
+        direction,
+    )
+}
+
+/// Same as `quote`, for the pool created by `InitializePool` for `pool_mint_id`
+pub fn quote_pool(
+    program_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount: u64,
+    direction: ChangeDirection,
+) -> Instruction {
+    let (pool_id, _) = find_pool_address(program_id, pool_mint_id);
+    let (vault_id, _) = find_program_address(program_id, &pool_id);
+    quote_with(
+        program_id,
+        user_wallet_x_id,
+        pool_mint_id,
+        &pool_id,
+        &vault_id,
+        amount,
+        direction,
+    )
+}
+
+fn quote_with(
+    program_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    pool_mint_authority_id: &Pubkey,
+    pool_wallet_x_id: &Pubkey,
+    amount: u64,
+    direction: ChangeDirection,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*user_wallet_x_id, false),
+        AccountMeta::new_readonly(*pool_mint_id, false),
+        AccountMeta::new_readonly(*pool_mint_authority_id, false),
+        AccountMeta::new_readonly(*pool_wallet_x_id, false),
+    ];
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &ContractInstruction::Quote { amount, direction }.pack(),
+        accounts,
+    )
+}
+
+/// `VerifySolvency` of the pool created by `InitializePool` for `pool_mint_id`
+pub fn verify_solvency(program_id: &Pubkey, pool_mint_id: &Pubkey) -> Instruction {
+    let (pool_id, _) = find_pool_address(program_id, pool_mint_id);
+    let (vault_id, _) = find_program_address(program_id, &pool_id);
+    let accounts = vec![
+        AccountMeta::new_readonly(pool_id, false),
+        AccountMeta::new_readonly(*pool_mint_id, false),
+        AccountMeta::new_readonly(vault_id, false),
+    ];
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &ContractInstruction::VerifySolvency.pack(),
+        accounts,
+    )
+}
+
+/// Creates the pool of `pool_mint_id`, a new account signing the transaction
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_pool(
+    program_id: &Pubkey,
+    admin_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    token_x_mint_id: &Pubkey,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+    mode: PoolMode,
+    unwrap_delay: u32,
+    subscription_deadline: i64,
+    maturity: i64,
+) -> Instruction {
+    let (pool_id, _) = find_pool_address(program_id, pool_mint_id);
+    let (vault_id, _) = find_program_address(program_id, &pool_id);
+    let (metadata_id, _) = metadata::find_metadata_address(pool_mint_id);
+    let accounts = vec![
+        AccountMeta::new(*admin_id, true),
+        AccountMeta::new(*pool_mint_id, true),
+        AccountMeta::new(pool_id, false),
+        AccountMeta::new(vault_id, false),
+        AccountMeta::new_readonly(*token_x_mint_id, false),
+        AccountMeta::new(metadata_id, false),
+        AccountMeta::new_readonly(metadata::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &ContractInstruction::InitializePool {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            uri: uri.to_string(),
+            mode,
+            unwrap_delay,
+            subscription_deadline,
+            maturity,
+        }
+        .pack(),
+        accounts,
+    )
+}
+
+pub fn update_pool_metadata(
+    program_id: &Pubkey,
+    admin_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+) -> Instruction {
+    let (pool_id, _) = find_pool_address(program_id, pool_mint_id);
+    let (metadata_id, _) = metadata::find_metadata_address(pool_mint_id);
+    let accounts = vec![
+        AccountMeta::new_readonly(*admin_id, true),
+        AccountMeta::new_readonly(pool_id, false),
+        AccountMeta::new(metadata_id, false),
+        AccountMeta::new_readonly(metadata::id(), false),
+    ];
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &ContractInstruction::UpdatePoolMetadata {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            uri: uri.to_string(),
+        }
+        .pack(),
+        accounts,
+    )
+}
+
+/// Moves the excess token X of the vault of the pool of `pool_mint_id` to `treasury_id`
+pub fn skim(
+    program_id: &Pubkey,
+    admin_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    treasury_id: &Pubkey,
+) -> Instruction {
+    let (pool_id, _) = find_pool_address(program_id, pool_mint_id);
+    let (vault_id, _) = find_program_address(program_id, &pool_id);
+    let accounts = vec![
+        AccountMeta::new_readonly(*admin_id, true),
+        AccountMeta::new_readonly(pool_id, false),
+        AccountMeta::new_readonly(*pool_mint_id, false),
+        AccountMeta::new(vault_id, false),
+        AccountMeta::new(*treasury_id, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    Instruction::new_with_bytes(*program_id, &ContractInstruction::Skim.pack(), accounts)
+}
+
+/// Burns `amount` pool tokens of the pool of `pool_mint_id` for the withdrawal
+/// ticket `nonce` of `owner_id`
+pub fn request_unwrap(
+    program_id: &Pubkey,
+    owner_id: &Pubkey,
+    user_wallet_y_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    amount: u64,
+    nonce: u64,
+) -> Instruction {
+    let (pool_id, _) = find_pool_address(program_id, pool_mint_id);
+    let (vault_id, _) = find_program_address(program_id, &pool_id);
+    let (ticket_id, _) = find_ticket_address(program_id, &pool_id, owner_id, nonce);
+    let accounts = vec![
+        AccountMeta::new(*owner_id, true),
+        AccountMeta::new(*user_wallet_y_id, false),
+        AccountMeta::new(*pool_mint_id, false),
+        AccountMeta::new(pool_id, false),
+        AccountMeta::new_readonly(vault_id, false),
+        AccountMeta::new(ticket_id, false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &ContractInstruction::RequestUnwrap { amount, nonce }.pack(),
+        accounts,
+    )
+}
+
+/// Claims the withdrawal ticket `nonce` of `owner_id` in the pool of `pool_mint_id`
+/// to `user_wallet_x_id`
+pub fn claim_unwrap(
+    program_id: &Pubkey,
+    owner_id: &Pubkey,
+    user_wallet_x_id: &Pubkey,
+    pool_mint_id: &Pubkey,
+    nonce: u64,
+) -> Instruction {
+    let (pool_id, _) = find_pool_address(program_id, pool_mint_id);
+    let (vault_id, _) = find_program_address(program_id, &pool_id);
+    let (ticket_id, _) = find_ticket_address(program_id, &pool_id, owner_id, nonce);
+    let accounts = vec![
+        AccountMeta::new(*owner_id, true),
+        AccountMeta::new(*user_wallet_x_id, false),
+        AccountMeta::new(pool_id, false),
+        AccountMeta::new(vault_id, false),
+        AccountMeta::new(ticket_id, false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
         AccountMeta::new_readonly(spl_token::id(), false),
     ];
 
-    Instruction::new_with_borsh(
+    Instruction::new_with_bytes(
         *program_id,
-        &ContractInstruction::ChangeYtoX { amount },
+        &ContractInstruction::ClaimUnwrap.pack(),
         accounts,
     )
 }
@@ -2,9 +2,15 @@ extern crate core;
 
 use solana_program::pubkey::Pubkey;
 
+pub mod context;
+#[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 pub mod error;
+pub mod events;
+pub mod idl;
 pub mod instruction;
+pub mod metadata;
+pub mod parser;
 pub mod processor;
 pub mod state;
 pub mod utils;
@@ -15,3 +21,35 @@ solana_program::declare_id!("6pMk1woyNXh31Ba7wYuwkDeBgWfohTCor1AGVUQAGxXb");
 pub fn find_program_address(program_id: &Pubkey, pubkey: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[&pubkey.to_bytes()[..32]], program_id)
 }
+
+/// First seed of the state of pools created by `InitializePool`, so that it can't
+/// be the pool authority of a pool mint created off-chain
+pub const POOL_SEED: &[u8] = b"pool";
+
+/// Generates the state address and seed bump of the pool created for `pool_mint`
+/// by `InitializePool`. The pool state is also the pool authority of the mint
+pub fn find_pool_address(program_id: &Pubkey, pool_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_SEED, &pool_mint.to_bytes()], program_id)
+}
+
+/// First seed of the withdrawal tickets created by `RequestUnwrap`
+pub const TICKET_SEED: &[u8] = b"ticket";
+
+/// Generates the address and seed bump of the withdrawal ticket `nonce` of `owner`
+/// in the pool whose state is `pool`
+pub fn find_ticket_address(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    owner: &Pubkey,
+    nonce: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TICKET_SEED,
+            &pool.to_bytes(),
+            &owner.to_bytes(),
+            &nonce.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
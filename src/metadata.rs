@@ -0,0 +1,138 @@
+//! Instructions of the Metaplex token metadata program invoked by the wrapper.
+//! They're encoded here since its crate depends on other Solana versions
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program::{system_program, sysvar};
+
+solana_program::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// First seed of metadata accounts, followed by the program id and the mint
+pub const PREFIX: &[u8] = b"metadata";
+
+/// Tags of the instructions in the metadata program
+pub const UPDATE_METADATA_ACCOUNT_V2_TAG: u8 = 15;
+pub const CREATE_METADATA_ACCOUNT_V2_TAG: u8 = 16;
+
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub enum UseMethod {
+    Burn,
+    Multiple,
+    Single,
+}
+
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct Uses {
+    pub use_method: UseMethod,
+    pub remaining: u64,
+    pub total: u64,
+}
+
+/// Metadata of a mint, as shown by wallets
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct DataV2 {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+    pub collection: Option<Collection>,
+    pub uses: Option<Uses>,
+}
+
+impl DataV2 {
+    /// Metadata of a pool mint, which has no royalties, creators, collection or uses
+    pub fn new(name: &str, symbol: &str, uri: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            uri: uri.to_string(),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct CreateMetadataAccountArgsV2 {
+    pub data: DataV2,
+    pub is_mutable: bool,
+}
+
+/// Fields left `None` are kept
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct UpdateMetadataAccountArgsV2 {
+    pub data: Option<DataV2>,
+    pub update_authority: Option<Pubkey>,
+    pub primary_sale_happened: Option<bool>,
+    pub is_mutable: Option<bool>,
+}
+
+/// Generates the metadata account of `mint`
+pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX, &id().to_bytes(), &mint.to_bytes()], &id())
+}
+
+/// Creates the mutable metadata account of `mint_id`, `payer_id` pays for it
+pub fn create_metadata_account_v2(
+    metadata_id: &Pubkey,
+    mint_id: &Pubkey,
+    mint_authority_id: &Pubkey,
+    payer_id: &Pubkey,
+    update_authority_id: &Pubkey,
+    data: DataV2,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*metadata_id, false),
+        AccountMeta::new_readonly(*mint_id, false),
+        AccountMeta::new_readonly(*mint_authority_id, true),
+        AccountMeta::new(*payer_id, true),
+        AccountMeta::new_readonly(*update_authority_id, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    let args = CreateMetadataAccountArgsV2 {
+        data,
+        is_mutable: true,
+    };
+
+    let data = (CREATE_METADATA_ACCOUNT_V2_TAG, args).try_to_vec().unwrap();
+    Instruction::new_with_bytes(id(), &data, accounts)
+}
+
+/// Replaces the metadata of `metadata_id`
+pub fn update_metadata_account_v2(
+    metadata_id: &Pubkey,
+    update_authority_id: &Pubkey,
+    data: DataV2,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*metadata_id, false),
+        AccountMeta::new_readonly(*update_authority_id, true),
+    ];
+    let args = UpdateMetadataAccountArgsV2 {
+        data: Some(data),
+        update_authority: None,
+        primary_sale_happened: None,
+        is_mutable: None,
+    };
+
+    let data = (UPDATE_METADATA_ACCOUNT_V2_TAG, args).try_to_vec().unwrap();
+    Instruction::new_with_bytes(id(), &data, accounts)
+}
@@ -0,0 +1,64 @@
+//! Decoding of the program instructions for explorers and audit tools
+
+use crate::instruction::ContractInstruction;
+use serde::Serialize;
+use solana_program::instruction::Instruction;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+/// Name given to accounts past the ones expected by the instruction
+pub const UNKNOWN_ACCOUNT: &str = "Unknown account";
+
+/// Instruction with its arguments and labelled accounts
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ParsedInstruction {
+    pub program_id: String,
+    pub name: &'static str,
+    pub instruction: ContractInstruction,
+    pub accounts: Vec<ParsedAccount>,
+}
+
+/// Account meta labelled with its role in the instruction
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ParsedAccount {
+    pub name: &'static str,
+    pub pubkey: String,
+    pub writable: bool,
+    pub signer: bool,
+}
+
+/// Decodes an instruction of the program deployed at `program_id`
+pub fn parse_instruction(
+    program_id: &Pubkey,
+    instruction: &Instruction,
+) -> Result<ParsedInstruction, ProgramError> {
+    if instruction.program_id != *program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let contract_instruction = ContractInstruction::unpack(&instruction.data)?;
+    let specs = contract_instruction.accounts();
+    let required = specs.iter().filter(|spec| !spec.optional).count();
+    if instruction.accounts.len() < required {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let name = |i: usize| specs.get(i).map_or(UNKNOWN_ACCOUNT, |spec| spec.name);
+
+    let accounts = instruction
+        .accounts
+        .iter()
+        .enumerate()
+        .map(|(i, meta)| ParsedAccount {
+            name: name(i),
+            pubkey: meta.pubkey.to_string(),
+            writable: meta.is_writable,
+            signer: meta.is_signer,
+        })
+        .collect();
+
+    Ok(ParsedInstruction {
+        program_id: program_id.to_string(),
+        name: contract_instruction.name(),
+        instruction: contract_instruction,
+        accounts,
+    })
+}
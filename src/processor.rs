@@ -1,18 +1,43 @@
+use crate::context::{
+    ChangeXtoYAccounts, ChangeYtoXAccounts, ClaimUnwrapAccounts, InitializePoolAccounts,
+    QuoteAccounts, RequestUnwrapAccounts, SkimAccounts, UpdatePoolMetadataAccounts,
+    VerifySolvencyAccounts, VestingAccounts, WrapWithVestingAccounts,
+};
 use crate::error::CrateError;
-use crate::instruction::ContractInstruction;
-use crate::utils::create_account;
-use borsh::BorshDeserialize;
-use solana_program::account_info::{next_account_info, AccountInfo};
+use crate::events::{ContractEvent, SolvencyEvent, UnwrapEvent, WrapEvent};
+use crate::instruction::{
+    ChangeDirection, ChangeResult, ContractInstruction, CLAIM_VESTED_ACCOUNTS,
+    REVOKE_VESTING_ACCOUNTS,
+};
+use crate::metadata::{self, DataV2};
+use crate::state::{Pool, PoolMode, Rate, Ticket, Vesting, SHARES_DECIMALS_OFFSET};
+use crate::utils::{create_account, create_associated_token_account};
+use crate::{find_program_address, POOL_SEED, TICKET_SEED};
+use borsh::BorshSerialize;
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::msg;
-use solana_program::program::invoke_signed;
+use solana_program::program::{invoke_signed, set_return_data};
 use solana_program::program_error::ProgramError;
 use solana_program::program_option::COption;
 use solana_program::program_pack::{IsInitialized, Pack};
 use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::sysvar::Sysvar;
-use spl_token::state::Account;
+use spl_token::state::{Account, Mint};
+
+/// Amount of a change, as given by the instruction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChangeAmount {
+    /// Exact input
+    In(u64),
+    /// The whole balance of the source account
+    All,
+    /// Exact output, for at most `maximum_in`
+    Out { amount_out: u64, maximum_in: u64 },
+}
+
 /// Program state handler.
 pub struct Processor {}
 
@@ -22,7 +47,7 @@ impl Processor {
         pool_mint_authority_info: &AccountInfo,
     ) -> ProgramResult {
         let mint = if pool_mint_info.owner != &spl_token::id() {
-            return Err(CrateError::IncorrectTokenProgramId.into());
+            return Err(CrateError::ExpectedMint.into());
         } else {
             spl_token::state::Mint::unpack(&pool_mint_info.data.borrow())
                 .map_err(|_| CrateError::ExpectedMint)
@@ -30,33 +55,126 @@ impl Processor {
 
         if let COption::Some(ref pk) = mint.mint_authority {
             if pk != pool_mint_authority_info.key {
-                return Err(CrateError::InvalidOwner.into());
+                return Err(CrateError::InvalidPoolMintAuthority.into());
             }
         } else {
-            return Err(CrateError::InvalidOwner.into());
+            return Err(CrateError::InvalidPoolMintAuthority.into());
         };
         Ok(())
     }
 
+    /// Checks the wallets of a `ChangeXtoY` and returns the token X taken and the
+    /// pool tokens minted at `rate`
     pub fn check_user_wallets(
         user_authority: &AccountInfo,
         user_wallet_x: &AccountInfo,
         user_wallet_y: &AccountInfo,
         pool_mint: &AccountInfo,
-        amount: u64,
-    ) -> ProgramResult {
-        let user_wallet_x = spl_token::state::Account::unpack(&user_wallet_x.data.borrow())?;
-        let user_wallet_y = spl_token::state::Account::unpack(&user_wallet_y.data.borrow())?;
+        amount: ChangeAmount,
+        rate: Rate,
+    ) -> Result<(u64, u64), ProgramError> {
+        let user_wallet_x = spl_token::state::Account::unpack(&user_wallet_x.data.borrow())
+            .map_err(|_| CrateError::ExpectedAccount)?;
+        let user_wallet_y = spl_token::state::Account::unpack(&user_wallet_y.data.borrow())
+            .map_err(|_| CrateError::ExpectedAccount)?;
         if &user_wallet_x.owner != user_authority.key || &user_wallet_y.owner != user_authority.key
         {
-            return Err(CrateError::InvalidOwner.into());
+            return Err(CrateError::InvalidWalletOwner.into());
         }
-        if user_wallet_x.amount < amount {
+        let (amount_in, amount_out) =
+            Self::change_amounts(rate, ChangeDirection::XtoY, amount, user_wallet_x.amount)?;
+        if user_wallet_x.amount < amount_in {
             return Err(CrateError::NotEnoughTokens.into());
         }
         if &user_wallet_y.mint != pool_mint.key {
             return Err(CrateError::IncorrectPoolMint.into());
         }
+        Ok((amount_in, amount_out))
+    }
+
+    /// Input and output of a change at `rate`, both rounded against the user.
+    /// `balance` is the balance of the source account, changed by `ChangeAmount::All`
+    pub fn change_amounts(
+        rate: Rate,
+        direction: ChangeDirection,
+        amount: ChangeAmount,
+        balance: u64,
+    ) -> Result<(u64, u64), ProgramError> {
+        let (amount_in, amount_out) = match amount {
+            ChangeAmount::In(amount_in) => (amount_in, rate.amount_out(direction, amount_in)?),
+            ChangeAmount::All => (balance, rate.amount_out(direction, balance)?),
+            ChangeAmount::Out {
+                amount_out,
+                maximum_in,
+            } => {
+                let amount_in = rate.amount_in(direction, amount_out)?;
+                if amount_in > maximum_in {
+                    msg!("Error: {} required, at most {}", amount_in, maximum_in);
+                    return Err(CrateError::MaximumInExceeded.into());
+                }
+                (amount_in, amount_out)
+            }
+        };
+        if amount_in > 0 && amount_out == 0 {
+            return Err(CrateError::ZeroAmountOut.into());
+        }
+        Ok((amount_in, amount_out))
+    }
+
+    /// Rate of `pool`, 1:1 for the pools created off-chain. `vault_info` is the
+    /// vault of the pool
+    fn pool_rate(
+        pool: Option<&Pool>,
+        pool_mint_info: &AccountInfo,
+        vault_info: &AccountInfo,
+    ) -> Result<Rate, ProgramError> {
+        match pool {
+            Some(pool) if pool.mode == PoolMode::Shares => {
+                let pool_mint = Mint::unpack(&pool_mint_info.data.borrow())
+                    .map_err(|_| CrateError::ExpectedMint)?;
+                let vault = Account::unpack(&vault_info.data.borrow())
+                    .map_err(|_| CrateError::ExpectedAccount)?;
+                Ok(Rate::Shares {
+                    assets: vault.amount.saturating_sub(pool.pending_unwrap),
+                    supply: pool_mint.supply,
+                })
+            }
+            _ => Ok(Rate::Fixed),
+        }
+    }
+
+    /// Fails for the pools unwrapping through withdrawal tickets
+    fn check_no_unwrap_delay(pool: Option<&Pool>) -> ProgramResult {
+        match pool {
+            Some(pool) if pool.unwrap_delay > 0 => Err(CrateError::UnwrapDelayed.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails outside of the windows of a fixed-term pool: token X is wrapped until
+    /// the subscription deadline and pool tokens are unwrapped from the maturity
+    fn check_term(pool: Option<&Pool>, direction: ChangeDirection) -> ProgramResult {
+        match (pool, direction) {
+            (Some(pool), ChangeDirection::XtoY) if pool.subscription_deadline != 0 => {
+                let now = Clock::get()?.unix_timestamp;
+                if now > pool.subscription_deadline {
+                    msg!(
+                        "Error: subscription closed at {}, now {}",
+                        pool.subscription_deadline,
+                        now
+                    );
+                    return Err(CrateError::SubscriptionClosed.into());
+                }
+            }
+            (Some(pool), ChangeDirection::YtoX) if pool.maturity != 0 => {
+                let now = Clock::get()?.unix_timestamp;
+                if now < pool.maturity {
+                    msg!("Error: matures at {}, now {}", pool.maturity, now);
+                    return Err(CrateError::NotMatured.into());
+                }
+            }
+            _ => {}
+        }
         Ok(())
     }
 
@@ -136,6 +254,23 @@ impl Processor {
         invoke_signed(&ix, &[account, mint, rent], signers_seeds)
     }
 
+    /// Issue a spl_token `InitializeMint` instruction.
+    pub fn initialize_mint<'a>(
+        mint: AccountInfo<'a>,
+        authority: &Pubkey,
+        decimals: u8,
+        rent: AccountInfo<'a>,
+    ) -> Result<(), ProgramError> {
+        let ix = spl_token::instruction::initialize_mint(
+            &spl_token::id(),
+            mint.key,
+            authority,
+            None,
+            decimals,
+        )?;
+        invoke_signed(&ix, &[mint, rent], &[])
+    }
+
     // pub fn init_account(program_info: AccountInfo, account_id: &Pubkey, mint_id: &Pubkey, authority_id: &Pubkey, bump_seed: u8) -> Result<(), ProgramError> {
     //     let authority_signature_seeds = [&authority_id[..32], &[bump_seed]];
     //     let signers = &[&authority_signature_seeds[..]];
@@ -143,85 +278,151 @@ impl Processor {
     //     invoke_signed(&ix, &[], signers)
     // }
 
+    /// Wraps token X at the rate of the pool
     pub fn change_x_to_y(
         program_id: &Pubkey,
-        token_x_amount: u64,
+        amount: ChangeAmount,
         accounts: &[AccountInfo],
     ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-
-        let user_wallets_authority_info = next_account_info(account_info_iter)?;
-        let user_wallet_x_info = next_account_info(account_info_iter)?;
-        let token_x_mint_info = next_account_info(account_info_iter)?;
-        let user_wallet_y_info = next_account_info(account_info_iter)?;
-        let pool_mint_info = next_account_info(account_info_iter)?;
-        let pool_mint_authority_info = next_account_info(account_info_iter)?;
-        let pool_wallet_x_info = next_account_info(account_info_iter)?;
-        let rent_info = next_account_info(account_info_iter)?;
-        let rent = &Rent::from_account_info(rent_info)?;
-        let _system_program_info = next_account_info(account_info_iter)?;
-        let _token_program_info = next_account_info(account_info_iter)?;
+        let change_x_to_y = ChangeXtoYAccounts::parse(program_id, accounts)?;
+        let ChangeXtoYAccounts {
+            user_wallets_authority: user_wallets_authority_info,
+            user_wallet_x: user_wallet_x_info,
+            user_wallet_y: user_wallet_y_info,
+            pool_mint: pool_mint_info,
+            pool_mint_authority: pool_mint_authority_info,
+            pool_wallet_x: pool_wallet_x_info,
+            rent: rent_info,
+            system_program: system_program_info,
+            token_program: token_program_info,
+            ..
+        } = change_x_to_y;
+        let pool = change_x_to_y.pool_state.as_ref();
 
-        if !user_wallets_authority_info.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
+        Self::check_mint(pool_mint_info, pool_mint_authority_info)?;
+        Self::check_term(pool, ChangeDirection::XtoY)?;
+        let rate = Self::pool_rate(pool, pool_mint_info, pool_wallet_x_info)?;
+
+        if user_wallet_y_info.owner == &solana_program::system_program::id() {
+            change_x_to_y.check_payer()?;
+            let associated_token_program_info = change_x_to_y.associated_token_program()?;
+            let associated_account_id = spl_associated_token_account::get_associated_token_address(
+                user_wallets_authority_info.key,
+                pool_mint_info.key,
+            );
+            if *user_wallet_y_info.key != associated_account_id {
+                return Err(CrateError::InvalidAssociatedAccount.into());
+            }
+            create_associated_token_account(
+                user_wallets_authority_info.clone(),
+                user_wallet_y_info.clone(),
+                user_wallets_authority_info.clone(),
+                pool_mint_info.clone(),
+                system_program_info.clone(),
+                token_program_info.clone(),
+                rent_info.clone(),
+                associated_token_program_info.clone(),
+            )?;
         }
 
-        Self::check_mint(pool_mint_info, pool_mint_authority_info)?;
-        Self::check_user_wallets(
+        let (token_x_amount, pool_tokens) = Self::check_user_wallets(
             user_wallets_authority_info,
             user_wallet_x_info,
             user_wallet_y_info,
             pool_mint_info,
-            token_x_amount,
+            amount,
+            rate,
         )?;
 
-        if pool_wallet_x_info.owner != &solana_program::system_program::id()
-            && pool_wallet_x_info.owner != &spl_token::id()
-        {
-            return Err(CrateError::AlreadyInUse.into());
-        }
+        Self::wrap(&change_x_to_y, token_x_amount, pool_tokens)?;
+        //
+        //
+        // let program_id = program_id_info.key;
+        //
+        // let user_wallet_x = spl_token::state::Account::unpack(&user_wallet_x_info.data.borrow())
+        //     .map_err(|_| CrateError::ExpectedAccount)?;
+        //
+        // let mint_x_id = user_wallet_x.mint;
+        //
+        // let (token_y_id, bump_seed) =
+        //     Pubkey::find_program_address(&[&mint_x_id[..]], program_id);
+        //
+        // if *authority_info.key != program_wallet_y {
+        //     return Err(CrateError::InvalidProgramAddress.into());
+        // }
+        //
+        // let token_y = Self::unpack_self_token_account(token_y_info, program_id)?;
+        // let pool_mint = Self::unpack_mint(mint_y_info, program_id)?;
 
-        let (pool_wallet_x_authority, bump_seed) =
-            Pubkey::find_program_address(&[&user_wallet_x_info.key.to_bytes()], program_id);
-        if *pool_wallet_x_info.key != pool_wallet_x_authority {
-            msg!("Error: Associated address does not match seed derivation");
-            return Err(ProgramError::InvalidSeeds);
-        }
+        Ok(())
+    }
 
-        let signers_seeds = &[&user_wallet_x_info.key.to_bytes()[..32], &[bump_seed]];
+    /// Moves `token_x_amount` token X into the pool wallet, created if needed, and
+    /// mints `pool_tokens` to the destination, which is already checked
+    fn wrap(
+        change_x_to_y: &ChangeXtoYAccounts,
+        token_x_amount: u64,
+        pool_tokens: u64,
+    ) -> ProgramResult {
+        let ChangeXtoYAccounts {
+            user_wallets_authority: user_wallets_authority_info,
+            user_wallet_x: user_wallet_x_info,
+            token_x_mint: token_x_mint_info,
+            user_wallet_y: user_wallet_y_info,
+            pool_mint: pool_mint_info,
+            pool_mint_authority: pool_mint_authority_info,
+            pool_wallet_x: pool_wallet_x_info,
+            rent: rent_info,
+            pool,
+            pool_mint_authority_bump,
+            pool_wallet_x_bump,
+            ..
+        } = *change_x_to_y;
+        let rent = &Rent::from_account_info(rent_info)?;
 
-        if pool_wallet_x_info.owner == &solana_program::system_program::id() {
-            create_account::<Account>(
-                user_wallets_authority_info.clone(),
-                pool_wallet_x_info.clone(),
-                &[signers_seeds],
-                rent,
-            )?;
-            Self::initialize_account(
-                pool_wallet_x_info.clone(),
-                token_x_mint_info.clone(),
-                &pool_wallet_x_authority,
-                rent_info.clone(),
-                &[signers_seeds],
-            )?;
-        } else {
-            let account =
-                spl_token::state::Account::unpack_unchecked(&pool_wallet_x_info.data.borrow())
-                    .map_err(|_| CrateError::ExpectedAccount)?;
-            if !account.is_initialized() {
+        let signers_seeds = &[
+            &change_x_to_y.pool_wallet_x_seed().to_bytes()[..32],
+            &[pool_wallet_x_bump],
+        ];
+
+        let pool_wallet_x_balance =
+            if pool_wallet_x_info.owner == &solana_program::system_program::id() {
+                change_x_to_y.check_payer()?;
+                create_account::<Account>(
+                    user_wallets_authority_info.clone(),
+                    pool_wallet_x_info.clone(),
+                    &[signers_seeds],
+                    rent,
+                    &spl_token::id(),
+                )?;
                 Self::initialize_account(
                     pool_wallet_x_info.clone(),
                     token_x_mint_info.clone(),
-                    &pool_wallet_x_authority,
+                    pool_wallet_x_info.key,
                     rent_info.clone(),
                     &[signers_seeds],
                 )?;
+                0
             } else {
-                if &account.mint != token_x_mint_info.key {
-                    return Err(CrateError::IncorrectPoolMint.into());
+                let account =
+                    spl_token::state::Account::unpack_unchecked(&pool_wallet_x_info.data.borrow())
+                        .map_err(|_| CrateError::ExpectedAccount)?;
+                if !account.is_initialized() {
+                    Self::initialize_account(
+                        pool_wallet_x_info.clone(),
+                        token_x_mint_info.clone(),
+                        pool_wallet_x_info.key,
+                        rent_info.clone(),
+                        &[signers_seeds],
+                    )?;
+                    0
+                } else {
+                    if &account.mint != token_x_mint_info.key {
+                        return Err(CrateError::IncorrectTokenXMint.into());
+                    }
+                    account.amount
                 }
-            }
-        }
+            };
 
         Self::token_transfer(
             user_wallet_x_info.clone(),
@@ -231,74 +432,101 @@ impl Processor {
             &[],
         )?;
 
-        let (pool_mint_authority, bump_seed) =
-            Pubkey::find_program_address(&[&pool_mint_info.key.to_bytes()], program_id);
-        if *pool_mint_authority_info.key != pool_mint_authority {
-            return Err(CrateError::InvalidProgramAddress.into());
-        }
-
-        let signers_seeds = &[&pool_mint_info.key.to_bytes()[..32], &[bump_seed]];
+        let pool_mint_key = pool_mint_info.key.to_bytes();
+        let bump = [pool_mint_authority_bump];
+        let pool_seeds: [&[u8]; 3] = [POOL_SEED, &pool_mint_key, &bump];
+        let legacy_seeds: [&[u8]; 2] = [&pool_mint_key, &bump];
+        let signers_seeds: &[&[u8]] = match pool {
+            Some(_) => &pool_seeds,
+            None => &legacy_seeds,
+        };
 
         Self::token_mint_to(
             pool_mint_info.clone(),
             user_wallet_y_info.clone(),
             pool_mint_authority_info.clone(),
-            token_x_amount,
+            pool_tokens,
             &[signers_seeds],
         )?;
-        //
-        //
-        // let program_id = program_id_info.key;
-        //
-        // let user_wallet_x = spl_token::state::Account::unpack(&user_wallet_x_info.data.borrow())
-        //     .map_err(|_| CrateError::ExpectedAccount)?;
-        //
-        // let mint_x_id = user_wallet_x.mint;
-        //
-        // let (token_y_id, bump_seed) =
-        //     Pubkey::find_program_address(&[&mint_x_id[..]], program_id);
-        //
-        // if *authority_info.key != program_wallet_y {
-        //     return Err(CrateError::InvalidProgramAddress.into());
-        // }
-        //
-        // let token_y = Self::unpack_self_token_account(token_y_info, program_id)?;
-        // let pool_mint = Self::unpack_mint(mint_y_info, program_id)?;
 
+        ContractEvent::Wrap(WrapEvent {
+            pool_mint: *pool_mint_info.key,
+            pool_wallet_x: *pool_wallet_x_info.key,
+            owner: *user_wallets_authority_info.key,
+            amount_in: token_x_amount,
+            amount_out: pool_tokens,
+            fee: 0,
+            // The transfer already failed if the sum overflows
+            pool_wallet_x_balance: pool_wallet_x_balance + token_x_amount,
+        })
+        .emit();
+
+        set_return_data(
+            &ChangeResult {
+                amount_in: token_x_amount,
+                amount_out: pool_tokens,
+            }
+            .try_to_vec()?,
+        );
         Ok(())
     }
 
+    /// Unwraps pool tokens at the rate of the pool
     pub fn change_y_to_x(
         program_id: &Pubkey,
-        token_y_amount: u64,
+        amount: ChangeAmount,
         accounts: &[AccountInfo],
     ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
+        let ChangeYtoXAccounts {
+            user_wallets_authority: user_wallets_authority_info,
+            user_wallet_x: user_wallet_x_info,
+            user_wallet_y: user_wallet_y_info,
+            pool_mint: pool_mint_info,
+            pool_wallet_x: pool_wallet_x_info,
+            pool: pool_info,
+            pool_state: pool,
+            pool_wallet_x_bump,
+            ..
+        } = ChangeYtoXAccounts::parse(program_id, accounts)?;
+        let pool_wallet_x_seed = pool_info.map_or(user_wallet_x_info.key, |pool| pool.key);
 
-        let user_wallets_authority_info = next_account_info(account_info_iter)?;
-        let user_wallet_x_info = next_account_info(account_info_iter)?;
-        let user_wallet_y_info = next_account_info(account_info_iter)?;
-        let pool_mint_info = next_account_info(account_info_iter)?;
-        let pool_wallet_x_info = next_account_info(account_info_iter)?;
-        let _token_program_info = next_account_info(account_info_iter)?;
-
-        if !user_wallets_authority_info.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
+        let pool_mint = spl_token::state::Mint::unpack(&pool_mint_info.data.borrow())
+            .map_err(|_| CrateError::ExpectedMint)?;
+        let pool_mint_authority = match pool_info {
+            Some(pool_info) => *pool_info.key,
+            None => find_program_address(program_id, pool_mint_info.key).0,
+        };
+        if pool_mint.mint_authority != COption::Some(pool_mint_authority) {
+            return Err(CrateError::InvalidPoolMintAuthority.into());
         }
 
-        let (pool_wallet_x_authority, bump_seed) =
-            Pubkey::find_program_address(&[&user_wallet_x_info.key.to_bytes()], program_id);
-        if *pool_wallet_x_info.key != pool_wallet_x_authority {
-            return Err(CrateError::InvalidProgramAddress.into());
+        Self::check_no_unwrap_delay(pool.as_ref())?;
+        Self::check_term(pool.as_ref(), ChangeDirection::YtoX)?;
+        let rate = Self::pool_rate(pool.as_ref(), pool_mint_info, pool_wallet_x_info)?;
+
+        let user_wallet_y = Account::unpack(&user_wallet_y_info.data.borrow())
+            .map_err(|_| CrateError::ExpectedAccount)?;
+        let (token_y_amount, token_x_amount) =
+            Self::change_amounts(rate, ChangeDirection::YtoX, amount, user_wallet_y.amount)?;
+        let pool_wallet_x = Account::unpack(&pool_wallet_x_info.data.borrow())
+            .map_err(|_| CrateError::ExpectedAccount)?;
+        if pool_wallet_x.amount < token_x_amount {
+            return Err(CrateError::InsufficientPoolBalance.into());
+        }
+        if user_wallet_y.mint != *pool_mint_info.key {
+            return Err(CrateError::IncorrectPoolMint.into());
+        }
+        if user_wallet_y.amount < token_y_amount {
+            return Err(CrateError::NotEnoughTokens.into());
         }
 
-        let signers_seeds = &[&user_wallet_x_info.key.to_bytes()[..32], &[bump_seed]];
+        let signers_seeds = &[&pool_wallet_x_seed.to_bytes()[..32], &[pool_wallet_x_bump]];
 
         Self::token_transfer(
             pool_wallet_x_info.clone(),
             user_wallet_x_info.clone(),
             pool_wallet_x_info.clone(),
-            token_y_amount,
+            token_x_amount,
             &[signers_seeds],
         )?;
 
@@ -309,6 +537,641 @@ impl Processor {
             token_y_amount,
             &[],
         )?;
+
+        ContractEvent::Unwrap(UnwrapEvent {
+            pool_mint: *pool_mint_info.key,
+            pool_wallet_x: *pool_wallet_x_info.key,
+            owner: *user_wallets_authority_info.key,
+            amount_in: token_y_amount,
+            amount_out: token_x_amount,
+            fee: 0,
+            pool_wallet_x_balance: pool_wallet_x.amount - token_x_amount,
+        })
+        .emit();
+
+        set_return_data(
+            &ChangeResult {
+                amount_in: token_y_amount,
+                amount_out: token_x_amount,
+            }
+            .try_to_vec()?,
+        );
+        Ok(())
+    }
+
+    pub fn quote(
+        program_id: &Pubkey,
+        amount: u64,
+        direction: ChangeDirection,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let QuoteAccounts {
+            user_wallet_x: user_wallet_x_info,
+            pool_mint: pool_mint_info,
+            pool_mint_authority: pool_mint_authority_info,
+            pool_wallet_x: pool_wallet_x_info,
+            pool_state: pool,
+            ..
+        } = QuoteAccounts::parse(program_id, accounts)?;
+
+        Self::check_mint(pool_mint_info, pool_mint_authority_info)?;
+        if direction == ChangeDirection::YtoX {
+            Self::check_no_unwrap_delay(pool.as_ref())?;
+        }
+        Self::check_term(pool.as_ref(), direction)?;
+        let rate = Self::pool_rate(pool.as_ref(), pool_mint_info, pool_wallet_x_info)?;
+        let (_, amount_out) =
+            Self::change_amounts(rate, direction, ChangeAmount::In(amount), amount)?;
+
+        let user_wallet_x = Account::unpack(&user_wallet_x_info.data.borrow())
+            .map_err(|_| CrateError::ExpectedAccount)?;
+
+        match direction {
+            ChangeDirection::XtoY => {
+                if user_wallet_x.amount < amount {
+                    return Err(CrateError::NotEnoughTokens.into());
+                }
+                if pool_wallet_x_info.owner == &spl_token::id() {
+                    let pool_wallet_x =
+                        Account::unpack_unchecked(&pool_wallet_x_info.data.borrow())
+                            .map_err(|_| CrateError::ExpectedAccount)?;
+                    if pool_wallet_x.is_initialized() && pool_wallet_x.mint != user_wallet_x.mint {
+                        return Err(CrateError::IncorrectTokenXMint.into());
+                    }
+                } else if pool_wallet_x_info.owner != &solana_program::system_program::id() {
+                    return Err(CrateError::PoolWalletInUse.into());
+                }
+            }
+            ChangeDirection::YtoX => {
+                let pool_wallet_x = Account::unpack(&pool_wallet_x_info.data.borrow())
+                    .map_err(|_| CrateError::ExpectedAccount)?;
+                if pool_wallet_x.amount < amount_out {
+                    return Err(CrateError::InsufficientPoolBalance.into());
+                }
+            }
+        }
+
+        set_return_data(
+            &ChangeResult {
+                amount_in: amount,
+                amount_out,
+            }
+            .try_to_vec()?,
+        );
+        Ok(())
+    }
+
+    pub fn wrap_with_vesting(
+        program_id: &Pubkey,
+        amount: u64,
+        cliff_ts: i64,
+        end_ts: i64,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let change_x_to_y = ChangeXtoYAccounts::parse_required(program_id, accounts)?;
+        let ChangeXtoYAccounts {
+            user_wallets_authority: grantor_info,
+            user_wallet_x: user_wallet_x_info,
+            user_wallet_y: escrow_info,
+            pool_mint: pool_mint_info,
+            pool_mint_authority: pool_mint_authority_info,
+            pool_wallet_x: pool_wallet_x_info,
+            rent: rent_info,
+            ..
+        } = change_x_to_y;
+        let pool = change_x_to_y.pool_state.as_ref();
+        let WrapWithVestingAccounts {
+            vesting: vesting_info,
+            beneficiary: beneficiary_info,
+            clock: clock_info,
+            escrow_bump,
+        } = WrapWithVestingAccounts::parse(program_id, &change_x_to_y, accounts)?;
+        let rent = &Rent::from_account_info(rent_info)?;
+        let now = Clock::from_account_info(clock_info)?.unix_timestamp;
+
+        if cliff_ts < now || end_ts < cliff_ts {
+            msg!(
+                "Error: cliff {} and end {} for a start at {}",
+                cliff_ts,
+                end_ts,
+                now
+            );
+            return Err(CrateError::InvalidVestingSchedule.into());
+        }
+        Self::check_mint(pool_mint_info, pool_mint_authority_info)?;
+        let user_wallet_x = Account::unpack(&user_wallet_x_info.data.borrow())
+            .map_err(|_| CrateError::ExpectedAccount)?;
+        if user_wallet_x.owner != *grantor_info.key {
+            return Err(CrateError::InvalidWalletOwner.into());
+        }
+        if user_wallet_x.amount < amount {
+            return Err(CrateError::NotEnoughTokens.into());
+        }
+        Self::check_term(pool, ChangeDirection::XtoY)?;
+        let rate = Self::pool_rate(pool, pool_mint_info, pool_wallet_x_info)?;
+        let (_, pool_tokens) = Self::change_amounts(
+            rate,
+            ChangeDirection::XtoY,
+            ChangeAmount::In(amount),
+            user_wallet_x.amount,
+        )?;
+
+        create_account::<Vesting>(
+            grantor_info.clone(),
+            vesting_info.clone(),
+            &[],
+            rent,
+            program_id,
+        )?;
+        let signers_seeds = &[&vesting_info.key.to_bytes()[..32], &[escrow_bump]];
+        create_account::<Account>(
+            grantor_info.clone(),
+            escrow_info.clone(),
+            &[signers_seeds],
+            rent,
+            &spl_token::id(),
+        )?;
+        Self::initialize_account(
+            escrow_info.clone(),
+            pool_mint_info.clone(),
+            escrow_info.key,
+            rent_info.clone(),
+            &[signers_seeds],
+        )?;
+
+        Self::wrap(&change_x_to_y, amount, pool_tokens)?;
+
+        Vesting {
+            is_initialized: true,
+            pool_mint: *pool_mint_info.key,
+            grantor: *grantor_info.key,
+            beneficiary: *beneficiary_info.key,
+            start_ts: now,
+            cliff_ts,
+            end_ts,
+            total: pool_tokens,
+            claimed: 0,
+            escrow_bump,
+        }
+        .pack_into_slice(&mut vesting_info.data.borrow_mut());
+        Ok(())
+    }
+
+    pub fn claim_vested(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let VestingAccounts {
+            authority: beneficiary_info,
+            vesting: vesting_info,
+            escrow: escrow_info,
+            destination: destination_info,
+            clock: clock_info,
+            vesting_state: mut vesting,
+            ..
+        } = VestingAccounts::parse(program_id, &CLAIM_VESTED_ACCOUNTS, accounts)?;
+        if vesting.beneficiary != *beneficiary_info.key {
+            return Err(CrateError::InvalidVestingAuthority.into());
+        }
+        let now = Clock::from_account_info(clock_info)?.unix_timestamp;
+
+        // The clock may go back a little, the claimed amount is vested anyway
+        let amount = vesting.vested(now).saturating_sub(vesting.claimed);
+        Self::token_transfer(
+            escrow_info.clone(),
+            destination_info.clone(),
+            escrow_info.clone(),
+            amount,
+            &[&[&vesting_info.key.to_bytes()[..32], &[vesting.escrow_bump]]],
+        )?;
+        msg!("Claimed {} pool tokens", amount);
+
+        vesting.claimed += amount;
+        Vesting::pack(vesting, &mut vesting_info.data.borrow_mut())
+    }
+
+    pub fn revoke_vesting(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let VestingAccounts {
+            authority: grantor_info,
+            vesting: vesting_info,
+            escrow: escrow_info,
+            destination: destination_info,
+            clock: clock_info,
+            vesting_state: mut vesting,
+            ..
+        } = VestingAccounts::parse(program_id, &REVOKE_VESTING_ACCOUNTS, accounts)?;
+        if vesting.grantor != *grantor_info.key {
+            return Err(CrateError::InvalidVestingAuthority.into());
+        }
+        let now = Clock::from_account_info(clock_info)?.unix_timestamp;
+
+        let vested = vesting.vested(now).max(vesting.claimed);
+        let amount = vesting.total - vested;
+        Self::token_transfer(
+            escrow_info.clone(),
+            destination_info.clone(),
+            escrow_info.clone(),
+            amount,
+            &[&[&vesting_info.key.to_bytes()[..32], &[vesting.escrow_bump]]],
+        )?;
+        msg!("Revoked {} pool tokens", amount);
+
+        // What vested so far stays claimable at once
+        vesting.total = vested;
+        vesting.start_ts = now;
+        vesting.cliff_ts = now;
+        vesting.end_ts = now;
+        Vesting::pack(vesting, &mut vesting_info.data.borrow_mut())
+    }
+
+    /// Logs the backing of a pool created by `InitializePool` and fails if its vault
+    /// holds less token X than it owes
+    pub fn verify_solvency(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let VerifySolvencyAccounts {
+            pool_mint: pool_mint_info,
+            vault: vault_info,
+            pool_state: pool,
+            ..
+        } = VerifySolvencyAccounts::parse(program_id, accounts)?;
+
+        let pool_mint =
+            Mint::unpack(&pool_mint_info.data.borrow()).map_err(|_| CrateError::ExpectedMint)?;
+        let vault =
+            Account::unpack(&vault_info.data.borrow()).map_err(|_| CrateError::ExpectedAccount)?;
+
+        let backing = vault.amount.saturating_sub(pool.pending_unwrap);
+        let ratio_bps = (pool_mint.supply > 0).then(|| {
+            let ratio = backing as u128 * 10_000 / pool_mint.supply as u128;
+            u64::try_from(ratio).unwrap_or(u64::MAX)
+        });
+        ContractEvent::Solvency(SolvencyEvent {
+            pool_mint: pool.pool_mint,
+            token_x_mint: pool.token_x_mint,
+            supply: pool_mint.supply,
+            vault_balance: vault.amount,
+            pending_unwrap: pool.pending_unwrap,
+            ratio_bps,
+        })
+        .emit();
+        msg!(
+            "Vault holds {} for a supply of {} and {} pending unwrap",
+            vault.amount,
+            pool_mint.supply,
+            pool.pending_unwrap
+        );
+
+        // The tickets are owed first. Then each pool token is owed a token X in
+        // `Fixed` mode, while in `Shares` mode the pool tokens are only owed some
+        let owed = pool.pending_unwrap as u128
+            + match pool.mode {
+                PoolMode::Fixed => pool_mint.supply as u128,
+                PoolMode::Shares => (pool_mint.supply > 0) as u128,
+            };
+        if (vault.amount as u128) < owed {
+            return Err(CrateError::Insolvent.into());
+        }
+        Ok(())
+    }
+
+    /// Creates the pool mint, owned by the pool state, the pool state, the vault
+    /// and the metadata of the pool mint. The admin pays for all of them
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_pool(
+        program_id: &Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+        mode: PoolMode,
+        unwrap_delay: u32,
+        subscription_deadline: i64,
+        maturity: i64,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let InitializePoolAccounts {
+            admin: admin_info,
+            pool_mint: pool_mint_info,
+            pool: pool_info,
+            vault: vault_info,
+            token_x_mint: token_x_mint_info,
+            metadata: metadata_info,
+            metadata_program: metadata_program_info,
+            rent: rent_info,
+            system_program: system_program_info,
+            token_program: _,
+            pool_bump,
+            vault_bump,
+        } = InitializePoolAccounts::parse(program_id, accounts)?;
+        let rent = &Rent::from_account_info(rent_info)?;
+
+        let token_x_mint =
+            Mint::unpack(&token_x_mint_info.data.borrow()).map_err(|_| CrateError::ExpectedMint)?;
+        let decimals = match mode {
+            PoolMode::Fixed => Some(token_x_mint.decimals),
+            PoolMode::Shares => token_x_mint.decimals.checked_add(SHARES_DECIMALS_OFFSET),
+        }
+        .ok_or(ProgramError::InvalidArgument)?;
+        // A fixed-term pool matures once its subscription is closed
+        if subscription_deadline != 0 && maturity != 0 && maturity <= subscription_deadline {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        create_account::<Mint>(
+            admin_info.clone(),
+            pool_mint_info.clone(),
+            &[],
+            rent,
+            &spl_token::id(),
+        )?;
+        Self::initialize_mint(
+            pool_mint_info.clone(),
+            pool_info.key,
+            decimals,
+            rent_info.clone(),
+        )?;
+
+        let pool_seeds: &[&[u8]] = &[POOL_SEED, &pool_mint_info.key.to_bytes(), &[pool_bump]];
+        create_account::<Pool>(
+            admin_info.clone(),
+            pool_info.clone(),
+            &[pool_seeds],
+            rent,
+            program_id,
+        )?;
+
+        let vault_seeds: &[&[u8]] = &[&pool_info.key.to_bytes(), &[vault_bump]];
+        create_account::<Account>(
+            admin_info.clone(),
+            vault_info.clone(),
+            &[vault_seeds],
+            rent,
+            &spl_token::id(),
+        )?;
+        Self::initialize_account(
+            vault_info.clone(),
+            token_x_mint_info.clone(),
+            vault_info.key,
+            rent_info.clone(),
+            &[],
+        )?;
+
+        let ix = metadata::create_metadata_account_v2(
+            metadata_info.key,
+            pool_mint_info.key,
+            pool_info.key,
+            admin_info.key,
+            pool_info.key,
+            DataV2::new(&name, &symbol, &uri),
+        );
+        invoke_signed(
+            &ix,
+            &[
+                metadata_info.clone(),
+                pool_mint_info.clone(),
+                pool_info.clone(),
+                admin_info.clone(),
+                pool_info.clone(),
+                system_program_info.clone(),
+                rent_info.clone(),
+                metadata_program_info.clone(),
+            ],
+            &[pool_seeds],
+        )?;
+
+        // Written last, the metadata instruction takes the pool state read-only
+        Pool {
+            is_initialized: true,
+            admin: *admin_info.key,
+            pool_mint: *pool_mint_info.key,
+            token_x_mint: *token_x_mint_info.key,
+            mode,
+            unwrap_delay,
+            pending_unwrap: 0,
+            subscription_deadline,
+            maturity,
+            bump: pool_bump,
+            vault_bump,
+        }
+        .pack_into_slice(&mut pool_info.data.borrow_mut());
+        Ok(())
+    }
+
+    /// Replaces the metadata of the pool mint, signed by the pool state
+    pub fn update_pool_metadata(
+        program_id: &Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let UpdatePoolMetadataAccounts {
+            admin: admin_info,
+            pool: pool_info,
+            metadata: metadata_info,
+            metadata_program: metadata_program_info,
+            pool_state: pool,
+        } = UpdatePoolMetadataAccounts::parse(program_id, accounts)?;
+
+        if pool.admin != *admin_info.key {
+            return Err(CrateError::InvalidPoolAdmin.into());
+        }
+
+        let ix = metadata::update_metadata_account_v2(
+            metadata_info.key,
+            pool_info.key,
+            DataV2::new(&name, &symbol, &uri),
+        );
+        invoke_signed(
+            &ix,
+            &[
+                metadata_info.clone(),
+                pool_info.clone(),
+                metadata_program_info.clone(),
+            ],
+            &[&[POOL_SEED, &pool.pool_mint.to_bytes(), &[pool.bump]]],
+        )
+    }
+
+    /// Moves the token X of the vault over the supply of the pool mint and the token X
+    /// owed to withdrawal tickets to the treasury, for pools in `Fixed` mode
+    pub fn skim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let SkimAccounts {
+            admin: admin_info,
+            pool: pool_info,
+            pool_mint: pool_mint_info,
+            vault: vault_info,
+            treasury: treasury_info,
+            pool_state: pool,
+            vault_bump,
+            ..
+        } = SkimAccounts::parse(program_id, accounts)?;
+
+        if pool.admin != *admin_info.key {
+            return Err(CrateError::InvalidPoolAdmin.into());
+        }
+        if pool.mode != PoolMode::Fixed {
+            return Err(CrateError::InvalidPoolMode.into());
+        }
+        let pool_mint =
+            Mint::unpack(&pool_mint_info.data.borrow()).map_err(|_| CrateError::ExpectedMint)?;
+        let vault =
+            Account::unpack(&vault_info.data.borrow()).map_err(|_| CrateError::ExpectedAccount)?;
+
+        let amount = vault
+            .amount
+            .saturating_sub(pool.pending_unwrap)
+            .saturating_sub(pool_mint.supply);
+        Self::token_transfer(
+            vault_info.clone(),
+            treasury_info.clone(),
+            vault_info.clone(),
+            amount,
+            &[&[&pool_info.key.to_bytes()[..32], &[vault_bump]]],
+        )?;
+        msg!("Skimmed {} token X", amount);
+        Ok(())
+    }
+
+    /// Burns pool tokens for a withdrawal ticket of the token X they are worth now,
+    /// unlocked after the unwrap delay of the pool
+    pub fn request_unwrap(
+        program_id: &Pubkey,
+        amount: u64,
+        nonce: u64,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let RequestUnwrapAccounts {
+            owner: owner_info,
+            user_wallet_y: user_wallet_y_info,
+            pool_mint: pool_mint_info,
+            pool: pool_info,
+            vault: vault_info,
+            ticket: ticket_info,
+            clock: clock_info,
+            rent: rent_info,
+            pool_state: mut pool,
+            ticket_bump,
+            ..
+        } = RequestUnwrapAccounts::parse(program_id, nonce, accounts)?;
+        let rent = &Rent::from_account_info(rent_info)?;
+        let now = Clock::from_account_info(clock_info)?.unix_timestamp;
+
+        Self::check_term(Some(&pool), ChangeDirection::YtoX)?;
+        let rate = Self::pool_rate(Some(&pool), pool_mint_info, vault_info)?;
+        let user_wallet_y = Account::unpack(&user_wallet_y_info.data.borrow())
+            .map_err(|_| CrateError::ExpectedAccount)?;
+        if user_wallet_y.mint != *pool_mint_info.key {
+            return Err(CrateError::IncorrectPoolMint.into());
+        }
+        if user_wallet_y.amount < amount {
+            return Err(CrateError::NotEnoughTokens.into());
+        }
+        let (_, token_x_amount) = Self::change_amounts(
+            rate,
+            ChangeDirection::YtoX,
+            ChangeAmount::In(amount),
+            user_wallet_y.amount,
+        )?;
+        let vault =
+            Account::unpack(&vault_info.data.borrow()).map_err(|_| CrateError::ExpectedAccount)?;
+        if vault.amount.saturating_sub(pool.pending_unwrap) < token_x_amount {
+            return Err(CrateError::InsufficientPoolBalance.into());
+        }
+
+        Self::token_burn(
+            user_wallet_y_info.clone(),
+            pool_mint_info.clone(),
+            owner_info.clone(),
+            amount,
+            &[],
+        )?;
+
+        create_account::<Ticket>(
+            owner_info.clone(),
+            ticket_info.clone(),
+            &[&[
+                TICKET_SEED,
+                &pool_info.key.to_bytes(),
+                &owner_info.key.to_bytes(),
+                &nonce.to_le_bytes(),
+                &[ticket_bump],
+            ]],
+            rent,
+            program_id,
+        )?;
+        let unlock_ts = now.saturating_add(pool.unwrap_delay.into());
+        Ticket {
+            is_initialized: true,
+            owner: *owner_info.key,
+            pool: *pool_info.key,
+            amount: token_x_amount,
+            unlock_ts,
+        }
+        .pack_into_slice(&mut ticket_info.data.borrow_mut());
+
+        // The vault holds the pending token X, checked above
+        pool.pending_unwrap += token_x_amount;
+        Pool::pack(pool, &mut pool_info.data.borrow_mut())?;
+        msg!("{} token X unlock at {}", token_x_amount, unlock_ts);
+
+        set_return_data(
+            &ChangeResult {
+                amount_in: amount,
+                amount_out: token_x_amount,
+            }
+            .try_to_vec()?,
+        );
+        Ok(())
+    }
+
+    /// Sends the token X of an unlocked withdrawal ticket and closes it
+    pub fn claim_unwrap(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let ClaimUnwrapAccounts {
+            owner: owner_info,
+            destination: destination_info,
+            pool: pool_info,
+            vault: vault_info,
+            ticket: ticket_info,
+            clock: clock_info,
+            pool_state: mut pool,
+            vault_bump,
+            ..
+        } = ClaimUnwrapAccounts::parse(program_id, accounts)?;
+
+        let ticket =
+            Ticket::unpack(&ticket_info.data.borrow()).map_err(|_| CrateError::ExpectedTicket)?;
+        if ticket.owner != *owner_info.key {
+            return Err(CrateError::InvalidTicketOwner.into());
+        }
+        if ticket.pool != *pool_info.key {
+            return Err(CrateError::ExpectedTicket.into());
+        }
+        let now = Clock::from_account_info(clock_info)?.unix_timestamp;
+        if now < ticket.unlock_ts {
+            msg!("Error: unlocks at {}, now {}", ticket.unlock_ts, now);
+            return Err(CrateError::TicketLocked.into());
+        }
+
+        Self::token_transfer(
+            vault_info.clone(),
+            destination_info.clone(),
+            vault_info.clone(),
+            ticket.amount,
+            &[&[&pool_info.key.to_bytes()[..32], &[vault_bump]]],
+        )?;
+        msg!("Claimed {} token X", ticket.amount);
+
+        pool.pending_unwrap = pool
+            .pending_unwrap
+            .checked_sub(ticket.amount)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        Pool::pack(pool, &mut pool_info.data.borrow_mut())?;
+
+        // The owner gets the lamports of the ticket, which is removed at the end of
+        // the transaction
+        let lamports = owner_info
+            .lamports()
+            .checked_add(ticket_info.lamports())
+            .ok_or(ProgramError::InvalidAccountData)?;
+        **owner_info.lamports.borrow_mut() = lamports;
+        **ticket_info.lamports.borrow_mut() = 0;
+        ticket_info.data.borrow_mut().fill(0);
         Ok(())
     }
 
@@ -318,16 +1181,108 @@ impl Processor {
         accounts: &[AccountInfo],
         input: &[u8],
     ) -> ProgramResult {
-        let instruction = ContractInstruction::try_from_slice(input)?;
+        let instruction = ContractInstruction::unpack(input)?;
         match instruction {
             ContractInstruction::ChangeXtoY { amount } => {
                 msg!("Instruction: ChangeXtoY");
-                Self::change_x_to_y(program_id, amount, accounts)
+                Self::change_x_to_y(program_id, ChangeAmount::In(amount), accounts)
             }
             ContractInstruction::ChangeYtoX { amount } => {
                 msg!("Instruction: ChangeYtoX");
+                Self::change_y_to_x(program_id, ChangeAmount::In(amount), accounts)
+            }
+            ContractInstruction::Quote { amount, direction } => {
+                msg!("Instruction: Quote");
+                Self::quote(program_id, amount, direction, accounts)
+            }
+            ContractInstruction::VerifySolvency => {
+                msg!("Instruction: VerifySolvency");
+                Self::verify_solvency(program_id, accounts)
+            }
+            ContractInstruction::ChangeXtoYExactOut {
+                amount_out,
+                maximum_in,
+            } => {
+                msg!("Instruction: ChangeXtoYExactOut");
+                let amount = ChangeAmount::Out {
+                    amount_out,
+                    maximum_in,
+                };
+                Self::change_x_to_y(program_id, amount, accounts)
+            }
+            ContractInstruction::ChangeYtoXExactOut {
+                amount_out,
+                maximum_in,
+            } => {
+                msg!("Instruction: ChangeYtoXExactOut");
+                let amount = ChangeAmount::Out {
+                    amount_out,
+                    maximum_in,
+                };
                 Self::change_y_to_x(program_id, amount, accounts)
             }
+            ContractInstruction::ChangeXtoYAll => {
+                msg!("Instruction: ChangeXtoYAll");
+                Self::change_x_to_y(program_id, ChangeAmount::All, accounts)
+            }
+            ContractInstruction::ChangeYtoXAll => {
+                msg!("Instruction: ChangeYtoXAll");
+                Self::change_y_to_x(program_id, ChangeAmount::All, accounts)
+            }
+            ContractInstruction::WrapWithVesting {
+                amount,
+                cliff_ts,
+                end_ts,
+            } => {
+                msg!("Instruction: WrapWithVesting");
+                Self::wrap_with_vesting(program_id, amount, cliff_ts, end_ts, accounts)
+            }
+            ContractInstruction::ClaimVested => {
+                msg!("Instruction: ClaimVested");
+                Self::claim_vested(program_id, accounts)
+            }
+            ContractInstruction::RevokeVesting => {
+                msg!("Instruction: RevokeVesting");
+                Self::revoke_vesting(program_id, accounts)
+            }
+            ContractInstruction::InitializePool {
+                name,
+                symbol,
+                uri,
+                mode,
+                unwrap_delay,
+                subscription_deadline,
+                maturity,
+            } => {
+                msg!("Instruction: InitializePool");
+                Self::initialize_pool(
+                    program_id,
+                    name,
+                    symbol,
+                    uri,
+                    mode,
+                    unwrap_delay,
+                    subscription_deadline,
+                    maturity,
+                    accounts,
+                )
+            }
+            ContractInstruction::UpdatePoolMetadata { name, symbol, uri } => {
+                msg!("Instruction: UpdatePoolMetadata");
+                Self::update_pool_metadata(program_id, name, symbol, uri, accounts)
+            }
+            ContractInstruction::Skim => {
+                msg!("Instruction: Skim");
+                Self::skim(program_id, accounts)
+            }
+            ContractInstruction::RequestUnwrap { amount, nonce } => {
+                msg!("Instruction: RequestUnwrap");
+                Self::request_unwrap(program_id, amount, nonce, accounts)
+            }
+            ContractInstruction::ClaimUnwrap => {
+                msg!("Instruction: ClaimUnwrap");
+                Self::claim_unwrap(program_id, accounts)
+            }
         }
     }
 }
@@ -1 +1,254 @@
+//! Accounts owned by the program
 
+use crate::error::CrateError;
+use crate::instruction::ChangeDirection;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::Serialize;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::{IsInitialized, Pack, Sealed};
+use solana_program::pubkey::Pubkey;
+
+/// Pool tokens locked for a beneficiary by `WrapWithVesting`. They are held by
+/// the escrow account, the program address derived from the vesting account
+#[derive(Clone, Debug, Default, BorshDeserialize, BorshSerialize, PartialEq, Serialize)]
+pub struct Vesting {
+    pub is_initialized: bool,
+    pub pool_mint: Pubkey,
+    /// Wrapped the token X, can revoke the unvested pool tokens
+    pub grantor: Pubkey,
+    /// Can claim the vested pool tokens
+    pub beneficiary: Pubkey,
+    /// Unix timestamps, nothing vests before the cliff, then pool tokens vest
+    /// linearly from the start until the end
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    /// Pool tokens vesting, lowered to the vested amount on revoke
+    pub total: u64,
+    /// Pool tokens already sent to the beneficiary
+    pub claimed: u64,
+    /// Bump seed of the escrow address
+    pub escrow_bump: u8,
+}
+
+impl Vesting {
+    /// Pool tokens vested at `now`, rounded down against the beneficiary
+    pub fn vested(&self, now: i64) -> u64 {
+        if now < self.cliff_ts {
+            0
+        } else if now >= self.end_ts {
+            self.total
+        } else {
+            // The cliff isn't before the start, so start <= now < end
+            let elapsed = (now - self.start_ts) as u128;
+            let duration = (self.end_ts - self.start_ts) as u128;
+            (self.total as u128 * elapsed / duration) as u64
+        }
+    }
+}
+
+impl Sealed for Vesting {}
+
+impl IsInitialized for Vesting {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Vesting {
+    const LEN: usize = 1 + 32 * 3 + 8 * 5 + 1;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let data = self.try_to_vec().unwrap();
+        dst.copy_from_slice(&data);
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        Self::try_from_slice(src).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+/// State of a pool created by `InitializePool`, at the program address derived
+/// from its pool mint. It's the pool authority of the mint, and the vault holding
+/// the token X of the pool is the program address derived from it
+#[derive(Clone, Debug, Default, BorshDeserialize, BorshSerialize, PartialEq, Serialize)]
+pub struct Pool {
+    pub is_initialized: bool,
+    /// Can update the metadata of the pool mint and skim the vault
+    pub admin: Pubkey,
+    pub pool_mint: Pubkey,
+    pub token_x_mint: Pubkey,
+    pub mode: PoolMode,
+    /// Seconds from `RequestUnwrap` until the ticket can be claimed. `ChangeYtoX`
+    /// only unwraps when it's 0
+    pub unwrap_delay: u32,
+    /// Token X of the vault owed to the withdrawal tickets not claimed yet, which
+    /// no pool token represents anymore
+    pub pending_unwrap: u64,
+    /// Unix timestamp after which token X isn't wrapped anymore, 0 for none
+    pub subscription_deadline: i64,
+    /// Unix timestamp from which pool tokens are unwrapped, 0 for none
+    pub maturity: i64,
+    /// Bump seeds of the addresses of the pool state and the vault, so that they are
+    /// checked without searching for them
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl Sealed for Pool {}
+
+impl IsInitialized for Pool {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Pool {
+    /// Differs from the length of `Vesting`, the other accounts of the program
+    const LEN: usize = 1 + 32 * 3 + 1 + 4 + 8 * 3 + 2;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let data = self.try_to_vec().unwrap();
+        dst.copy_from_slice(&data);
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        Self::try_from_slice(src).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+/// Token X owed by a pool for the pool tokens burnt by `RequestUnwrap`, sent by
+/// `ClaimUnwrap` once unlocked. At the program address derived from the pool
+/// state, the owner and a nonce, closed when claimed
+#[derive(Clone, Debug, Default, BorshDeserialize, BorshSerialize, PartialEq, Serialize)]
+pub struct Ticket {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    /// Pool state
+    pub pool: Pubkey,
+    /// Token X claimable
+    pub amount: u64,
+    /// Unix timestamp from which the ticket can be claimed
+    pub unlock_ts: i64,
+}
+
+impl Ticket {
+    /// Offset of the owner in the account data, so that the tickets of an owner are
+    /// listed by `getProgramAccounts` with a memcmp filter at this offset and a data
+    /// size filter of `Ticket::LEN`
+    pub const OWNER_OFFSET: usize = 1;
+}
+
+impl Sealed for Ticket {}
+
+impl IsInitialized for Ticket {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Ticket {
+    /// Differs from the lengths of `Vesting` and `Pool`
+    const LEN: usize = 1 + 32 * 2 + 8 * 2;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let data = self.try_to_vec().unwrap();
+        dst.copy_from_slice(&data);
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        Self::try_from_slice(src).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+/// How the pool tokens of a pool created by `InitializePool` are valued
+#[derive(Clone, Copy, Debug, BorshDeserialize, BorshSerialize, PartialEq, Serialize)]
+pub enum PoolMode {
+    /// Each pool token is a claim on one token X, as for pools created off-chain
+    Fixed,
+    /// Pool tokens are shares of the token X held by the vault, so that the yield
+    /// of token X sent to the vault goes to the holders pro rata
+    Shares,
+}
+
+impl Default for PoolMode {
+    fn default() -> Self {
+        PoolMode::Fixed
+    }
+}
+
+/// Decimals the pool mint of a pool in `Shares` mode has over token X
+pub const SHARES_DECIMALS_OFFSET: u8 = 3;
+
+/// Pool tokens and token X the rate of a pool in `Shares` mode counts on top of
+/// the supply and the vault balance. Without them, the first depositor could wrap
+/// a single token X and send many more to the vault, so that the deposits that
+/// follow round down to no pool token and are taken by that one share. With them,
+/// such a donation is shared with the virtual shares and costs the attacker far
+/// more than the rounding takes from the others
+pub const VIRTUAL_SHARES: u64 = 10u64.pow(SHARES_DECIMALS_OFFSET as u32);
+pub const VIRTUAL_ASSETS: u64 = 1;
+
+/// Rate between token X and the pool tokens of a pool
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rate {
+    /// One pool token per token X
+    Fixed,
+    /// Pool tokens are shares of the vault balance not owed to withdrawal tickets
+    Shares { assets: u64, supply: u64 },
+}
+
+impl Rate {
+    /// Output of a change of `amount_in` in `direction`, rounded down against
+    /// the user
+    pub fn amount_out(
+        &self,
+        direction: ChangeDirection,
+        amount_in: u64,
+    ) -> Result<u64, ProgramError> {
+        self.convert(direction, amount_in, false)
+    }
+
+    /// Input of a change producing `amount_out` in `direction`, rounded up against
+    /// the user
+    pub fn amount_in(
+        &self,
+        direction: ChangeDirection,
+        amount_out: u64,
+    ) -> Result<u64, ProgramError> {
+        // The input buys the output in the opposite direction
+        let direction = match direction {
+            ChangeDirection::XtoY => ChangeDirection::YtoX,
+            ChangeDirection::YtoX => ChangeDirection::XtoY,
+        };
+        self.convert(direction, amount_out, true)
+    }
+
+    /// Converts `amount` of the source token of `direction`
+    fn convert(
+        &self,
+        direction: ChangeDirection,
+        amount: u64,
+        round_up: bool,
+    ) -> Result<u64, ProgramError> {
+        let (assets, supply) = match *self {
+            Rate::Fixed => return Ok(amount),
+            Rate::Shares { assets, supply } => (
+                assets as u128 + VIRTUAL_ASSETS as u128,
+                supply as u128 + VIRTUAL_SHARES as u128,
+            ),
+        };
+        let (numerator, denominator) = match direction {
+            ChangeDirection::XtoY => (supply, assets),
+            ChangeDirection::YtoX => (assets, supply),
+        };
+        // Both factors fit in 65 bits
+        let product = amount as u128 * numerator;
+        let converted = if round_up {
+            (product + denominator - 1) / denominator
+        } else {
+            product / denominator
+        };
+        u64::try_from(converted).map_err(|_| CrateError::AmountOverflow.into())
+    }
+}
@@ -1,7 +1,8 @@
 use solana_program::account_info::AccountInfo;
 use solana_program::entrypoint::ProgramResult;
-use solana_program::program::invoke_signed;
+use solana_program::program::{invoke, invoke_signed};
 use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::system_instruction;
 
@@ -10,14 +11,46 @@ pub fn create_account<'a, S: Pack>(
     to: AccountInfo<'a>,
     signers_seeds: &[&[&[u8]]],
     rent: &Rent,
+    owner: &Pubkey,
 ) -> ProgramResult {
     let ix = system_instruction::create_account(
         from.key,
         to.key,
         rent.minimum_balance(S::LEN),
         S::LEN as u64,
-        &spl_token::id(),
+        owner,
     );
 
     invoke_signed(&ix, &[from, to], signers_seeds)
 }
+
+/// Issue an spl_associated_token_account `Create` instruction, `funder` pays for the account
+#[allow(clippy::too_many_arguments)]
+pub fn create_associated_token_account<'a>(
+    funder: AccountInfo<'a>,
+    associated_account: AccountInfo<'a>,
+    wallet: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    rent: AccountInfo<'a>,
+    associated_token_program: AccountInfo<'a>,
+) -> ProgramResult {
+    let ix = spl_associated_token_account::create_associated_token_account(
+        funder.key, wallet.key, mint.key,
+    );
+
+    invoke(
+        &ix,
+        &[
+            funder,
+            associated_account,
+            wallet,
+            mint,
+            system_program,
+            token_program,
+            rent,
+            associated_token_program,
+        ],
+    )
+}
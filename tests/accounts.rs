@@ -0,0 +1,197 @@
+mod utils;
+
+use crate::contract::TestContract;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::TransactionError;
+use solana_token::error::CrateError;
+use solana_token::{find_program_address, id, instruction};
+use utils::*;
+
+fn change_x_to_y(test_contract: &TestContract, token_x: &TokenX, user: &User) -> Instruction {
+    instruction::change_x_to_y(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        10,
+    )
+}
+
+fn change_y_to_x(test_contract: &TestContract, user: &User) -> Instruction {
+    instruction::change_y_to_x(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        10,
+    )
+}
+
+#[tokio::test]
+async fn fail_with_incorrect_program_accounts() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+    // The pool wallet must exist for ChangeYtoX
+    test_contract
+        .change_x_to_y(&mut context, &user, &token_x, 50)
+        .await
+        .unwrap();
+
+    // Rent sysvar, system program, token program
+    for index in 7..10 {
+        let mut ix = change_x_to_y(&test_contract, &token_x, &user);
+        ix.accounts[index] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+        assert_eq!(
+            process_error(&mut context, ix, &[&user.account]).await,
+            crate_error(0, CrateError::IncorrectProgramAccount),
+            "account {}",
+            index
+        );
+    }
+    // Optional associated token account program
+    let mut ix = change_x_to_y(&test_contract, &token_x, &user);
+    ix.accounts
+        .push(AccountMeta::new_readonly(Pubkey::new_unique(), false));
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::IncorrectProgramAccount)
+    );
+
+    let mut ix = change_y_to_x(&test_contract, &user);
+    ix.accounts[5] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::IncorrectProgramAccount)
+    );
+}
+
+#[tokio::test]
+async fn fail_with_read_only_account() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+
+    let mut ix = change_x_to_y(&test_contract, &token_x, &user);
+    ix.accounts[4].is_writable = false;
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::AccountNotWritable)
+    );
+}
+
+#[tokio::test]
+async fn creating_destination_needs_associated_token_program() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+
+    let mut ix = instruction::change_x_to_y_associated(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        10,
+    );
+    ix.accounts.pop();
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+    );
+}
+
+#[tokio::test]
+async fn creating_accounts_needs_writable_authority() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+
+    // The authority doesn't pay the fee, so it stays read-only
+    let mut ix = instruction::change_x_to_y_associated(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        10,
+    );
+    ix.accounts[0].is_writable = false;
+    let payer = payer(&context);
+    assert_eq!(
+        process_error(&mut context, ix, &[&payer, &user.account]).await,
+        crate_error(0, CrateError::AccountNotWritable)
+    );
+}
+
+#[tokio::test]
+async fn fail_with_duplicate_accounts() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+    // The pool wallet must exist for ChangeYtoX
+    test_contract
+        .change_x_to_y(&mut context, &user, &token_x, 50)
+        .await
+        .unwrap();
+
+    let mut ix = change_x_to_y(&test_contract, &token_x, &user);
+    ix.accounts[3] = ix.accounts[1].clone();
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::DuplicateAccount)
+    );
+
+    let mut ix = change_y_to_x(&test_contract, &user);
+    ix.accounts[2] = ix.accounts[1].clone();
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::DuplicateAccount)
+    );
+}
+
+#[tokio::test]
+async fn fail_with_incorrect_pool_addresses() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+
+    let mut ix = change_x_to_y(&test_contract, &token_x, &user);
+    ix.accounts[5] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::InvalidPoolAuthority)
+    );
+
+    let mut ix = change_x_to_y(&test_contract, &token_x, &user);
+    ix.accounts[6] = AccountMeta::new(Pubkey::new_unique(), false);
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::InvalidPoolWalletAddress)
+    );
+}
+
+#[tokio::test]
+async fn unwrap_fails_with_foreign_pool_mint() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+    test_contract
+        .change_x_to_y(&mut context, &user, &token_x, 100)
+        .await
+        .unwrap();
+
+    // Mint of the same decimals controlled by the user, not by the pool authority
+    let foreign_mint = Keypair::new();
+    create_mint(&mut context, &foreign_mint, &user.account.pubkey())
+        .await
+        .unwrap();
+    let mut ix = change_y_to_x(&test_contract, &user);
+    ix.accounts[3] = AccountMeta::new(foreign_mint.pubkey(), false);
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::InvalidPoolMintAuthority)
+    );
+    assert_eq!(
+        get_token_balance(
+            &mut context,
+            &find_program_address(&id(), &user.user_wallet_x.pubkey()).0
+        )
+        .await,
+        100
+    );
+}
@@ -0,0 +1,88 @@
+mod utils;
+
+use crate::caller::call_through;
+use crate::contract::TestContract;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::signature::Signer;
+use solana_token::{find_program_address, id, instruction};
+use utils::*;
+
+#[tokio::test]
+async fn success() {
+    let caller_id = Pubkey::new_unique();
+    let (mut context, test_contract, token_x, user) =
+        setup(program_test_with_caller(&caller_id), 100).await;
+
+    let change_x_to_y = instruction::change_x_to_y_all(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+    );
+    let ix = call_through(&caller_id, change_x_to_y, 130);
+    // Received after the instruction was built
+    token_x
+        .mint_to(
+            &mut context,
+            &user.user_wallet_x.pubkey(),
+            &token_x.owner,
+            30,
+        )
+        .await
+        .unwrap();
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+
+    let (pool_wallet_x, _) = find_program_address(&id(), &user.user_wallet_x.pubkey());
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_x.pubkey()).await,
+        0
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_y.pubkey()).await,
+        130
+    );
+    assert_eq!(get_token_balance(&mut context, &pool_wallet_x).await, 130);
+
+    let change_y_to_x = instruction::change_y_to_x_all(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+    );
+    let ix = call_through(&caller_id, change_y_to_x, 130);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_x.pubkey()).await,
+        130
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_y.pubkey()).await,
+        0
+    );
+    assert_eq!(get_token_balance(&mut context, &pool_wallet_x).await, 0);
+}
+
+#[tokio::test]
+async fn fail_with_other_amount() {
+    let caller_id = Pubkey::new_unique();
+    let (mut context, test_contract, token_x, user) =
+        setup(program_test_with_caller(&caller_id), 100).await;
+
+    let change_x_to_y = instruction::change_x_to_y_all(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+    );
+    let ix = call_through(&caller_id, change_x_to_y, 99);
+    assert!(process(&mut context, ix, &[&user.account]).await.is_err());
+}
@@ -8,26 +8,9 @@ use solana_sdk::signature::Signer;
 use solana_token::find_program_address;
 use utils::*;
 
-async fn setup() -> (ProgramTestContext, TestContract, TokenX, User) {
-    let mut context = program_test().start_with_context().await;
-
-    let test_contract = TestContract::new();
-    test_contract.create(&mut context).await.unwrap();
-
-    let token_x = TokenX::new();
-    token_x.init(&mut context).await.unwrap();
-
-    let user = User::new();
-    user.init(&mut context, &token_x, &test_contract)
-        .await
-        .unwrap();
-
-    (context, test_contract, token_x, user)
-}
-
 #[tokio::test]
 async fn success() {
-    let (mut context, test_contract, token_x, user) = setup().await;
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 0).await;
 
     let amount = 100;
 
@@ -74,3 +57,43 @@ async fn success() {
     assert_eq!(get_token_balance(&mut context, &pool_wallet_x).await, 0);
     assert_eq!(get_token_balance(&mut context, &user_wallet_y).await, 0);
 }
+
+#[tokio::test]
+async fn success_with_associated_account() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 0).await;
+
+    let amount = 100;
+
+    let user_wallet_y = spl_associated_token_account::get_associated_token_address(
+        &user.account.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+    );
+    assert!(context
+        .banks_client
+        .get_account(user_wallet_y)
+        .await
+        .unwrap()
+        .is_none());
+    token_x
+        .mint_to(
+            &mut context,
+            &user.user_wallet_x.pubkey(),
+            &token_x.owner,
+            amount,
+        )
+        .await
+        .unwrap();
+    test_contract
+        .change_x_to_y_associated(&mut context, &user, &token_x, 40)
+        .await
+        .unwrap();
+    assert_eq!(get_token_balance(&mut context, &user_wallet_y).await, 40);
+    test_contract
+        .change_x_to_y_associated(&mut context, &user, &token_x, amount - 40)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user_wallet_y).await,
+        amount
+    );
+}
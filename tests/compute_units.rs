@@ -0,0 +1,522 @@
+mod utils;
+
+use crate::contract::TestContract;
+use crate::pool::TestPool;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::instruction::Instruction;
+use solana_program_test::*;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_token::state::PoolMode;
+use solana_token::{id, instruction};
+use std::collections::BTreeMap;
+use utils::*;
+
+const BASELINE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/compute_units.json");
+
+/// Allowed increase over the baseline, in percent
+const THRESHOLD_PERCENT: u64 = 5;
+
+/// Instruction paths measured by `compute_units_within_baseline`, in baseline order
+const PATHS: &[&str] = &[
+    "change_x_to_y",
+    "change_x_to_y_all",
+    "change_x_to_y_creating_pool_wallet",
+    "change_x_to_y_exact_out",
+    "change_x_to_y_pool",
+    "change_x_to_y_shares",
+    "change_y_to_x",
+    "change_y_to_x_all",
+    "change_y_to_x_exact_out",
+    "change_y_to_x_pool",
+    "change_y_to_x_shares",
+    "claim_unwrap",
+    "claim_vested",
+    "initialize_pool",
+    "quote",
+    "request_unwrap",
+    "revoke_vesting",
+    "skim",
+    "update_pool_metadata",
+    "verify_solvency",
+    "wrap_with_vesting",
+];
+
+fn read_baseline() -> BTreeMap<String, u64> {
+    let json = std::fs::read_to_string(BASELINE_PATH)
+        .unwrap_or_else(|err| panic!("no baseline at {}: {}", BASELINE_PATH, err));
+    serde_json::from_str(&json)
+        .unwrap_or_else(|err| panic!("invalid baseline at {}: {}", BASELINE_PATH, err))
+}
+
+/// Checked without the BPF program, so that a missing or partial baseline fails every run
+#[test]
+fn baseline_covers_every_path() {
+    let baseline = read_baseline();
+    assert_eq!(
+        baseline.keys().map(String::as_str).collect::<Vec<_>>(),
+        PATHS,
+        "rewrite the baseline with `UPDATE_CU_BASELINE=1 cargo test-bpf --test compute_units`"
+    );
+    assert!(baseline.values().all(|&units| units > 0));
+}
+
+/// Compute units used by `ix`, which is simulated but not committed
+async fn measure(context: &mut ProgramTestContext, ix: Instruction, signers: &[&Keypair]) -> u64 {
+    let logs = simulate(context, ix, signers).await;
+    let prefix = format!("Program {} consumed ", id());
+    let consumed = logs
+        .iter()
+        .find_map(|log| log.strip_prefix(&prefix))
+        .expect("no compute units in the program log");
+    consumed
+        .split_whitespace()
+        .next()
+        .and_then(|units| units.parse().ok())
+        .unwrap_or_else(|| panic!("invalid log {:?}", consumed))
+}
+
+/// Compute units of each instruction path of the BPF program.
+///
+/// Run with `cargo test-bpf --test compute_units`, and with `UPDATE_CU_BASELINE=1` to
+/// rewrite the checked-in baseline. Skipped without `BPF_OUT_DIR`, since the native
+/// processor doesn't consume compute units, and fails if the program isn't built there.
+#[tokio::test]
+async fn compute_units_within_baseline() {
+    if std::env::var_os("BPF_OUT_DIR").is_none() {
+        println!("Skipped: BPF_OUT_DIR is not set, run with `cargo test-bpf`");
+        return;
+    }
+    // A missing program would silently fall back to the native processor
+    assert!(
+        find_file("solana_token.so").is_some(),
+        "solana_token.so not found in BPF_OUT_DIR, build it with `cargo build-bpf`"
+    );
+    let mut program_test = program_test();
+    program_test.prefer_bpf(true);
+    let (mut context, test_contract, token_x, user) = setup(program_test, 1000).await;
+
+    let change_x_to_y = |amount| {
+        instruction::change_x_to_y(
+            &id(),
+            &user.account.pubkey(),
+            &user.user_wallet_x.pubkey(),
+            &token_x.mint.pubkey(),
+            &user.user_wallet_y.pubkey(),
+            &test_contract.pool_mint.pubkey(),
+            amount,
+        )
+    };
+
+    let mut measured = BTreeMap::new();
+    measured.insert(
+        "change_x_to_y_creating_pool_wallet",
+        measure(&mut context, change_x_to_y(100), &[&user.account]).await,
+    );
+    test_contract
+        .change_x_to_y(&mut context, &user, &token_x, 500)
+        .await
+        .unwrap();
+    measured.insert(
+        "change_x_to_y",
+        measure(&mut context, change_x_to_y(200), &[&user.account]).await,
+    );
+    measured.insert(
+        "change_y_to_x",
+        measure(
+            &mut context,
+            instruction::change_y_to_x(
+                &id(),
+                &user.account.pubkey(),
+                &user.user_wallet_x.pubkey(),
+                &user.user_wallet_y.pubkey(),
+                &test_contract.pool_mint.pubkey(),
+                300,
+            ),
+            &[&user.account],
+        )
+        .await,
+    );
+    measured.insert(
+        "change_x_to_y_exact_out",
+        measure(
+            &mut context,
+            instruction::change_x_to_y_exact_out(
+                &id(),
+                &user.account.pubkey(),
+                &user.user_wallet_x.pubkey(),
+                &token_x.mint.pubkey(),
+                &user.user_wallet_y.pubkey(),
+                &test_contract.pool_mint.pubkey(),
+                200,
+                200,
+            ),
+            &[&user.account],
+        )
+        .await,
+    );
+    measured.insert(
+        "change_y_to_x_exact_out",
+        measure(
+            &mut context,
+            instruction::change_y_to_x_exact_out(
+                &id(),
+                &user.account.pubkey(),
+                &user.user_wallet_x.pubkey(),
+                &user.user_wallet_y.pubkey(),
+                &test_contract.pool_mint.pubkey(),
+                300,
+                300,
+            ),
+            &[&user.account],
+        )
+        .await,
+    );
+    measured.insert(
+        "change_x_to_y_all",
+        measure(
+            &mut context,
+            instruction::change_x_to_y_all(
+                &id(),
+                &user.account.pubkey(),
+                &user.user_wallet_x.pubkey(),
+                &token_x.mint.pubkey(),
+                &user.user_wallet_y.pubkey(),
+                &test_contract.pool_mint.pubkey(),
+            ),
+            &[&user.account],
+        )
+        .await,
+    );
+    measured.insert(
+        "change_y_to_x_all",
+        measure(
+            &mut context,
+            instruction::change_y_to_x_all(
+                &id(),
+                &user.account.pubkey(),
+                &user.user_wallet_x.pubkey(),
+                &user.user_wallet_y.pubkey(),
+                &test_contract.pool_mint.pubkey(),
+            ),
+            &[&user.account],
+        )
+        .await,
+    );
+    measured.insert(
+        "quote",
+        measure(
+            &mut context,
+            instruction::quote(
+                &id(),
+                &user.user_wallet_x.pubkey(),
+                &test_contract.pool_mint.pubkey(),
+                300,
+                instruction::ChangeDirection::XtoY,
+            ),
+            &[&user.account],
+        )
+        .await,
+    );
+
+    let beneficiary = add_user(&mut context, &test_contract, &token_x, 0).await;
+    let vesting = new_keypair();
+    set_time(&mut context, 1_000).await;
+    let wrap_with_vesting = instruction::wrap_with_vesting(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        &vesting.pubkey(),
+        &beneficiary.account.pubkey(),
+        100,
+        1_010,
+        1_100,
+    );
+    measured.insert(
+        "wrap_with_vesting",
+        measure(
+            &mut context,
+            wrap_with_vesting.clone(),
+            &[&user.account, &vesting],
+        )
+        .await,
+    );
+    process(&mut context, wrap_with_vesting, &[&user.account, &vesting])
+        .await
+        .unwrap();
+    set_time(&mut context, 1_050).await;
+    measured.insert(
+        "claim_vested",
+        measure(
+            &mut context,
+            instruction::claim_vested(
+                &id(),
+                &beneficiary.account.pubkey(),
+                &vesting.pubkey(),
+                &beneficiary.user_wallet_y.pubkey(),
+            ),
+            &[&beneficiary.account],
+        )
+        .await,
+    );
+    measured.insert(
+        "revoke_vesting",
+        measure(
+            &mut context,
+            instruction::revoke_vesting(
+                &id(),
+                &user.account.pubkey(),
+                &vesting.pubkey(),
+                &user.user_wallet_y.pubkey(),
+            ),
+            &[&user.account],
+        )
+        .await,
+    );
+
+    let test_pool = TestPool::new();
+    let initialize_pool = instruction::initialize_pool(
+        &id(),
+        &test_pool.admin.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        &token_x.mint.pubkey(),
+        "Wrapped X",
+        "WX",
+        "https://example.com/pool.json",
+        PoolMode::Fixed,
+        0,
+        0,
+        0,
+    );
+    let payer = payer(&context);
+    process(
+        &mut context,
+        solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &test_pool.admin.pubkey(),
+            999999999,
+        ),
+        &[&payer],
+    )
+    .await
+    .unwrap();
+    let pool_signers = [&test_pool.admin, &test_pool.contract.pool_mint];
+    measured.insert(
+        "initialize_pool",
+        measure(&mut context, initialize_pool.clone(), &pool_signers).await,
+    );
+    process(&mut context, initialize_pool, &pool_signers)
+        .await
+        .unwrap();
+    measured.insert(
+        "update_pool_metadata",
+        measure(
+            &mut context,
+            instruction::update_pool_metadata(
+                &id(),
+                &test_pool.admin.pubkey(),
+                &test_pool.contract.pool_mint.pubkey(),
+                "Wrapped X",
+                "WX",
+                "https://example.com/v2.json",
+            ),
+            &[&test_pool.admin],
+        )
+        .await,
+    );
+    let pool_user = add_user(&mut context, &test_pool.contract, &token_x, 1000).await;
+    process(
+        &mut context,
+        instruction::change_x_to_y_pool(
+            &id(),
+            &pool_user.account.pubkey(),
+            &pool_user.user_wallet_x.pubkey(),
+            &token_x.mint.pubkey(),
+            &pool_user.user_wallet_y.pubkey(),
+            &test_pool.contract.pool_mint.pubkey(),
+            500,
+        ),
+        &[&pool_user.account],
+    )
+    .await
+    .unwrap();
+    measured.insert(
+        "change_x_to_y_pool",
+        measure(
+            &mut context,
+            instruction::change_x_to_y_pool(
+                &id(),
+                &pool_user.account.pubkey(),
+                &pool_user.user_wallet_x.pubkey(),
+                &token_x.mint.pubkey(),
+                &pool_user.user_wallet_y.pubkey(),
+                &test_pool.contract.pool_mint.pubkey(),
+                200,
+            ),
+            &[&pool_user.account],
+        )
+        .await,
+    );
+    measured.insert(
+        "skim",
+        measure(
+            &mut context,
+            instruction::skim(
+                &id(),
+                &test_pool.admin.pubkey(),
+                &test_pool.contract.pool_mint.pubkey(),
+                &user.user_wallet_x.pubkey(),
+            ),
+            &[&test_pool.admin],
+        )
+        .await,
+    );
+    measured.insert(
+        "verify_solvency",
+        measure(
+            &mut context,
+            instruction::verify_solvency(&id(), &test_pool.contract.pool_mint.pubkey()),
+            &[&pool_user.account],
+        )
+        .await,
+    );
+    measured.insert(
+        "change_y_to_x_pool",
+        measure(
+            &mut context,
+            instruction::change_y_to_x_pool(
+                &id(),
+                &pool_user.account.pubkey(),
+                &pool_user.user_wallet_x.pubkey(),
+                &pool_user.user_wallet_y.pubkey(),
+                &test_pool.contract.pool_mint.pubkey(),
+                300,
+            ),
+            &[&pool_user.account],
+        )
+        .await,
+    );
+
+    let shares_pool = TestPool::new();
+    shares_pool
+        .create(
+            &mut context,
+            &token_x,
+            "Shares of X",
+            "SX",
+            "https://example.com/shares.json",
+            PoolMode::Shares,
+            0,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+    let shares_user = add_user(&mut context, &shares_pool.contract, &token_x, 1000).await;
+    let change_x_to_y_shares = |amount| {
+        instruction::change_x_to_y_pool(
+            &id(),
+            &shares_user.account.pubkey(),
+            &shares_user.user_wallet_x.pubkey(),
+            &token_x.mint.pubkey(),
+            &shares_user.user_wallet_y.pubkey(),
+            &shares_pool.contract.pool_mint.pubkey(),
+            amount,
+        )
+    };
+    process(
+        &mut context,
+        change_x_to_y_shares(500),
+        &[&shares_user.account],
+    )
+    .await
+    .unwrap();
+    measured.insert(
+        "change_x_to_y_shares",
+        measure(
+            &mut context,
+            change_x_to_y_shares(200),
+            &[&shares_user.account],
+        )
+        .await,
+    );
+    measured.insert(
+        "change_y_to_x_shares",
+        measure(
+            &mut context,
+            instruction::change_y_to_x_pool(
+                &id(),
+                &shares_user.account.pubkey(),
+                &shares_user.user_wallet_x.pubkey(),
+                &shares_user.user_wallet_y.pubkey(),
+                &shares_pool.contract.pool_mint.pubkey(),
+                300_000,
+            ),
+            &[&shares_user.account],
+        )
+        .await,
+    );
+
+    let request_unwrap = instruction::request_unwrap(
+        &id(),
+        &shares_user.account.pubkey(),
+        &shares_user.user_wallet_y.pubkey(),
+        &shares_pool.contract.pool_mint.pubkey(),
+        300_000,
+        0,
+    );
+    measured.insert(
+        "request_unwrap",
+        measure(
+            &mut context,
+            request_unwrap.clone(),
+            &[&shares_user.account],
+        )
+        .await,
+    );
+    process(&mut context, request_unwrap, &[&shares_user.account])
+        .await
+        .unwrap();
+    measured.insert(
+        "claim_unwrap",
+        measure(
+            &mut context,
+            instruction::claim_unwrap(
+                &id(),
+                &shares_user.account.pubkey(),
+                &shares_user.user_wallet_x.pubkey(),
+                &shares_pool.contract.pool_mint.pubkey(),
+                0,
+            ),
+            &[&shares_user.account],
+        )
+        .await,
+    );
+
+    assert_eq!(measured.keys().copied().collect::<Vec<_>>(), PATHS);
+    if std::env::var_os("UPDATE_CU_BASELINE").is_some() {
+        let json = serde_json::to_string_pretty(&measured).unwrap() + "\n";
+        std::fs::write(BASELINE_PATH, json).unwrap();
+    }
+    let baseline = read_baseline();
+
+    let mut regressions = vec![];
+    for (path, units) in &measured {
+        println!("{}: {} compute units", path, units);
+        match baseline.get(*path) {
+            Some(&base) if units * 100 > base * (100 + THRESHOLD_PERCENT) => {
+                regressions.push(format!("{}: {} (baseline {})", path, units, base));
+            }
+            Some(_) => {}
+            None => regressions.push(format!("{}: {} (no baseline)", path, units)),
+        }
+    }
+    assert!(
+        regressions.is_empty(),
+        "compute units over the baseline by more than {}%, run with `UPDATE_CU_BASELINE=1` if expected:\n{}",
+        THRESHOLD_PERCENT,
+        regressions.join("\n")
+    );
+}
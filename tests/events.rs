@@ -0,0 +1,88 @@
+use borsh::BorshSerialize;
+use solana_program::pubkey::Pubkey;
+use solana_token::events::{decode_events, ContractEvent, SolvencyEvent, UnwrapEvent, WrapEvent};
+use solana_token::id;
+
+fn program_data(event: &ContractEvent) -> String {
+    format!(
+        "Program data: {}",
+        base64::encode(event.try_to_vec().unwrap())
+    )
+}
+
+fn wrap_event(amount: u64) -> ContractEvent {
+    ContractEvent::Wrap(WrapEvent {
+        pool_mint: Pubkey::new_unique(),
+        pool_wallet_x: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        amount_in: amount,
+        amount_out: amount,
+        fee: 0,
+        pool_wallet_x_balance: amount,
+    })
+}
+
+#[test]
+fn decode_wrap_and_unwrap() {
+    let wrap = wrap_event(100);
+    let unwrap = ContractEvent::Unwrap(UnwrapEvent {
+        pool_mint: Pubkey::new_unique(),
+        pool_wallet_x: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        amount_in: 40,
+        amount_out: 40,
+        fee: 0,
+        pool_wallet_x_balance: 60,
+    });
+    let logs = vec![
+        format!("Program {} invoke [1]", id()),
+        "Program log: Instruction: ChangeXtoY".to_string(),
+        format!("Program {} invoke [2]", spl_token::id()),
+        "Program log: Instruction: Transfer".to_string(),
+        format!("Program {} success", spl_token::id()),
+        program_data(&wrap),
+        format!("Program {} success", id()),
+        format!("Program {} invoke [1]", id()),
+        program_data(&unwrap),
+        format!("Program {} success", id()),
+    ];
+
+    assert_eq!(decode_events(&id(), &logs), vec![wrap, unwrap]);
+}
+
+#[test]
+fn skip_foreign_program_data() {
+    let other_program = Pubkey::new_unique();
+    let event = wrap_event(100);
+    let logs = vec![
+        format!("Program {} invoke [1]", other_program),
+        program_data(&event),
+        format!("Program {} invoke [2]", id()),
+        program_data(&event),
+        format!("Program {} success", id()),
+        program_data(&event),
+        "Program data: bm90IGFuIGV2ZW50".to_string(),
+        format!("Program {} success", other_program),
+    ];
+
+    assert_eq!(decode_events(&id(), &logs), vec![event]);
+}
+
+#[test]
+fn decode_solvency() {
+    let solvency = ContractEvent::Solvency(SolvencyEvent {
+        pool_mint: Pubkey::new_unique(),
+        token_x_mint: Pubkey::new_unique(),
+        supply: 150,
+        vault_balance: 110,
+        pending_unwrap: 10,
+        ratio_bps: Some(6666),
+    });
+    let logs = vec![
+        format!("Program {} invoke [1]", id()),
+        "Program log: Instruction: VerifySolvency".to_string(),
+        program_data(&solvency),
+        format!("Program {} failed: custom program error: 0x75", id()),
+    ];
+    assert_eq!(decode_events(&id(), &logs), vec![solvency]);
+}
@@ -0,0 +1,94 @@
+mod utils;
+
+use crate::contract::TestContract;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::instruction::Instruction;
+use solana_program_test::*;
+use solana_sdk::signature::Signer;
+use solana_token::error::CrateError;
+use solana_token::{find_program_address, id, instruction};
+use utils::*;
+
+fn change_x_to_y_exact_out(
+    test_contract: &TestContract,
+    token_x: &TokenX,
+    user: &User,
+    amount_out: u64,
+    maximum_in: u64,
+) -> Instruction {
+    instruction::change_x_to_y_exact_out(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        amount_out,
+        maximum_in,
+    )
+}
+
+fn change_y_to_x_exact_out(
+    test_contract: &TestContract,
+    user: &User,
+    amount_out: u64,
+    maximum_in: u64,
+) -> Instruction {
+    instruction::change_y_to_x_exact_out(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        amount_out,
+        maximum_in,
+    )
+}
+
+#[tokio::test]
+async fn success() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+
+    let ix = change_x_to_y_exact_out(&test_contract, &token_x, &user, 60, 60);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    let ix = change_y_to_x_exact_out(&test_contract, &user, 20, 25);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+
+    let (pool_wallet_x, _) = find_program_address(&id(), &user.user_wallet_x.pubkey());
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_x.pubkey()).await,
+        60
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_y.pubkey()).await,
+        40
+    );
+    assert_eq!(get_token_balance(&mut context, &pool_wallet_x).await, 40);
+}
+
+#[tokio::test]
+async fn fail_x_to_y_over_maximum_in() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+
+    let ix = change_x_to_y_exact_out(&test_contract, &token_x, &user, 60, 59);
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::MaximumInExceeded)
+    );
+}
+
+#[tokio::test]
+async fn fail_y_to_x_over_maximum_in() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+
+    test_contract
+        .change_x_to_y(&mut context, &user, &token_x, 100)
+        .await
+        .unwrap();
+    let ix = change_y_to_x_exact_out(&test_contract, &user, 60, 0);
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::MaximumInExceeded)
+    );
+}
@@ -0,0 +1,208 @@
+mod utils;
+
+use crate::contract::TestContract;
+use crate::pool::TestPool;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::instruction::Instruction;
+use solana_program_test::*;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::TransactionError;
+use solana_token::error::CrateError;
+use solana_token::instruction::ChangeDirection;
+use solana_token::state::PoolMode;
+use solana_token::{id, instruction};
+use utils::*;
+
+const START: i64 = 1_000;
+const SUBSCRIPTION_DEADLINE: i64 = START + 100;
+const MATURITY: i64 = START + 200;
+
+/// Fixed-term pool subscribed until `SUBSCRIPTION_DEADLINE` and maturing at `MATURITY`,
+/// unwrapping after `unwrap_delay`, and a user holding 100 token X, at `START`
+async fn setup_pool(unwrap_delay: u32) -> (ProgramTestContext, TestPool, TokenX, User) {
+    let mut context = program_test().start_with_context().await;
+    set_time(&mut context, START).await;
+
+    let token_x = TokenX::new();
+    token_x.init(&mut context).await.unwrap();
+    let test_pool = TestPool::new();
+    test_pool
+        .create(
+            &mut context,
+            &token_x,
+            "Term X",
+            "TX",
+            "https://example.com/term.json",
+            PoolMode::Fixed,
+            unwrap_delay,
+            SUBSCRIPTION_DEADLINE,
+            MATURITY,
+        )
+        .await
+        .unwrap();
+
+    let user = add_user(&mut context, &test_pool.contract, &token_x, 100).await;
+    (context, test_pool, token_x, user)
+}
+
+fn change_x_to_y(
+    contract: &TestContract,
+    token_x: &TokenX,
+    user: &User,
+    amount: u64,
+) -> Instruction {
+    instruction::change_x_to_y_pool(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &contract.pool_mint.pubkey(),
+        amount,
+    )
+}
+
+fn change_y_to_x(contract: &TestContract, user: &User, amount: u64) -> Instruction {
+    instruction::change_y_to_x_pool(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &contract.pool_mint.pubkey(),
+        amount,
+    )
+}
+
+fn quote(test_pool: &TestPool, user: &User, direction: ChangeDirection) -> Instruction {
+    instruction::quote_pool(
+        &id(),
+        &user.user_wallet_x.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        10,
+        direction,
+    )
+}
+
+#[tokio::test]
+async fn changes_within_windows() {
+    let (mut context, test_pool, token_x, user) = setup_pool(0).await;
+
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &user, 50);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    assert_eq!(
+        process_error(
+            &mut context,
+            change_y_to_x(&test_pool.contract, &user, 10),
+            &[&user.account]
+        )
+        .await,
+        crate_error(0, CrateError::NotMatured)
+    );
+    let payer = payer(&context);
+    assert_eq!(
+        process_error(
+            &mut context,
+            quote(&test_pool, &user, ChangeDirection::YtoX),
+            &[&payer]
+        )
+        .await,
+        crate_error(0, CrateError::NotMatured)
+    );
+
+    // The deadline is the last second of the subscription
+    set_time(&mut context, SUBSCRIPTION_DEADLINE).await;
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &user, 30);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+
+    set_time(&mut context, SUBSCRIPTION_DEADLINE + 1).await;
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &user, 20);
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::SubscriptionClosed)
+    );
+    assert_eq!(
+        process_error(
+            &mut context,
+            quote(&test_pool, &user, ChangeDirection::XtoY),
+            &[&payer]
+        )
+        .await,
+        crate_error(0, CrateError::SubscriptionClosed)
+    );
+
+    set_time(&mut context, MATURITY).await;
+    process(
+        &mut context,
+        change_y_to_x(&test_pool.contract, &user, 80),
+        &[&user.account],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_x.pubkey()).await,
+        100
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_y.pubkey()).await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn fails_to_request_unwrap_before_maturity() {
+    let (mut context, test_pool, token_x, user) = setup_pool(50).await;
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &user, 50);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+
+    let request_unwrap = |amount| {
+        instruction::request_unwrap(
+            &id(),
+            &user.account.pubkey(),
+            &user.user_wallet_y.pubkey(),
+            &test_pool.contract.pool_mint.pubkey(),
+            amount,
+            0,
+        )
+    };
+    assert_eq!(
+        process_error(&mut context, request_unwrap(50), &[&user.account]).await,
+        crate_error(0, CrateError::NotMatured)
+    );
+
+    set_time(&mut context, MATURITY).await;
+    process(&mut context, request_unwrap(50), &[&user.account])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_y.pubkey()).await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn fails_to_mature_before_subscription_deadline() {
+    let mut context = program_test().start_with_context().await;
+    let token_x = TokenX::new();
+    token_x.init(&mut context).await.unwrap();
+    let test_pool = TestPool::new();
+
+    let result = test_pool
+        .create(
+            &mut context,
+            &token_x,
+            "Term X",
+            "TX",
+            "https://example.com/term.json",
+            PoolMode::Fixed,
+            0,
+            MATURITY,
+            SUBSCRIPTION_DEADLINE,
+        )
+        .await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+}
@@ -0,0 +1,235 @@
+use borsh::BorshSerialize;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_token::idl::{generate, instruction_variants, IdlField, IdlTypeDef};
+use solana_token::instruction::{ChangeResult, ContractInstruction, INSTRUCTION_VERSION};
+use solana_token::state::{Pool, PoolMode, Ticket, Vesting};
+
+const IDL_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/idl/solana_token.json");
+
+/// Number of `ContractInstruction` variants
+const VARIANTS: usize = 16;
+
+/// Position of the variant in `ContractInstruction`. The match has no wildcard arm,
+/// so a new instruction doesn't compile until it's listed here
+fn variant_index(instruction: &ContractInstruction) -> usize {
+    match instruction {
+        ContractInstruction::ChangeXtoY { .. } => 0,
+        ContractInstruction::ChangeYtoX { .. } => 1,
+        ContractInstruction::Quote { .. } => 2,
+        ContractInstruction::VerifySolvency => 3,
+        ContractInstruction::ChangeXtoYExactOut { .. } => 4,
+        ContractInstruction::ChangeYtoXExactOut { .. } => 5,
+        ContractInstruction::ChangeXtoYAll => 6,
+        ContractInstruction::ChangeYtoXAll => 7,
+        ContractInstruction::WrapWithVesting { .. } => 8,
+        ContractInstruction::ClaimVested => 9,
+        ContractInstruction::RevokeVesting => 10,
+        ContractInstruction::InitializePool { .. } => 11,
+        ContractInstruction::UpdatePoolMetadata { .. } => 12,
+        ContractInstruction::Skim => 13,
+        ContractInstruction::RequestUnwrap { .. } => 14,
+        ContractInstruction::ClaimUnwrap => 15,
+    }
+}
+
+/// Reads a value of an IDL type from borsh data, as serde would serialize it to JSON
+fn decode(types: &[IdlTypeDef], ty: &str, data: &mut &[u8]) -> Value {
+    let mut take = |len: usize| {
+        assert!(data.len() >= len, "{} past the end of the data", ty);
+        let (taken, rest) = data.split_at(len);
+        *data = rest;
+        taken
+    };
+    match ty {
+        "bool" => Value::from(take(1)[0] != 0),
+        "u8" => Value::from(take(1)[0]),
+        "u32" => Value::from(u32::from_le_bytes(take(4).try_into().unwrap())),
+        "u64" => Value::from(u64::from_le_bytes(take(8).try_into().unwrap())),
+        "i64" => Value::from(i64::from_le_bytes(take(8).try_into().unwrap())),
+        "publicKey" => Value::from(take(32).to_vec()),
+        "string" => {
+            let len = u32::from_le_bytes(take(4).try_into().unwrap());
+            Value::from(String::from_utf8(take(len as usize).to_vec()).unwrap())
+        }
+        _ => match types.iter().find(|def| type_name(def) == ty) {
+            // Variants without fields
+            Some(IdlTypeDef::Enum { variants, .. }) => Value::from(variants[take(1)[0] as usize]),
+            Some(IdlTypeDef::Struct { fields, .. }) => decode_fields(types, fields, data),
+            None => panic!("unknown type {}", ty),
+        },
+    }
+}
+
+fn type_name(def: &IdlTypeDef) -> &'static str {
+    match def {
+        IdlTypeDef::Struct { name, .. } | IdlTypeDef::Enum { name, .. } => name,
+    }
+}
+
+fn decode_fields(types: &[IdlTypeDef], fields: &[IdlField], data: &mut &[u8]) -> Value {
+    let decoded: Map<String, Value> = fields
+        .iter()
+        .map(|field| (field.name.to_string(), decode(types, field.ty, data)))
+        .collect();
+    Value::from(decoded)
+}
+
+/// Decodes `data` with the IDL fields, which must read all of it, and compares the
+/// result with `value`. The values of the fields must be distinct for a swap of
+/// two fields of the same type to be noticed
+fn assert_fields_match<T: Serialize>(
+    types: &[IdlTypeDef],
+    fields: &[IdlField],
+    data: &[u8],
+    value: &T,
+    name: &str,
+) {
+    let mut rest = data;
+    let decoded = decode_fields(types, fields, &mut rest);
+    assert!(
+        rest.is_empty(),
+        "{} has {} bytes not described",
+        name,
+        rest.len()
+    );
+    assert_eq!(decoded, serde_json::to_value(value).unwrap(), "{}", name);
+}
+
+/// Run with `UPDATE_IDL=1` to rewrite the checked-in IDL
+#[test]
+fn idl_is_up_to_date() {
+    let generated = serde_json::to_string_pretty(&generate()).unwrap() + "\n";
+    if std::env::var_os("UPDATE_IDL").is_some() {
+        std::fs::write(IDL_PATH, &generated).unwrap();
+    }
+    let checked_in = std::fs::read_to_string(IDL_PATH).unwrap_or_default();
+    assert!(
+        checked_in == generated,
+        "{} is out of date, run `UPDATE_IDL=1 cargo test --test idl`",
+        IDL_PATH
+    );
+}
+
+#[test]
+fn every_instruction_is_described() {
+    let variants = instruction_variants();
+    let mut indexes: Vec<usize> = variants.iter().map(variant_index).collect();
+    indexes.sort_unstable();
+    assert_eq!(indexes, (0..VARIANTS).collect::<Vec<_>>());
+
+    let mut tags: Vec<u8> = variants.iter().map(ContractInstruction::tag).collect();
+    tags.sort_unstable();
+    tags.dedup();
+    assert_eq!(tags.len(), variants.len());
+
+    // Tags without an instruction in the IDL must not decode with any arguments
+    let max_args_len = variants
+        .iter()
+        .map(|instruction| instruction.pack().len() - 2)
+        .max()
+        .unwrap();
+    for tag in (0..=u8::MAX).filter(|tag| !tags.contains(tag)) {
+        for args_len in 0..=max_args_len + 8 {
+            let mut data = vec![INSTRUCTION_VERSION, tag];
+            data.resize(2 + args_len, 0);
+            assert!(ContractInstruction::unpack(&data).is_err(), "tag {}", tag);
+        }
+    }
+}
+
+#[test]
+fn fields_match_encoding() {
+    let idl = generate();
+
+    for (described, instruction) in idl.instructions.iter().zip(instruction_variants()) {
+        let data = instruction.pack();
+        assert_eq!(data[..2], [INSTRUCTION_VERSION, described.discriminant]);
+        // serde writes variants without fields as their name
+        let args = match serde_json::to_value(&instruction).unwrap() {
+            Value::String(_) => Value::from(Map::new()),
+            Value::Object(variant) => variant[described.name].clone(),
+            value => panic!("unexpected {}", value),
+        };
+        assert_fields_match(
+            &idl.types,
+            &described.args,
+            &data[2..],
+            &args,
+            described.name,
+        );
+    }
+
+    let account = |name| match idl.accounts.iter().find(|def| type_name(def) == name) {
+        Some(IdlTypeDef::Struct { fields, .. }) => fields,
+        _ => panic!("no account {}", name),
+    };
+    let key = |byte| Pubkey::new_from_array([byte; 32]);
+    let vesting = Vesting {
+        is_initialized: true,
+        pool_mint: key(1),
+        grantor: key(2),
+        beneficiary: key(3),
+        start_ts: -1,
+        cliff_ts: 2,
+        end_ts: 3,
+        total: 4,
+        claimed: 5,
+        escrow_bump: 6,
+    };
+    let pool = Pool {
+        is_initialized: true,
+        admin: key(1),
+        pool_mint: key(2),
+        token_x_mint: key(3),
+        mode: PoolMode::Shares,
+        unwrap_delay: 1,
+        pending_unwrap: 2,
+        subscription_deadline: 3,
+        maturity: 4,
+        bump: 5,
+        vault_bump: 6,
+    };
+    let ticket = Ticket {
+        is_initialized: true,
+        owner: key(1),
+        pool: key(2),
+        amount: 1,
+        unlock_ts: 2,
+    };
+    let mut data = [0; Vesting::LEN];
+    vesting.pack_into_slice(&mut data);
+    assert_fields_match(&idl.types, account("Vesting"), &data, &vesting, "Vesting");
+    let mut data = [0; Pool::LEN];
+    pool.pack_into_slice(&mut data);
+    assert_fields_match(&idl.types, account("Pool"), &data, &pool, "Pool");
+    let mut data = [0; Ticket::LEN];
+    ticket.pack_into_slice(&mut data);
+    assert_fields_match(&idl.types, account("Ticket"), &data, &ticket, "Ticket");
+    // Accounts of the program are told apart by their length
+    assert_ne!(Pool::LEN, Vesting::LEN);
+    assert_ne!(Ticket::LEN, Vesting::LEN);
+    assert_ne!(Ticket::LEN, Pool::LEN);
+
+    let change_result = ChangeResult {
+        amount_in: 1,
+        amount_out: 2,
+    };
+    let fields = match idl
+        .types
+        .iter()
+        .find(|def| type_name(def) == "ChangeResult")
+    {
+        Some(IdlTypeDef::Struct { fields, .. }) => fields,
+        _ => panic!("no type ChangeResult"),
+    };
+    assert_fields_match(
+        &idl.types,
+        fields,
+        &change_result.try_to_vec().unwrap(),
+        &change_result,
+        "ChangeResult",
+    );
+}
@@ -0,0 +1,105 @@
+use solana_program::program_error::ProgramError;
+use solana_token::error::CrateError;
+use solana_token::instruction::{ChangeDirection, ContractInstruction};
+
+/// Wire format of every instruction, these bytes must never change
+#[test]
+fn golden_bytes() {
+    let cases = [
+        (
+            ContractInstruction::ChangeXtoY {
+                amount: 0x0102030405060708,
+            },
+            vec![128, 0, 8, 7, 6, 5, 4, 3, 2, 1],
+        ),
+        (
+            ContractInstruction::ChangeYtoX { amount: 42 },
+            vec![128, 1, 42, 0, 0, 0, 0, 0, 0, 0],
+        ),
+        (
+            ContractInstruction::Quote {
+                amount: 42,
+                direction: ChangeDirection::XtoY,
+            },
+            vec![128, 2, 42, 0, 0, 0, 0, 0, 0, 0, 0],
+        ),
+        (
+            ContractInstruction::Quote {
+                amount: 42,
+                direction: ChangeDirection::YtoX,
+            },
+            vec![128, 2, 42, 0, 0, 0, 0, 0, 0, 0, 1],
+        ),
+        (ContractInstruction::VerifySolvency, vec![128, 3]),
+        (
+            ContractInstruction::ChangeXtoYExactOut {
+                amount_out: 42,
+                maximum_in: 43,
+            },
+            vec![128, 4, 42, 0, 0, 0, 0, 0, 0, 0, 43, 0, 0, 0, 0, 0, 0, 0],
+        ),
+        (
+            ContractInstruction::ChangeYtoXExactOut {
+                amount_out: 42,
+                maximum_in: 43,
+            },
+            vec![128, 5, 42, 0, 0, 0, 0, 0, 0, 0, 43, 0, 0, 0, 0, 0, 0, 0],
+        ),
+        (ContractInstruction::ChangeXtoYAll, vec![128, 6]),
+        (ContractInstruction::ChangeYtoXAll, vec![128, 7]),
+        (
+            ContractInstruction::WrapWithVesting {
+                amount: 42,
+                cliff_ts: 1,
+                end_ts: -1,
+            },
+            vec![
+                128, 8, 42, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255,
+                255, 255, 255,
+            ],
+        ),
+        (ContractInstruction::ClaimVested, vec![128, 9]),
+        (ContractInstruction::RevokeVesting, vec![128, 10]),
+    ];
+
+    for (instruction, bytes) in cases {
+        assert_eq!(instruction.pack(), bytes, "{:?}", instruction);
+        assert_eq!(ContractInstruction::unpack(&bytes).unwrap(), instruction);
+    }
+}
+
+#[test]
+fn legacy_bytes() {
+    assert_eq!(
+        ContractInstruction::unpack(&[0, 42, 0, 0, 0, 0, 0, 0, 0]).unwrap(),
+        ContractInstruction::ChangeXtoY { amount: 42 }
+    );
+    assert_eq!(
+        ContractInstruction::unpack(&[1, 42, 0, 0, 0, 0, 0, 0, 0]).unwrap(),
+        ContractInstruction::ChangeYtoX { amount: 42 }
+    );
+}
+
+#[test]
+fn invalid_bytes() {
+    let invalid: [&[u8]; 9] = [
+        &[],
+        &[128],
+        &[128, 0, 42],
+        &[128, 3, 0],
+        &[128, 4, 42, 0, 0, 0, 0, 0, 0, 0],
+        &[128, 6, 0],
+        &[128, 0, 42, 0, 0, 0, 0, 0, 0, 0, 0],
+        &[127, 0, 42, 0, 0, 0, 0, 0, 0, 0],
+        &[2, 42, 0, 0, 0, 0, 0, 0, 0, 0],
+    ];
+
+    for data in invalid {
+        assert_eq!(
+            ContractInstruction::unpack(data),
+            Err(ProgramError::from(CrateError::InvalidInstruction)),
+            "{:?}",
+            data
+        );
+    }
+}
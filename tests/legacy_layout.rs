@@ -0,0 +1,111 @@
+mod utils;
+
+use crate::contract::TestContract;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::{system_program, sysvar};
+use solana_program_test::*;
+use solana_sdk::signature::Signer;
+use solana_token::error::CrateError;
+use solana_token::{find_program_address, id};
+use utils::*;
+
+/// `ChangeXtoY` as sent by clients built before the instruction data was
+/// versioned: Borsh encoding and the original ten accounts
+fn legacy_change_x_to_y(
+    test_contract: &TestContract,
+    token_x: &TokenX,
+    user: &User,
+    amount: u64,
+) -> Instruction {
+    let pool_mint = test_contract.pool_mint.pubkey();
+    let user_wallet_x = user.user_wallet_x.pubkey();
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new_readonly(user.account.pubkey(), true),
+            AccountMeta::new(user_wallet_x, false),
+            AccountMeta::new(token_x.mint.pubkey(), false),
+            AccountMeta::new(user.user_wallet_y.pubkey(), false),
+            AccountMeta::new(pool_mint, false),
+            AccountMeta::new_readonly(find_program_address(&id(), &pool_mint).0, false),
+            AccountMeta::new(find_program_address(&id(), &user_wallet_x).0, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: [&[0][..], &amount.to_le_bytes()].concat(),
+    }
+}
+
+/// `ChangeYtoX` as sent by clients built before the instruction data was
+/// versioned
+fn legacy_change_y_to_x(test_contract: &TestContract, user: &User, amount: u64) -> Instruction {
+    let user_wallet_x = user.user_wallet_x.pubkey();
+    Instruction {
+        program_id: id(),
+        accounts: vec![
+            AccountMeta::new_readonly(user.account.pubkey(), true),
+            AccountMeta::new(user_wallet_x, false),
+            AccountMeta::new(user.user_wallet_y.pubkey(), false),
+            AccountMeta::new(test_contract.pool_mint.pubkey(), false),
+            AccountMeta::new(find_program_address(&id(), &user_wallet_x).0, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: [&[1][..], &amount.to_le_bytes()].concat(),
+    }
+}
+
+/// Token X of the user, pool tokens of the user and token X of the pool wallet
+async fn balances(context: &mut ProgramTestContext, user: &User) -> (u64, u64, u64) {
+    let user_wallet_x = user.user_wallet_x.pubkey();
+    (
+        get_token_balance(context, &user_wallet_x).await,
+        get_token_balance(context, &user.user_wallet_y.pubkey()).await,
+        get_token_balance(context, &find_program_address(&id(), &user_wallet_x).0).await,
+    )
+}
+
+#[tokio::test]
+async fn legacy_change_paid_by_authority() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+
+    // The fee payer is writable, so the authority pays for the pool wallet
+    let ix = legacy_change_x_to_y(&test_contract, &token_x, &user, 100);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    assert_eq!(balances(&mut context, &user).await, (0, 100, 100));
+
+    let ix = legacy_change_y_to_x(&test_contract, &user, 40);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    assert_eq!(balances(&mut context, &user).await, (40, 60, 60));
+}
+
+#[tokio::test]
+async fn legacy_change_with_read_only_authority() {
+    let (mut context, test_contract, token_x, user) = setup(program_test(), 100).await;
+    let payer = payer(&context);
+
+    // Nobody pays for the pool wallet of the first change
+    let ix = legacy_change_x_to_y(&test_contract, &token_x, &user, 50);
+    assert_eq!(
+        process_error(&mut context, ix, &[&payer, &user.account]).await,
+        crate_error(0, CrateError::AccountNotWritable)
+    );
+
+    test_contract
+        .change_x_to_y(&mut context, &user, &token_x, 50)
+        .await
+        .unwrap();
+    let ix = legacy_change_x_to_y(&test_contract, &token_x, &user, 30);
+    process(&mut context, ix, &[&payer, &user.account])
+        .await
+        .unwrap();
+    assert_eq!(balances(&mut context, &user).await, (20, 80, 80));
+
+    let ix = legacy_change_y_to_x(&test_contract, &user, 80);
+    process(&mut context, ix, &[&payer, &user.account])
+        .await
+        .unwrap();
+    assert_eq!(balances(&mut context, &user).await, (100, 0, 0));
+}
@@ -0,0 +1,311 @@
+mod utils;
+
+use crate::contract::TestContract;
+use crate::token_x::TokenX;
+use crate::user::User;
+use proptest::prelude::*;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+use solana_token::error::CrateError;
+use solana_token::{find_program_address, id, instruction};
+use spl_token::error::TokenError;
+use std::collections::HashSet;
+use utils::*;
+
+const USERS: usize = 3;
+const INITIAL_X: u64 = 1000;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Wrap {
+        user: usize,
+        amount: u64,
+    },
+    /// Token X is paid to the wallet passed, whoever burns the pool tokens
+    Unwrap {
+        user: usize,
+        wallet_x_of: usize,
+        amount: u64,
+    },
+    Transfer {
+        from: usize,
+        to: usize,
+        amount: u64,
+    },
+    /// Wrap the token X of the next user
+    WrapFromOtherWallet {
+        user: usize,
+        amount: u64,
+    },
+    /// Unwrap from the pool wallet of the next user
+    UnwrapFromOtherPoolWallet {
+        user: usize,
+        amount: u64,
+    },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let user = 0..USERS;
+    let amount = 0..=400u64;
+    prop_oneof![
+        3 => (user.clone(), amount.clone()).prop_map(|(user, amount)| Op::Wrap { user, amount }),
+        3 => (user.clone(), user.clone(), amount.clone()).prop_map(|(user, wallet_x_of, amount)| {
+            Op::Unwrap { user, wallet_x_of, amount }
+        }),
+        2 => (user.clone(), user.clone(), amount.clone())
+            .prop_map(|(from, to, amount)| Op::Transfer { from, to, amount }),
+        1 => (user.clone(), amount.clone())
+            .prop_map(|(user, amount)| Op::WrapFromOtherWallet { user, amount }),
+        1 => (user, amount).prop_map(|(user, amount)| Op::UnwrapFromOtherPoolWallet { user, amount }),
+    ]
+}
+
+/// Expected balances, in token units
+#[derive(Debug, Default, PartialEq)]
+struct Model {
+    wallet_x: [u64; USERS],
+    wallet_y: [u64; USERS],
+    /// `None` until the first wrap creates the pool wallet
+    pool_wallet_x: [Option<u64>; USERS],
+    supply: u64,
+}
+
+impl Model {
+    fn new() -> Self {
+        Self {
+            wallet_x: [INITIAL_X; USERS],
+            ..Self::default()
+        }
+    }
+
+    /// Applies `op` if the program accepts it, or returns the expected error
+    fn apply(&mut self, op: &Op) -> Result<(), TransactionError> {
+        match *op {
+            Op::Wrap { user, amount } => {
+                if self.wallet_x[user] < amount {
+                    return Err(crate_error(0, CrateError::NotEnoughTokens));
+                }
+                self.wallet_x[user] -= amount;
+                *self.pool_wallet_x[user].get_or_insert(0) += amount;
+                self.wallet_y[user] += amount;
+                self.supply += amount;
+            }
+            Op::Unwrap {
+                user,
+                wallet_x_of,
+                amount,
+            } => {
+                let pool_wallet_x = self.pool_wallet_x[wallet_x_of]
+                    .as_mut()
+                    .ok_or_else(|| crate_error(0, CrateError::ExpectedAccount))?;
+                if *pool_wallet_x < amount {
+                    return Err(crate_error(0, CrateError::InsufficientPoolBalance));
+                }
+                if self.wallet_y[user] < amount {
+                    return Err(crate_error(0, CrateError::NotEnoughTokens));
+                }
+                *pool_wallet_x -= amount;
+                self.wallet_x[wallet_x_of] += amount;
+                self.wallet_y[user] -= amount;
+                self.supply -= amount;
+            }
+            Op::Transfer { from, to, amount } => {
+                if self.wallet_y[from] < amount {
+                    return Err(TransactionError::InstructionError(
+                        0,
+                        InstructionError::Custom(TokenError::InsufficientFunds as u32),
+                    ));
+                }
+                self.wallet_y[from] -= amount;
+                self.wallet_y[to] += amount;
+            }
+            Op::WrapFromOtherWallet { .. } => {
+                return Err(crate_error(0, CrateError::InvalidWalletOwner));
+            }
+            Op::UnwrapFromOtherPoolWallet { .. } => {
+                return Err(crate_error(0, CrateError::InvalidPoolWalletAddress));
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Env {
+    context: ProgramTestContext,
+    contract: TestContract,
+    token_x: TokenX,
+    users: Vec<User>,
+    sent: HashSet<Signature>,
+}
+
+impl Env {
+    async fn new() -> Self {
+        let mut context = program_test().start_with_context().await;
+        let contract = TestContract::new();
+        contract.create(&mut context).await.unwrap();
+        let token_x = TokenX::new();
+        token_x.init(&mut context).await.unwrap();
+        let mut users = vec![];
+        for _ in 0..USERS {
+            users.push(add_user(&mut context, &contract, &token_x, INITIAL_X).await);
+        }
+        Self {
+            context,
+            contract,
+            token_x,
+            users,
+            sent: HashSet::new(),
+        }
+    }
+
+    fn pool_wallet_x(&self, user: usize) -> Pubkey {
+        find_program_address(&id(), &self.users[user].user_wallet_x.pubkey()).0
+    }
+
+    fn change_x_to_y(&self, user: usize, wallet_x_of: usize, amount: u64) -> Instruction {
+        instruction::change_x_to_y(
+            &id(),
+            &self.users[user].account.pubkey(),
+            &self.users[wallet_x_of].user_wallet_x.pubkey(),
+            &self.token_x.mint.pubkey(),
+            &self.users[user].user_wallet_y.pubkey(),
+            &self.contract.pool_mint.pubkey(),
+            amount,
+        )
+    }
+
+    fn change_y_to_x(&self, user: usize, wallet_x_of: usize, amount: u64) -> Instruction {
+        instruction::change_y_to_x(
+            &id(),
+            &self.users[user].account.pubkey(),
+            &self.users[wallet_x_of].user_wallet_x.pubkey(),
+            &self.users[user].user_wallet_y.pubkey(),
+            &self.contract.pool_mint.pubkey(),
+            amount,
+        )
+    }
+
+    fn instruction(&self, op: &Op) -> Instruction {
+        match *op {
+            Op::Wrap { user, amount } => self.change_x_to_y(user, user, amount),
+            Op::Unwrap {
+                user,
+                wallet_x_of,
+                amount,
+            } => self.change_y_to_x(user, wallet_x_of, amount),
+            Op::Transfer { from, to, amount } => spl_token::instruction::transfer(
+                &spl_token::id(),
+                &self.users[from].user_wallet_y.pubkey(),
+                &self.users[to].user_wallet_y.pubkey(),
+                &self.users[from].account.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+            Op::WrapFromOtherWallet { user, amount } => {
+                self.change_x_to_y(user, (user + 1) % USERS, amount)
+            }
+            Op::UnwrapFromOtherPoolWallet { user, amount } => {
+                let mut ix = self.change_y_to_x(user, user, amount);
+                ix.accounts[4].pubkey = self.pool_wallet_x((user + 1) % USERS);
+                ix
+            }
+        }
+    }
+
+    fn signer(&self, op: &Op) -> &Keypair {
+        let user = match *op {
+            Op::Wrap { user, .. }
+            | Op::Unwrap { user, .. }
+            | Op::WrapFromOtherWallet { user, .. }
+            | Op::UnwrapFromOtherPoolWallet { user, .. } => user,
+            Op::Transfer { from, .. } => from,
+        };
+        &self.users[user].account
+    }
+
+    async fn process(&mut self, op: &Op) -> Result<(), TransactionError> {
+        let ix = self.instruction(op);
+        let mut tx = Transaction::new_with_payer(&[ix], Some(&self.signer(op).pubkey()));
+        tx.sign(&[self.signer(op)], self.context.last_blockhash);
+        // The same operation twice in a slot would be rejected as already processed
+        if !self.sent.insert(tx.signatures[0]) {
+            let slot = self.context.banks_client.get_root_slot().await.unwrap();
+            self.context.warp_to_slot(slot + 2).unwrap();
+            self.context.last_blockhash = self
+                .context
+                .banks_client
+                .get_latest_blockhash()
+                .await
+                .unwrap();
+            tx.sign(&[self.signer(op)], self.context.last_blockhash);
+            self.sent.insert(tx.signatures[0]);
+        }
+        self.context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .map_err(|err| err.unwrap())
+    }
+
+    async fn token_balance(&mut self, pubkey: Pubkey) -> Option<u64> {
+        let account = self
+            .context
+            .banks_client
+            .get_account(pubkey)
+            .await
+            .unwrap()?;
+        Some(
+            spl_token::state::Account::unpack(&account.data)
+                .unwrap()
+                .amount,
+        )
+    }
+
+    async fn state(&mut self) -> Model {
+        let mut state = Model::default();
+        for user in 0..USERS {
+            state.wallet_x[user] = self
+                .token_balance(self.users[user].user_wallet_x.pubkey())
+                .await
+                .unwrap();
+            state.wallet_y[user] = self
+                .token_balance(self.users[user].user_wallet_y.pubkey())
+                .await
+                .unwrap();
+            state.pool_wallet_x[user] = self.token_balance(self.pool_wallet_x(user)).await;
+        }
+        let pool_mint = get_account(&mut self.context, &self.contract.pool_mint.pubkey()).await;
+        state.supply = spl_token::state::Mint::unpack(&pool_mint.data)
+            .unwrap()
+            .supply;
+        state
+    }
+}
+
+async fn run(ops: Vec<Op>) {
+    let mut env = Env::new().await;
+    let mut model = Model::new();
+    for op in &ops {
+        let expected = model.apply(op);
+        assert_eq!(env.process(op).await, expected, "{:?}", op);
+
+        let state = env.state().await;
+        assert_eq!(state, model, "after {:?}", op);
+        let pool_wallets_x: u64 = state.pool_wallet_x.iter().flatten().sum();
+        assert!(pool_wallets_x >= state.supply, "after {:?}", op);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn pool_wallets_cover_supply(ops in prop::collection::vec(op(), 1..24)) {
+        tokio::runtime::Runtime::new().unwrap().block_on(run(ops));
+    }
+}
@@ -0,0 +1,315 @@
+use solana_program::instruction::Instruction;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_token::error::CrateError;
+use solana_token::instruction::{self, ChangeDirection, ContractInstruction};
+use solana_token::parser::{parse_instruction, UNKNOWN_ACCOUNT};
+use solana_token::state::PoolMode;
+use solana_token::{find_program_address, id};
+
+fn builder_instructions() -> Vec<Instruction> {
+    let authority = Pubkey::new_unique();
+    let user_wallet_x = Pubkey::new_unique();
+    let user_wallet_y = Pubkey::new_unique();
+    let token_x_mint = Pubkey::new_unique();
+    let pool_mint = Pubkey::new_unique();
+    let vesting = Pubkey::new_unique();
+    let beneficiary = Pubkey::new_unique();
+    vec![
+        instruction::change_x_to_y(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &token_x_mint,
+            &user_wallet_y,
+            &pool_mint,
+            10,
+        ),
+        instruction::change_y_to_x(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &user_wallet_y,
+            &pool_mint,
+            10,
+        ),
+        instruction::quote(&id(), &user_wallet_x, &pool_mint, 10, ChangeDirection::YtoX),
+        instruction::change_x_to_y_associated(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &token_x_mint,
+            &pool_mint,
+            10,
+        ),
+        instruction::change_x_to_y_exact_out(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &token_x_mint,
+            &user_wallet_y,
+            &pool_mint,
+            10,
+            11,
+        ),
+        instruction::change_y_to_x_exact_out(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &user_wallet_y,
+            &pool_mint,
+            10,
+            11,
+        ),
+        instruction::change_x_to_y_all(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &token_x_mint,
+            &user_wallet_y,
+            &pool_mint,
+        ),
+        instruction::change_y_to_x_all(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &user_wallet_y,
+            &pool_mint,
+        ),
+        instruction::wrap_with_vesting(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &token_x_mint,
+            &pool_mint,
+            &vesting,
+            &beneficiary,
+            10,
+            20,
+            30,
+        ),
+        instruction::claim_vested(&id(), &beneficiary, &vesting, &user_wallet_y),
+        instruction::revoke_vesting(&id(), &authority, &vesting, &user_wallet_y),
+        instruction::verify_solvency(&id(), &pool_mint),
+        instruction::initialize_pool(
+            &id(),
+            &authority,
+            &pool_mint,
+            &token_x_mint,
+            "Pool",
+            "POOL",
+            "https://example.com/pool.json",
+            PoolMode::Shares,
+            60,
+            1_700_000_000,
+            1_800_000_000,
+        ),
+        instruction::update_pool_metadata(&id(), &authority, &pool_mint, "Pool", "POOL", ""),
+        instruction::skim(&id(), &authority, &pool_mint, &user_wallet_x),
+        instruction::request_unwrap(&id(), &authority, &user_wallet_y, &pool_mint, 10, 7),
+        instruction::claim_unwrap(&id(), &authority, &user_wallet_x, &pool_mint, 7),
+        instruction::change_x_to_y_pool(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &token_x_mint,
+            &user_wallet_y,
+            &pool_mint,
+            10,
+        ),
+        instruction::change_y_to_x_pool(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &user_wallet_y,
+            &pool_mint,
+            10,
+        ),
+        instruction::quote_pool(&id(), &user_wallet_x, &pool_mint, 10, ChangeDirection::XtoY),
+        instruction::change_x_to_y_associated_pool(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &token_x_mint,
+            &pool_mint,
+            10,
+        ),
+        instruction::change_x_to_y_exact_out_pool(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &token_x_mint,
+            &user_wallet_y,
+            &pool_mint,
+            10,
+            11,
+        ),
+        instruction::change_y_to_x_exact_out_pool(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &user_wallet_y,
+            &pool_mint,
+            10,
+            11,
+        ),
+        instruction::change_x_to_y_all_pool(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &token_x_mint,
+            &user_wallet_y,
+            &pool_mint,
+        ),
+        instruction::change_y_to_x_all_pool(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &user_wallet_y,
+            &pool_mint,
+        ),
+        instruction::wrap_with_vesting_pool(
+            &id(),
+            &authority,
+            &user_wallet_x,
+            &token_x_mint,
+            &pool_mint,
+            &vesting,
+            &beneficiary,
+            10,
+            20,
+            30,
+        ),
+    ]
+}
+
+#[test]
+fn builders_match_account_specs() {
+    for ix in builder_instructions() {
+        let parsed = parse_instruction(&id(), &ix).unwrap();
+        let specs = parsed.instruction.accounts();
+        assert!(parsed.accounts.len() <= specs.len(), "{}", parsed.name);
+        for ((account, spec), meta) in parsed.accounts.iter().zip(specs).zip(&ix.accounts) {
+            assert_eq!(account.name, spec.name);
+            assert_eq!(account.pubkey, meta.pubkey.to_string());
+            // Builders mark a read-only signer writable when it may pay for a new account
+            assert!(
+                meta.is_writable == spec.writable || (meta.is_writable && spec.signer),
+                "{} of {}",
+                spec.name,
+                parsed.name
+            );
+            assert_eq!(
+                meta.is_signer, spec.signer,
+                "{} of {}",
+                spec.name, parsed.name
+            );
+        }
+    }
+}
+
+#[test]
+fn serialize_to_json() {
+    let authority = Pubkey::new_unique();
+    let user_wallet_x = Pubkey::new_unique();
+    let user_wallet_y = Pubkey::new_unique();
+    let pool_mint = Pubkey::new_unique();
+    let ix = instruction::change_y_to_x(
+        &id(),
+        &authority,
+        &user_wallet_x,
+        &user_wallet_y,
+        &pool_mint,
+        42,
+    );
+    let (pool_wallet_x, _) = find_program_address(&id(), &user_wallet_x);
+
+    let parsed = parse_instruction(&id(), &ix).unwrap();
+    assert_eq!(
+        parsed.instruction,
+        ContractInstruction::ChangeYtoX { amount: 42 }
+    );
+    assert_eq!(
+        serde_json::to_value(&parsed).unwrap(),
+        serde_json::json!({
+            "program_id": id().to_string(),
+            "name": "ChangeYtoX",
+            "instruction": { "ChangeYtoX": { "amount": 42 } },
+            "accounts": [
+                {
+                    "name": "User transfer authority",
+                    "pubkey": authority.to_string(),
+                    "writable": false,
+                    "signer": true,
+                },
+                {
+                    "name": "Destination account (token X)",
+                    "pubkey": user_wallet_x.to_string(),
+                    "writable": true,
+                    "signer": false,
+                },
+                {
+                    "name": "Source account (pool token)",
+                    "pubkey": user_wallet_y.to_string(),
+                    "writable": true,
+                    "signer": false,
+                },
+                {
+                    "name": "Pool mint",
+                    "pubkey": pool_mint.to_string(),
+                    "writable": true,
+                    "signer": false,
+                },
+                {
+                    "name": "Pool wallet (token X)",
+                    "pubkey": pool_wallet_x.to_string(),
+                    "writable": true,
+                    "signer": false,
+                },
+                {
+                    "name": "Token program id",
+                    "pubkey": spl_token::id().to_string(),
+                    "writable": false,
+                    "signer": false,
+                },
+            ],
+        })
+    );
+}
+
+#[test]
+fn extra_accounts_are_unknown() {
+    let mut ix = builder_instructions().remove(2);
+    ix.accounts
+        .push(solana_program::instruction::AccountMeta::new_readonly(
+            Pubkey::new_unique(),
+            false,
+        ));
+
+    let parsed = parse_instruction(&id(), &ix).unwrap();
+    assert_eq!(parsed.accounts.last().unwrap().name, UNKNOWN_ACCOUNT);
+}
+
+#[test]
+fn fail_to_parse() {
+    let ix = builder_instructions().remove(0);
+
+    assert_eq!(
+        parse_instruction(&Pubkey::new_unique(), &ix),
+        Err(ProgramError::IncorrectProgramId)
+    );
+
+    let mut bad_data = ix.clone();
+    bad_data.data = vec![9];
+    assert_eq!(
+        parse_instruction(&id(), &bad_data),
+        Err(CrateError::InvalidInstruction.into())
+    );
+
+    let mut missing_account = ix;
+    missing_account.accounts.pop();
+    assert_eq!(
+        parse_instruction(&id(), &missing_account),
+        Err(ProgramError::NotEnoughAccountKeys)
+    );
+}
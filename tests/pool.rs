@@ -0,0 +1,563 @@
+mod utils;
+
+use crate::contract::TestContract;
+use crate::metadata::StandInMetadata;
+use crate::pool::TestPool;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::instruction::Instruction;
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::TransactionError;
+use solana_token::error::CrateError;
+use solana_token::metadata::find_metadata_address;
+use solana_token::state::{Pool, PoolMode, SHARES_DECIMALS_OFFSET, VIRTUAL_SHARES};
+use solana_token::{find_pool_address, find_program_address, id, instruction};
+use utils::*;
+
+const URI: &str = "https://example.com/pool.json";
+
+/// Pool created by `InitializePool` for a new token X, and a user holding 100 token X
+async fn setup_pool(mode: PoolMode) -> (ProgramTestContext, TestPool, TokenX, User) {
+    let mut context = program_test().start_with_context().await;
+
+    let token_x = TokenX::new();
+    token_x.init(&mut context).await.unwrap();
+
+    let test_pool = TestPool::new();
+    test_pool
+        .create(
+            &mut context,
+            &token_x,
+            "Wrapped X",
+            "WX",
+            URI,
+            mode,
+            0,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+
+    let user = add_user(&mut context, &test_pool.contract, &token_x, 100).await;
+    (context, test_pool, token_x, user)
+}
+
+async fn get_metadata(context: &mut ProgramTestContext, test_pool: &TestPool) -> StandInMetadata {
+    let (metadata, _) = find_metadata_address(&test_pool.contract.pool_mint.pubkey());
+    StandInMetadata::unpack(&get_account(context, &metadata).await.data)
+}
+
+fn change_x_to_y(
+    contract: &TestContract,
+    token_x: &TokenX,
+    user: &User,
+    amount: u64,
+) -> Instruction {
+    instruction::change_x_to_y_pool(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &contract.pool_mint.pubkey(),
+        amount,
+    )
+}
+
+fn change_y_to_x(contract: &TestContract, user: &User, amount: u64) -> Instruction {
+    instruction::change_y_to_x_pool(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &contract.pool_mint.pubkey(),
+        amount,
+    )
+}
+
+#[tokio::test]
+async fn creates_pool_with_metadata() {
+    let (mut context, test_pool, token_x, _) = setup_pool(PoolMode::Fixed).await;
+
+    let pool_mint = get_account(&mut context, &test_pool.contract.pool_mint.pubkey()).await;
+    let pool_mint = spl_token::state::Mint::unpack(&pool_mint.data).unwrap();
+    let token_x_mint = get_account(&mut context, &token_x.mint.pubkey()).await;
+    let token_x_mint = spl_token::state::Mint::unpack(&token_x_mint.data).unwrap();
+    assert_eq!(pool_mint.mint_authority, COption::Some(test_pool.pool));
+    assert_eq!(pool_mint.decimals, token_x_mint.decimals);
+    assert_eq!(pool_mint.supply, 0);
+
+    let pool = get_account(&mut context, &test_pool.pool).await;
+    assert_eq!(pool.owner, id());
+    assert_eq!(
+        Pool::unpack(&pool.data).unwrap(),
+        Pool {
+            is_initialized: true,
+            admin: test_pool.admin.pubkey(),
+            pool_mint: test_pool.contract.pool_mint.pubkey(),
+            token_x_mint: token_x.mint.pubkey(),
+            mode: PoolMode::Fixed,
+            unwrap_delay: 0,
+            pending_unwrap: 0,
+            subscription_deadline: 0,
+            maturity: 0,
+            bump: find_pool_address(&id(), &test_pool.contract.pool_mint.pubkey()).1,
+            vault_bump: find_program_address(&id(), &test_pool.pool).1,
+        }
+    );
+
+    let vault = get_account(&mut context, &test_pool.vault).await;
+    let vault = spl_token::state::Account::unpack(&vault.data).unwrap();
+    assert_eq!(vault.mint, token_x.mint.pubkey());
+    assert_eq!(vault.owner, test_pool.vault);
+
+    assert_eq!(
+        get_metadata(&mut context, &test_pool).await,
+        StandInMetadata {
+            update_authority: test_pool.pool,
+            mint: test_pool.contract.pool_mint.pubkey(),
+            name: "Wrapped X".to_string(),
+            symbol: "WX".to_string(),
+            uri: URI.to_string(),
+            is_mutable: true,
+        }
+    );
+}
+
+#[tokio::test]
+async fn wraps_into_vault() {
+    let (mut context, test_pool, token_x, user) = setup_pool(PoolMode::Fixed).await;
+
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &user, 100);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    assert_eq!(get_token_balance(&mut context, &test_pool.vault).await, 100);
+
+    let ix = instruction::change_y_to_x_pool(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        40,
+    );
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    assert_eq!(get_token_balance(&mut context, &test_pool.vault).await, 60);
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_x.pubkey()).await,
+        40
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_y.pubkey()).await,
+        60
+    );
+
+    let ix = instruction::verify_solvency(&id(), &test_pool.contract.pool_mint.pubkey());
+    let payer = payer(&context);
+    let logs = simulate(&mut context, ix, &[&payer]).await;
+    assert!(logs
+        .iter()
+        .any(|log| log == "Program log: Vault holds 60 for a supply of 60 and 0 pending unwrap"));
+}
+
+#[tokio::test]
+async fn fails_to_wrap_with_pool_authority_of_mint() {
+    let (mut context, test_pool, token_x, user) = setup_pool(PoolMode::Fixed).await;
+
+    let ix = instruction::change_x_to_y(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        100,
+    );
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::InvalidPoolMintAuthority)
+    );
+}
+
+#[tokio::test]
+async fn fails_to_unwrap_with_pool_state_of_another_mint() {
+    let (mut context, test_pool, token_x, user) = setup_pool(PoolMode::Fixed).await;
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &user, 100);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+
+    let other_pool = TestPool::new();
+    other_pool
+        .create(
+            &mut context,
+            &token_x,
+            "Other",
+            "OX",
+            URI,
+            PoolMode::Fixed,
+            0,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+    let mut ix = instruction::change_y_to_x_pool(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        100,
+    );
+    ix.accounts[4].pubkey = other_pool.vault;
+    ix.accounts[6].pubkey = other_pool.pool;
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::InvalidPoolAuthority)
+    );
+}
+
+#[tokio::test]
+async fn updates_metadata_by_admin() {
+    let (mut context, test_pool, _, user) = setup_pool(PoolMode::Fixed).await;
+    let pool_mint = test_pool.contract.pool_mint.pubkey();
+
+    let ix =
+        instruction::update_pool_metadata(&id(), &user.account.pubkey(), &pool_mint, "X", "X", "");
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::InvalidPoolAdmin)
+    );
+
+    let ix = instruction::update_pool_metadata(
+        &id(),
+        &test_pool.admin.pubkey(),
+        &pool_mint,
+        "Wrapped X v2",
+        "WX2",
+        "https://example.com/v2.json",
+    );
+    process(&mut context, ix, &[&test_pool.admin])
+        .await
+        .unwrap();
+
+    let metadata = get_metadata(&mut context, &test_pool).await;
+    assert_eq!(metadata.name, "Wrapped X v2");
+    assert_eq!(metadata.symbol, "WX2");
+    assert_eq!(metadata.uri, "https://example.com/v2.json");
+    assert_eq!(metadata.update_authority, test_pool.pool);
+}
+
+#[tokio::test]
+async fn fails_with_too_long_metadata() {
+    let (mut context, test_pool, _, _) = setup_pool(PoolMode::Fixed).await;
+
+    let ix = instruction::update_pool_metadata(
+        &id(),
+        &test_pool.admin.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        "Wrapped X",
+        "WRAPPED_X_TOKEN",
+        URI,
+    );
+    assert_eq!(
+        process_error(&mut context, ix, &[&test_pool.admin]).await,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+}
+
+#[tokio::test]
+async fn fails_with_other_metadata_program() {
+    let mut context = program_test().start_with_context().await;
+    let token_x = TokenX::new();
+    token_x.init(&mut context).await.unwrap();
+    let test_pool = TestPool::new();
+    let admin = &test_pool.admin;
+    let payer = payer(&context);
+    let fund_ix =
+        solana_program::system_instruction::transfer(&payer.pubkey(), &admin.pubkey(), 999999999);
+    process(&mut context, fund_ix, &[&payer]).await.unwrap();
+
+    // The pool state would sign for any program
+    let mut ix = instruction::initialize_pool(
+        &id(),
+        &admin.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        &token_x.mint.pubkey(),
+        "Wrapped X",
+        "WX",
+        URI,
+        PoolMode::Fixed,
+        0,
+        0,
+        0,
+    );
+    ix.accounts[6].pubkey = Pubkey::new_unique();
+    assert_eq!(
+        process_error(&mut context, ix, &[admin, &test_pool.contract.pool_mint]).await,
+        crate_error(0, CrateError::IncorrectProgramAccount)
+    );
+}
+
+#[tokio::test]
+async fn fails_to_initialize_pool_twice() {
+    let (mut context, test_pool, token_x, _) = setup_pool(PoolMode::Fixed).await;
+
+    let ix = instruction::initialize_pool(
+        &id(),
+        &test_pool.admin.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        &token_x.mint.pubkey(),
+        "Wrapped X again",
+        "WX",
+        URI,
+        PoolMode::Fixed,
+        0,
+        0,
+        0,
+    );
+    // The pool mint already exists
+    assert!(process(
+        &mut context,
+        ix,
+        &[&test_pool.admin, &test_pool.contract.pool_mint]
+    )
+    .await
+    .is_err());
+}
+
+#[tokio::test]
+async fn skims_donations_to_treasury() {
+    let (mut context, test_pool, token_x, user) = setup_pool(PoolMode::Fixed).await;
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &user, 60);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    // 40 token X transferred to the vault without wrapping
+    token_x
+        .mint_to(&mut context, &test_pool.vault, &token_x.owner, 40)
+        .await
+        .unwrap();
+    let treasury = add_user(&mut context, &test_pool.contract, &token_x, 0).await;
+    let treasury = treasury.user_wallet_x.pubkey();
+
+    let ix = instruction::skim(
+        &id(),
+        &user.account.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        &treasury,
+    );
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::InvalidPoolAdmin)
+    );
+
+    let ix = instruction::skim(
+        &id(),
+        &test_pool.admin.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        &treasury,
+    );
+    process(&mut context, ix.clone(), &[&test_pool.admin])
+        .await
+        .unwrap();
+    assert_eq!(get_token_balance(&mut context, &test_pool.vault).await, 60);
+    assert_eq!(get_token_balance(&mut context, &treasury).await, 40);
+
+    // Nothing left to skim
+    let logs = simulate(&mut context, ix, &[&test_pool.admin]).await;
+    assert!(logs
+        .iter()
+        .any(|log| log == "Program log: Skimmed 0 token X"));
+}
+
+#[tokio::test]
+async fn changes_shares_pro_rata() {
+    let (mut context, test_pool, token_x, user) = setup_pool(PoolMode::Shares).await;
+    let other_user = add_user(&mut context, &test_pool.contract, &token_x, 100).await;
+
+    let pool_mint = get_account(&mut context, &test_pool.contract.pool_mint.pubkey()).await;
+    let pool_mint = spl_token::state::Mint::unpack(&pool_mint.data).unwrap();
+    let token_x_mint = get_account(&mut context, &token_x.mint.pubkey()).await;
+    let token_x_mint = spl_token::state::Mint::unpack(&token_x_mint.data).unwrap();
+    assert_eq!(
+        pool_mint.decimals,
+        token_x_mint.decimals + SHARES_DECIMALS_OFFSET
+    );
+
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &user, 100);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_y.pubkey()).await,
+        100 * VIRTUAL_SHARES
+    );
+
+    // Yield of 50 token X, shared by the holders
+    token_x
+        .mint_to(&mut context, &test_pool.vault, &token_x.owner, 50)
+        .await
+        .unwrap();
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &other_user, 100);
+    process(&mut context, ix, &[&other_user.account])
+        .await
+        .unwrap();
+    // 100 * (100000 + 1000) / (150 + 1), rounded down
+    assert_eq!(
+        get_token_balance(&mut context, &other_user.user_wallet_y.pubkey()).await,
+        66887
+    );
+
+    let ix = change_y_to_x(&test_pool.contract, &user, 100 * VIRTUAL_SHARES);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    // 100000 * (250 + 1) / (166887 + 1000), rounded down
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_x.pubkey()).await,
+        149
+    );
+
+    let ix = change_y_to_x(&test_pool.contract, &other_user, 66887);
+    process(&mut context, ix, &[&other_user.account])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &other_user.user_wallet_x.pubkey()).await,
+        100
+    );
+    // What rounding kept stays in the vault
+    assert_eq!(get_token_balance(&mut context, &test_pool.vault).await, 1);
+}
+
+#[tokio::test]
+async fn donation_to_first_share_is_a_loss() {
+    let (mut context, test_pool, token_x, victim) = setup_pool(PoolMode::Shares).await;
+    let attacker = add_user(&mut context, &test_pool.contract, &token_x, 10_001).await;
+
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &attacker, 1);
+    process(&mut context, ix, &[&attacker.account])
+        .await
+        .unwrap();
+    let ix = spl_token::instruction::transfer(
+        &spl_token::id(),
+        &attacker.user_wallet_x.pubkey(),
+        &test_pool.vault,
+        &attacker.account.pubkey(),
+        &[],
+        10_000,
+    )
+    .unwrap();
+    process(&mut context, ix, &[&attacker.account])
+        .await
+        .unwrap();
+
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &victim, 100);
+    process(&mut context, ix, &[&victim.account]).await.unwrap();
+    // 100 * (1000 + 1000) / (10001 + 1), rounded down
+    let victim_shares = get_token_balance(&mut context, &victim.user_wallet_y.pubkey()).await;
+    assert_eq!(victim_shares, 19);
+
+    let ix = change_y_to_x(&test_pool.contract, &attacker, VIRTUAL_SHARES);
+    process(&mut context, ix, &[&attacker.account])
+        .await
+        .unwrap();
+    let ix = change_y_to_x(&test_pool.contract, &victim, victim_shares);
+    process(&mut context, ix, &[&victim.account]).await.unwrap();
+
+    // The virtual shares take half the donation, for 5 token X of the victim
+    assert_eq!(
+        get_token_balance(&mut context, &attacker.user_wallet_x.pubkey()).await,
+        5003
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &victim.user_wallet_x.pubkey()).await,
+        95
+    );
+}
+
+#[tokio::test]
+async fn changes_exact_shares_rounding_up() {
+    let (mut context, test_pool, token_x, user) = setup_pool(PoolMode::Shares).await;
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &user, 60);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    token_x
+        .mint_to(&mut context, &test_pool.vault, &token_x.owner, 30)
+        .await
+        .unwrap();
+
+    // 1000 * (90 + 1) / (60000 + 1000) is 1.49 token X, 2 are taken
+    let exact_out = |amount_out, maximum_in| {
+        instruction::change_x_to_y_exact_out_pool(
+            &id(),
+            &user.account.pubkey(),
+            &user.user_wallet_x.pubkey(),
+            &token_x.mint.pubkey(),
+            &user.user_wallet_y.pubkey(),
+            &test_pool.contract.pool_mint.pubkey(),
+            amount_out,
+            maximum_in,
+        )
+    };
+    assert_eq!(
+        process_error(&mut context, exact_out(1000, 1), &[&user.account]).await,
+        crate_error(0, CrateError::MaximumInExceeded)
+    );
+    process(&mut context, exact_out(1000, 2), &[&user.account])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_x.pubkey()).await,
+        38
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_y.pubkey()).await,
+        61000
+    );
+
+    // 10 * (61000 + 1000) / (92 + 1) is 6666.7 pool tokens, 6667 are burnt
+    let ix = instruction::change_y_to_x_exact_out_pool(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        10,
+        6667,
+    );
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_x.pubkey()).await,
+        48
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_y.pubkey()).await,
+        61000 - 6667
+    );
+
+    // Less than a token X
+    let ix = change_y_to_x(&test_pool.contract, &user, 600);
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::ZeroAmountOut)
+    );
+}
+
+#[tokio::test]
+async fn fails_to_skim_shares() {
+    let (mut context, test_pool, token_x, user) = setup_pool(PoolMode::Shares).await;
+    token_x
+        .mint_to(&mut context, &test_pool.vault, &token_x.owner, 40)
+        .await
+        .unwrap();
+
+    let ix = instruction::skim(
+        &id(),
+        &test_pool.admin.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        &user.user_wallet_x.pubkey(),
+    );
+    assert_eq!(
+        process_error(&mut context, ix, &[&test_pool.admin]).await,
+        crate_error(0, CrateError::InvalidPoolMode)
+    );
+}
@@ -0,0 +1,132 @@
+mod utils;
+
+use crate::caller::call_through;
+use crate::contract::TestContract;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::signature::Signer;
+use solana_sdk::transport;
+use solana_token::error::CrateError;
+use solana_token::instruction::{self, ChangeDirection};
+use solana_token::{find_program_address, id};
+use utils::*;
+
+async fn quote(
+    context: &mut ProgramTestContext,
+    caller_id: &Pubkey,
+    test_contract: &TestContract,
+    user: &User,
+    amount: u64,
+    direction: ChangeDirection,
+) -> transport::Result<()> {
+    let ix = instruction::quote(
+        &id(),
+        &user.user_wallet_x.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        amount,
+        direction,
+    );
+    let payer = payer(context);
+    process(context, call_through(caller_id, ix, amount), &[&payer]).await
+}
+
+#[tokio::test]
+async fn success() {
+    let caller_id = Pubkey::new_unique();
+    let amount = 100;
+    let (mut context, test_contract, token_x, user) =
+        setup(program_test_with_caller(&caller_id), amount).await;
+
+    quote(
+        &mut context,
+        &caller_id,
+        &test_contract,
+        &user,
+        amount,
+        ChangeDirection::XtoY,
+    )
+    .await
+    .unwrap();
+
+    test_contract
+        .change_x_to_y(&mut context, &user, &token_x, amount)
+        .await
+        .unwrap();
+    let (pool_wallet_x, _) = find_program_address(&id(), &user.user_wallet_x.pubkey());
+    assert_eq!(
+        get_token_balance(&mut context, &pool_wallet_x).await,
+        amount
+    );
+
+    quote(
+        &mut context,
+        &caller_id,
+        &test_contract,
+        &user,
+        amount,
+        ChangeDirection::YtoX,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &pool_wallet_x).await,
+        amount
+    );
+}
+
+#[tokio::test]
+async fn fail_not_enough_tokens() {
+    let caller_id = Pubkey::new_unique();
+    let amount = 100;
+    let (mut context, test_contract, token_x, user) =
+        setup(program_test_with_caller(&caller_id), amount).await;
+    assert_eq!(
+        quote(
+            &mut context,
+            &caller_id,
+            &test_contract,
+            &user,
+            amount + 1,
+            ChangeDirection::XtoY,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        crate_error(0, CrateError::NotEnoughTokens)
+    );
+    assert_eq!(
+        quote(
+            &mut context,
+            &caller_id,
+            &test_contract,
+            &user,
+            amount,
+            ChangeDirection::YtoX,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        crate_error(0, CrateError::ExpectedAccount)
+    );
+
+    test_contract
+        .change_x_to_y(&mut context, &user, &token_x, amount)
+        .await
+        .unwrap();
+    assert_eq!(
+        quote(
+            &mut context,
+            &caller_id,
+            &test_contract,
+            &user,
+            amount + 1,
+            ChangeDirection::YtoX,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        crate_error(0, CrateError::InsufficientPoolBalance)
+    );
+}
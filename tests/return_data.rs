@@ -0,0 +1,73 @@
+mod utils;
+
+use crate::caller::call_through;
+use crate::contract::TestContract;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::Transaction;
+use solana_token::{id, instruction};
+use utils::*;
+
+#[tokio::test]
+async fn success() {
+    let caller_id = Pubkey::new_unique();
+    let amount = 100;
+    let (mut context, test_contract, token_x, user) =
+        setup(program_test_with_caller(&caller_id), amount).await;
+
+    let change_x_to_y = instruction::change_x_to_y(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        amount,
+    );
+    let change_y_to_x = instruction::change_y_to_x(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        amount,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            call_through(&caller_id, change_x_to_y, amount),
+            call_through(&caller_id, change_y_to_x, amount),
+        ],
+        Some(&user.account.pubkey()),
+        &[&user.account],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_x.pubkey()).await,
+        amount
+    );
+}
+
+#[tokio::test]
+async fn fail_with_other_amount() {
+    let caller_id = Pubkey::new_unique();
+    let amount = 100;
+    let (mut context, test_contract, token_x, user) =
+        setup(program_test_with_caller(&caller_id), amount).await;
+
+    let change_x_to_y = instruction::change_x_to_y(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_contract.pool_mint.pubkey(),
+        amount,
+    );
+    let ix = call_through(&caller_id, change_x_to_y, amount + 1);
+    assert!(process(&mut context, ix, &[&user.account]).await.is_err());
+}
@@ -0,0 +1,161 @@
+mod utils;
+
+use crate::contract::TestContract;
+use crate::pool::TestPool;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::instruction::Instruction;
+use solana_program::program_pack::{IsInitialized, Pack};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::signature::Signer;
+use solana_token::error::CrateError;
+use solana_token::state::{Pool, PoolMode};
+use solana_token::{id, instruction};
+use utils::*;
+
+/// Pool created by `InitializePool` into which a user wrapped 100 token X
+async fn setup_pool(mode: PoolMode) -> (ProgramTestContext, TestPool, TokenX, User) {
+    let mut context = program_test().start_with_context().await;
+
+    let token_x = TokenX::new();
+    token_x.init(&mut context).await.unwrap();
+    let test_pool = TestPool::new();
+    test_pool
+        .create(
+            &mut context,
+            &token_x,
+            "Wrapped X",
+            "WX",
+            "https://example.com/pool.json",
+            mode,
+            0,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+
+    let user = add_user(&mut context, &test_pool.contract, &token_x, 100).await;
+    let ix = instruction::change_x_to_y_pool(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        100,
+    );
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    (context, test_pool, token_x, user)
+}
+
+fn verify_solvency(contract: &TestContract) -> Instruction {
+    instruction::verify_solvency(&id(), &contract.pool_mint.pubkey())
+}
+
+/// Rewrites the data of the account `pubkey`, packed as `T`
+async fn set_state<T: Pack + IsInitialized>(
+    context: &mut ProgramTestContext,
+    pubkey: &Pubkey,
+    update: impl FnOnce(&mut T),
+) {
+    let mut account = get_account(context, pubkey).await;
+    let mut state = T::unpack(&account.data).unwrap();
+    update(&mut state);
+    T::pack(state, &mut account.data).unwrap();
+    context.set_account(pubkey, &AccountSharedData::from(account));
+}
+
+#[tokio::test]
+async fn reports_backed_pool() {
+    let (mut context, test_pool, _, _) = setup_pool(PoolMode::Fixed).await;
+    let payer = payer(&context);
+
+    let ix = verify_solvency(&test_pool.contract);
+    let logs = simulate(&mut context, ix, &[&payer]).await;
+    assert!(logs
+        .iter()
+        .any(|log| log == "Program log: Vault holds 100 for a supply of 100 and 0 pending unwrap"));
+}
+
+#[tokio::test]
+async fn fails_with_vault_short_of_supply() {
+    let (mut context, test_pool, _, _) = setup_pool(PoolMode::Fixed).await;
+    let payer = payer(&context);
+
+    set_state(
+        &mut context,
+        &test_pool.vault,
+        |vault: &mut spl_token::state::Account| vault.amount = 99,
+    )
+    .await;
+    let ix = verify_solvency(&test_pool.contract);
+    assert_eq!(
+        process_error(&mut context, ix, &[&payer]).await,
+        crate_error(0, CrateError::Insolvent)
+    );
+}
+
+#[tokio::test]
+async fn fails_with_vault_short_of_pending_unwrap() {
+    for mode in [PoolMode::Fixed, PoolMode::Shares] {
+        let (mut context, test_pool, _, _) = setup_pool(mode).await;
+        let payer = payer(&context);
+
+        // The tickets take all of the vault, nothing is left for the pool tokens
+        set_state(&mut context, &test_pool.pool, |pool: &mut Pool| {
+            pool.pending_unwrap = 100
+        })
+        .await;
+        let ix = verify_solvency(&test_pool.contract);
+        assert_eq!(
+            process_error(&mut context, ix, &[&payer]).await,
+            crate_error(0, CrateError::Insolvent),
+            "{:?}",
+            mode
+        );
+    }
+}
+
+#[tokio::test]
+async fn accepts_shares_worth_less_than_deposited() {
+    let (mut context, test_pool, _, _) = setup_pool(PoolMode::Shares).await;
+    let payer = payer(&context);
+
+    // Shares are only owed what the vault holds
+    set_state(
+        &mut context,
+        &test_pool.vault,
+        |vault: &mut spl_token::state::Account| vault.amount = 1,
+    )
+    .await;
+    let ix = verify_solvency(&test_pool.contract);
+    process(&mut context, ix, &[&payer]).await.unwrap();
+}
+
+#[tokio::test]
+async fn fails_with_another_vault() {
+    let (mut context, test_pool, _, _) = setup_pool(PoolMode::Fixed).await;
+    let payer = payer(&context);
+
+    let mut ix = verify_solvency(&test_pool.contract);
+    ix.accounts[2].pubkey = Pubkey::new_unique();
+    assert_eq!(
+        process_error(&mut context, ix, &[&payer]).await,
+        crate_error(0, CrateError::InvalidPoolWalletAddress)
+    );
+}
+
+#[tokio::test]
+async fn fails_for_pool_created_off_chain() {
+    let (mut context, test_contract, _, _) = setup(program_test(), 100).await;
+    let payer = payer(&context);
+
+    let ix = verify_solvency(&test_contract);
+    assert_eq!(
+        process_error(&mut context, ix, &[&payer]).await,
+        crate_error(0, CrateError::InvalidPoolAuthority)
+    );
+}
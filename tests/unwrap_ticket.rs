@@ -0,0 +1,252 @@
+mod utils;
+
+use crate::contract::TestContract;
+use crate::pool::TestPool;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::instruction::Instruction;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::signature::Signer;
+use solana_token::error::CrateError;
+use solana_token::state::{Pool, PoolMode, Ticket, VIRTUAL_SHARES};
+use solana_token::{find_ticket_address, id, instruction};
+use utils::*;
+
+const START: i64 = 1_000;
+const DELAY: u32 = 100;
+
+/// Pool in `mode` unwrapping after `DELAY`, and a user who wrapped 100 token X, at `START`
+async fn setup_pool(mode: PoolMode) -> (ProgramTestContext, TestPool, TokenX, User) {
+    let mut context = program_test().start_with_context().await;
+    set_time(&mut context, START).await;
+
+    let token_x = TokenX::new();
+    token_x.init(&mut context).await.unwrap();
+    let test_pool = TestPool::new();
+    test_pool
+        .create(
+            &mut context,
+            &token_x,
+            "Staked X",
+            "SX",
+            "https://example.com/staked.json",
+            mode,
+            DELAY,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+
+    let user = add_user(&mut context, &test_pool.contract, &token_x, 100).await;
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &user, 100);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    (context, test_pool, token_x, user)
+}
+
+fn change_x_to_y(
+    contract: &TestContract,
+    token_x: &TokenX,
+    user: &User,
+    amount: u64,
+) -> Instruction {
+    instruction::change_x_to_y_pool(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &token_x.mint.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &contract.pool_mint.pubkey(),
+        amount,
+    )
+}
+
+fn request_unwrap(test_pool: &TestPool, user: &User, amount: u64, nonce: u64) -> Instruction {
+    instruction::request_unwrap(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        amount,
+        nonce,
+    )
+}
+
+fn claim_unwrap(test_pool: &TestPool, user: &User, nonce: u64) -> Instruction {
+    instruction::claim_unwrap(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        nonce,
+    )
+}
+
+async fn get_pool(context: &mut ProgramTestContext, test_pool: &TestPool) -> Pool {
+    Pool::unpack(&get_account(context, &test_pool.pool).await.data).unwrap()
+}
+
+#[tokio::test]
+async fn claims_ticket_after_delay() {
+    let (mut context, test_pool, _, user) = setup_pool(PoolMode::Fixed).await;
+
+    let ix = instruction::change_y_to_x_pool(
+        &id(),
+        &user.account.pubkey(),
+        &user.user_wallet_x.pubkey(),
+        &user.user_wallet_y.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        40,
+    );
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::UnwrapDelayed)
+    );
+
+    let ix = request_unwrap(&test_pool, &user, 40, 0);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_y.pubkey()).await,
+        60
+    );
+    assert_eq!(get_pool(&mut context, &test_pool).await.pending_unwrap, 40);
+    // The nonce is taken
+    let ix = request_unwrap(&test_pool, &user, 10, 0);
+    assert!(process(&mut context, ix, &[&user.account]).await.is_err());
+
+    let (ticket, _) = find_ticket_address(&id(), &test_pool.pool, &user.account.pubkey(), 0);
+    let ticket_account = get_account(&mut context, &ticket).await;
+    assert_eq!(ticket_account.owner, id());
+    assert_eq!(
+        ticket_account.data[Ticket::OWNER_OFFSET..Ticket::OWNER_OFFSET + 32],
+        user.account.pubkey().to_bytes()
+    );
+    assert_eq!(
+        Ticket::unpack(&ticket_account.data).unwrap(),
+        Ticket {
+            is_initialized: true,
+            owner: user.account.pubkey(),
+            pool: test_pool.pool,
+            amount: 40,
+            unlock_ts: START + DELAY as i64,
+        }
+    );
+
+    set_time(&mut context, START + DELAY as i64 - 1).await;
+    assert_eq!(
+        process_error(
+            &mut context,
+            claim_unwrap(&test_pool, &user, 0),
+            &[&user.account]
+        )
+        .await,
+        crate_error(0, CrateError::TicketLocked)
+    );
+
+    set_time(&mut context, START + DELAY as i64).await;
+    process(
+        &mut context,
+        claim_unwrap(&test_pool, &user, 0),
+        &[&user.account],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user.user_wallet_x.pubkey()).await,
+        40
+    );
+    assert_eq!(get_token_balance(&mut context, &test_pool.vault).await, 60);
+    assert_eq!(get_pool(&mut context, &test_pool).await.pending_unwrap, 0);
+    assert!(context
+        .banks_client
+        .get_account(ticket)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn fails_to_claim_ticket_of_another_owner() {
+    let (mut context, test_pool, token_x, user) = setup_pool(PoolMode::Fixed).await;
+    let other_user = add_user(&mut context, &test_pool.contract, &token_x, 0).await;
+    let ix = request_unwrap(&test_pool, &user, 40, 0);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    set_time(&mut context, START + DELAY as i64).await;
+
+    let (ticket, _) = find_ticket_address(&id(), &test_pool.pool, &user.account.pubkey(), 0);
+    let mut ix = claim_unwrap(&test_pool, &other_user, 0);
+    ix.accounts[4].pubkey = ticket;
+    assert_eq!(
+        process_error(&mut context, ix, &[&other_user.account]).await,
+        crate_error(0, CrateError::InvalidTicketOwner)
+    );
+}
+
+#[tokio::test]
+async fn skims_without_pending_unwrap() {
+    let (mut context, test_pool, token_x, user) = setup_pool(PoolMode::Fixed).await;
+    let ix = request_unwrap(&test_pool, &user, 40, 0);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    token_x
+        .mint_to(&mut context, &test_pool.vault, &token_x.owner, 10)
+        .await
+        .unwrap();
+    let treasury = add_user(&mut context, &test_pool.contract, &token_x, 0).await;
+    let treasury = treasury.user_wallet_x.pubkey();
+
+    let ix = instruction::skim(
+        &id(),
+        &test_pool.admin.pubkey(),
+        &test_pool.contract.pool_mint.pubkey(),
+        &treasury,
+    );
+    process(&mut context, ix, &[&test_pool.admin])
+        .await
+        .unwrap();
+    assert_eq!(get_token_balance(&mut context, &treasury).await, 10);
+    assert_eq!(get_token_balance(&mut context, &test_pool.vault).await, 100);
+}
+
+#[tokio::test]
+async fn shares_rate_excludes_pending_unwrap() {
+    let (mut context, test_pool, token_x, user) = setup_pool(PoolMode::Shares).await;
+    let other_user = add_user(&mut context, &test_pool.contract, &token_x, 50).await;
+
+    // 50000 * (100 + 1) / (100000 + 1000)
+    let ix = request_unwrap(&test_pool, &user, 50 * VIRTUAL_SHARES, 0);
+    process(&mut context, ix, &[&user.account]).await.unwrap();
+    let (ticket, _) = find_ticket_address(&id(), &test_pool.pool, &user.account.pubkey(), 0);
+    let ticket = Ticket::unpack(&get_account(&mut context, &ticket).await.data).unwrap();
+    assert_eq!(ticket.amount, 50);
+
+    // The vault holds 100 token X, 50 of them owed to the ticket
+    let ix = change_x_to_y(&test_pool.contract, &token_x, &other_user, 50);
+    process(&mut context, ix, &[&other_user.account])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &other_user.user_wallet_y.pubkey()).await,
+        50 * VIRTUAL_SHARES
+    );
+}
+
+#[tokio::test]
+async fn fails_to_request_more_than_balance() {
+    let (mut context, test_pool, _, user) = setup_pool(PoolMode::Fixed).await;
+
+    let ix = request_unwrap(&test_pool, &user, 101, 0);
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::NotEnoughTokens)
+    );
+
+    // Not a pool created by `InitializePool`
+    let mut ix = request_unwrap(&test_pool, &user, 40, 0);
+    ix.accounts[3].pubkey = Pubkey::new_unique();
+    assert_eq!(
+        process_error(&mut context, ix, &[&user.account]).await,
+        crate_error(0, CrateError::InvalidPoolAuthority)
+    );
+}
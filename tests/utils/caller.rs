@@ -0,0 +1,50 @@
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::invoke;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_token::{id, instruction};
+
+/// Test program which invokes the wrapper with the instruction data following the expected
+/// `amount_out`, fails if the returned `ChangeResult` doesn't match
+pub fn process_caller(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    let (amount_out, data) = input.split_at(8);
+    let amount_out = u64::from_le_bytes(amount_out.try_into().unwrap());
+    // The last account is the wrapper program, passed for the invocation only
+    let (_, wrapper_accounts) = accounts.split_last().unwrap();
+    let ix = Instruction {
+        program_id: id(),
+        accounts: wrapper_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    };
+    invoke(&ix, accounts)?;
+
+    let result = instruction::get_change_result(&id())?;
+    if result.amount_in != amount_out || result.amount_out != amount_out {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(())
+}
+
+/// Wraps a wrapper instruction into a call of the test program
+pub fn call_through(caller_id: &Pubkey, ix: Instruction, amount_out: u64) -> Instruction {
+    let mut accounts = ix.accounts;
+    accounts.push(AccountMeta::new_readonly(ix.program_id, false));
+    Instruction {
+        program_id: *caller_id,
+        accounts,
+        data: [amount_out.to_le_bytes().to_vec(), ix.data].concat(),
+    }
+}
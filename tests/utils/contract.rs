@@ -1,4 +1,4 @@
-use crate::{create_mint, TokenX, User};
+use crate::{create_mint, new_keypair, TokenX, User};
 use solana_program::pubkey::Pubkey;
 use solana_program_test::ProgramTestContext;
 use solana_sdk::{
@@ -16,7 +16,7 @@ pub struct TestContract {
 
 impl TestContract {
     pub fn new() -> Self {
-        let pool_mint = Keypair::new();
+        let pool_mint = new_keypair();
         let (mint_authority, _) = find_program_address(&id(), &pool_mint.pubkey());
         println!("TestContract: pool mint: {}", pool_mint.pubkey());
         println!("TestContract: pool mint authority: {}", mint_authority);
@@ -56,6 +56,30 @@ impl TestContract {
         context.banks_client.process_transaction(tx).await
     }
 
+    pub async fn change_x_to_y_associated(
+        &self,
+        context: &mut ProgramTestContext,
+        user: &User,
+        token_x: &TokenX,
+        amount: u64,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::change_x_to_y_associated(
+                &id(),
+                &user.account.pubkey(),
+                &user.user_wallet_x.pubkey(),
+                &token_x.mint.pubkey(),
+                &self.pool_mint.pubkey(),
+                amount,
+            )],
+            Some(&user.account.pubkey()),
+            &[&user.account],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
     pub async fn change_y_to_x(
         &self,
         context: &mut ProgramTestContext,
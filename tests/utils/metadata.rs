@@ -0,0 +1,168 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::account_info::{next_account_info, AccountInfo};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program::invoke_signed;
+use solana_program::program_error::ProgramError;
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_instruction;
+use solana_program::sysvar::Sysvar;
+use solana_token::metadata::{
+    find_metadata_address, CreateMetadataAccountArgsV2, DataV2, UpdateMetadataAccountArgsV2,
+    CREATE_METADATA_ACCOUNT_V2_TAG, PREFIX, UPDATE_METADATA_ACCOUNT_V2_TAG,
+};
+
+/// Limits of the metadata program
+const MAX_NAME_LENGTH: usize = 32;
+const MAX_SYMBOL_LENGTH: usize = 10;
+const MAX_URI_LENGTH: usize = 200;
+
+/// Account of the stand-in, sized for the longest strings
+const METADATA_LEN: usize =
+    32 + 32 + 4 + MAX_NAME_LENGTH + 4 + MAX_SYMBOL_LENGTH + 4 + MAX_URI_LENGTH + 1;
+
+/// Metadata stored by `process_metadata`
+#[derive(Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+pub struct StandInMetadata {
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub is_mutable: bool,
+}
+
+impl StandInMetadata {
+    pub fn unpack(data: &[u8]) -> Self {
+        Self::deserialize(&mut &data[..]).unwrap()
+    }
+}
+
+/// Stand-in for the token metadata program, whose BPF build isn't available to
+/// `solana-program-test`. Checks the accounts and signers of the two instructions
+/// invoked by the wrapper as the metadata program does
+pub fn process_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    let (tag, args) = input
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    match *tag {
+        CREATE_METADATA_ACCOUNT_V2_TAG => create_metadata(
+            program_id,
+            accounts,
+            BorshDeserialize::try_from_slice(args)?,
+        ),
+        UPDATE_METADATA_ACCOUNT_V2_TAG => update_metadata(
+            program_id,
+            accounts,
+            BorshDeserialize::try_from_slice(args)?,
+        ),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn check_data(data: &DataV2) -> ProgramResult {
+    if data.name.len() > MAX_NAME_LENGTH
+        || data.symbol.len() > MAX_SYMBOL_LENGTH
+        || data.uri.len() > MAX_URI_LENGTH
+    {
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+fn create_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CreateMetadataAccountArgsV2,
+) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let metadata_info = next_account_info(iter)?;
+    let mint_info = next_account_info(iter)?;
+    let mint_authority_info = next_account_info(iter)?;
+    let payer_info = next_account_info(iter)?;
+    let update_authority_info = next_account_info(iter)?;
+
+    let (expected_metadata, bump) = find_metadata_address(mint_info.key);
+    if *metadata_info.key != expected_metadata {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let mint = spl_token::state::Mint::unpack(&mint_info.data.borrow())?;
+    if mint.mint_authority != COption::Some(*mint_authority_info.key) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !mint_authority_info.is_signer || !update_authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    check_data(&args.data)?;
+
+    let ix = system_instruction::create_account(
+        payer_info.key,
+        metadata_info.key,
+        Rent::get()?.minimum_balance(METADATA_LEN),
+        METADATA_LEN as u64,
+        program_id,
+    );
+    invoke_signed(
+        &ix,
+        &[payer_info.clone(), metadata_info.clone()],
+        &[&[
+            PREFIX,
+            &program_id.to_bytes(),
+            &mint_info.key.to_bytes(),
+            &[bump],
+        ]],
+    )?;
+
+    StandInMetadata {
+        update_authority: *update_authority_info.key,
+        mint: *mint_info.key,
+        name: args.data.name,
+        symbol: args.data.symbol,
+        uri: args.data.uri,
+        is_mutable: args.is_mutable,
+    }
+    .serialize(&mut &mut metadata_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn update_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: UpdateMetadataAccountArgsV2,
+) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let metadata_info = next_account_info(iter)?;
+    let update_authority_info = next_account_info(iter)?;
+
+    if metadata_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut metadata = StandInMetadata::unpack(&metadata_info.data.borrow());
+    if metadata.update_authority != *update_authority_info.key || !metadata.is_mutable {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !update_authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if let Some(data) = args.data {
+        check_data(&data)?;
+        metadata.name = data.name;
+        metadata.symbol = data.symbol;
+        metadata.uri = data.uri;
+    }
+    if let Some(update_authority) = args.update_authority {
+        metadata.update_authority = update_authority;
+    }
+    if let Some(is_mutable) = args.is_mutable {
+        metadata.is_mutable = is_mutable;
+    }
+    metadata.serialize(&mut &mut metadata_info.data.borrow_mut()[..])?;
+    Ok(())
+}
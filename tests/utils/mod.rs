@@ -1,23 +1,68 @@
+#![allow(dead_code)]
+
+pub mod caller;
 pub mod contract;
+pub mod metadata;
+pub mod pool;
 pub mod token_x;
 pub mod user;
 
+use contract::TestContract;
+use solana_banks_client::BanksClientError;
+use solana_program::clock::Clock;
+use solana_program::instruction::Instruction;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use solana_program::system_instruction;
 use solana_program_test::{processor, ProgramTest, ProgramTestContext};
 use solana_sdk::account::Account;
-use solana_sdk::signature::{Keypair, Signer};
-use solana_sdk::transaction::Transaction;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::{keypair_from_seed, Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
 use solana_sdk::transport;
+use solana_token::error::CrateError;
 use solana_token::{id, processor};
+use std::cell::Cell;
+use token_x::TokenX;
+use user::User;
+
+thread_local! {
+    static KEYPAIR_COUNT: Cell<u64> = Cell::new(0);
+}
 
+/// Keypair of the test, the same sequence of keys in every test thread, since the
+/// compute units of the program depend on the bumps of the addresses derived from them
+pub fn new_keypair() -> Keypair {
+    let count = KEYPAIR_COUNT.with(|count| count.replace(count.get() + 1));
+    keypair_from_seed(&[count.to_le_bytes(); 4].concat()).unwrap()
+}
+
+/// Test of the program, with the stand-in of the metadata program
 pub fn program_test() -> ProgramTest {
-    ProgramTest::new(
+    let mut program_test = ProgramTest::new(
         "solana_token",
         id(),
         processor!(processor::Processor::process_instruction),
-    )
+    );
+    // Not `add_program`, which looks for a BPF build when `BPF_OUT_DIR` is set
+    program_test.add_builtin_program(
+        "metadata",
+        solana_token::metadata::id(),
+        processor!(metadata::process_metadata).unwrap(),
+    );
+    program_test
+}
+
+/// Same as `program_test`, with the `caller` test program added under `caller_id`
+pub fn program_test_with_caller(caller_id: &Pubkey) -> ProgramTest {
+    let mut program_test = program_test();
+    program_test.add_program("caller", *caller_id, processor!(caller::process_caller));
+    program_test
+}
+
+/// Error of a transaction failed by the program at instruction `index`
+pub fn crate_error(index: u8, error: CrateError) -> TransactionError {
+    TransactionError::InstructionError(index, InstructionError::Custom(error as u32))
 }
 
 pub async fn get_account(context: &mut ProgramTestContext, pubkey: &Pubkey) -> Account {
@@ -132,3 +177,133 @@ pub async fn create_mint(
 
     context.banks_client.process_transaction(tx).await
 }
+
+/// Creates a user of the pool holding `amount` token X
+pub async fn add_user(
+    context: &mut ProgramTestContext,
+    test_contract: &TestContract,
+    token_x: &TokenX,
+    amount: u64,
+) -> User {
+    let user = User::new();
+    user.init(context, token_x, test_contract).await.unwrap();
+    if amount > 0 {
+        token_x
+            .mint_to(
+                context,
+                &user.user_wallet_x.pubkey(),
+                &token_x.owner,
+                amount,
+            )
+            .await
+            .unwrap();
+    }
+    user
+}
+
+/// Starts `program_test` with the pool of a new token X, and a user holding
+/// `amount` token X
+pub async fn setup(
+    program_test: ProgramTest,
+    amount: u64,
+) -> (ProgramTestContext, TestContract, TokenX, User) {
+    let mut context = program_test.start_with_context().await;
+
+    let test_contract = TestContract::new();
+    test_contract.create(&mut context).await.unwrap();
+
+    let token_x = TokenX::new();
+    token_x.init(&mut context).await.unwrap();
+
+    let user = add_user(&mut context, &test_contract, &token_x, amount).await;
+
+    (context, test_contract, token_x, user)
+}
+
+/// Copy of the payer of `context`, to sign while `context` is borrowed mutably
+pub fn payer(context: &ProgramTestContext) -> Keypair {
+    Keypair::from_bytes(&context.payer.to_bytes()).unwrap()
+}
+
+fn transaction(context: &ProgramTestContext, ix: Instruction, signers: &[&Keypair]) -> Transaction {
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signers[0].pubkey()),
+        &signers.to_vec(),
+        context.last_blockhash,
+    )
+}
+
+/// Processes `ix`, paid by the first signer
+pub async fn process(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+    signers: &[&Keypair],
+) -> transport::Result<()> {
+    let tx = transaction(context, ix, signers);
+    context.banks_client.process_transaction(tx).await
+}
+
+/// Error of `ix` processed as by `process`, which must fail
+pub async fn process_error(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+    signers: &[&Keypair],
+) -> TransactionError {
+    process(context, ix, signers).await.unwrap_err().unwrap()
+}
+
+/// Logs of `ix`, which must succeed, processed as by `process` but not committed.
+///
+/// Banks client only returns the logs of failed transactions, so `ix` is followed
+/// by an instruction that always fails.
+pub async fn simulate(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+    signers: &[&Keypair],
+) -> Vec<String> {
+    let failing_ix = Instruction::new_with_bytes(id(), &[], vec![]);
+    let tx = Transaction::new_signed_with_payer(
+        &[ix, failing_ix],
+        Some(&signers[0].pubkey()),
+        &signers.to_vec(),
+        context.last_blockhash,
+    );
+    match context
+        .banks_client
+        .process_transaction_with_preflight(tx)
+        .await
+    {
+        Err(BanksClientError::SimulationError { err, logs, .. }) => {
+            assert_eq!(err, crate_error(1, CrateError::InvalidInstruction));
+            logs
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+/// Same as `process`, failed transactions return their logs
+pub async fn process_with_preflight(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let tx = transaction(context, ix, signers);
+    context
+        .banks_client
+        .process_transaction_with_preflight(tx)
+        .await
+}
+
+/// Sets the clock to `unix_timestamp`, on a new slot so that transactions sent
+/// before can be sent again
+pub async fn set_time(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let slot = context.banks_client.get_root_slot().await.unwrap();
+    context.warp_to_slot(slot + 2).unwrap();
+    context.last_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let clock = Clock {
+        unix_timestamp,
+        ..context.banks_client.get_sysvar::<Clock>().await.unwrap()
+    };
+    context.set_sysvar(&clock);
+}
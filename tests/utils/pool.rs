@@ -0,0 +1,72 @@
+use crate::{new_keypair, process, TestContract, TokenX};
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transport;
+use solana_token::state::PoolMode;
+use solana_token::{find_pool_address, find_program_address, id, instruction};
+
+/// Pool created by `InitializePool`. Its contract has the pool state as mint
+/// authority, so that users are added as to other pools
+#[derive(Debug)]
+pub struct TestPool {
+    pub admin: Keypair,
+    pub contract: TestContract,
+    pub pool: Pubkey,
+    pub vault: Pubkey,
+}
+
+impl TestPool {
+    pub fn new() -> Self {
+        let admin = new_keypair();
+        let contract = TestContract::new();
+        let (pool, _) = find_pool_address(&id(), &contract.pool_mint.pubkey());
+        let (vault, _) = find_program_address(&id(), &pool);
+        println!("TestPool: admin: {}", admin.pubkey());
+        println!("TestPool: pool state: {}", pool);
+        Self {
+            admin,
+            contract: TestContract {
+                mint_authority: pool,
+                ..contract
+            },
+            pool,
+            vault,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        &self,
+        context: &mut ProgramTestContext,
+        token_x: &TokenX,
+        name: &str,
+        symbol: &str,
+        uri: &str,
+        mode: PoolMode,
+        unwrap_delay: u32,
+        subscription_deadline: i64,
+        maturity: i64,
+    ) -> transport::Result<()> {
+        let payer = crate::payer(context);
+        let fund_ix =
+            system_instruction::transfer(&payer.pubkey(), &self.admin.pubkey(), 999999999);
+        process(context, fund_ix, &[&payer]).await?;
+
+        let ix = instruction::initialize_pool(
+            &id(),
+            &self.admin.pubkey(),
+            &self.contract.pool_mint.pubkey(),
+            &token_x.mint.pubkey(),
+            name,
+            symbol,
+            uri,
+            mode,
+            unwrap_delay,
+            subscription_deadline,
+            maturity,
+        );
+        process(context, ix, &[&self.admin, &self.contract.pool_mint]).await
+    }
+}
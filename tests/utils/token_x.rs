@@ -1,4 +1,4 @@
-use crate::create_mint;
+use crate::{create_mint, new_keypair};
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use solana_program::system_instruction;
@@ -16,8 +16,8 @@ pub struct TokenX {
 
 impl TokenX {
     pub fn new() -> Self {
-        let owner = Keypair::new();
-        let mint = Keypair::new();
+        let owner = new_keypair();
+        let mint = new_keypair();
         println!("TokenX: owner: {}", owner.pubkey());
         println!("TokenX: mint: {}", mint.pubkey());
         Self { owner, mint }
@@ -1,5 +1,5 @@
 use crate::token_x::TokenX;
-use crate::{create_token_account, TestContract};
+use crate::{create_token_account, new_keypair, TestContract};
 use solana_program::system_instruction;
 use solana_program_test::ProgramTestContext;
 use solana_sdk::signature::Keypair;
@@ -16,9 +16,9 @@ pub struct User {
 
 impl User {
     pub fn new() -> Self {
-        let account = Keypair::new();
-        let user_wallet_x = Keypair::new();
-        let user_wallet_y = Keypair::new();
+        let account = new_keypair();
+        let user_wallet_x = new_keypair();
+        let user_wallet_y = new_keypair();
         println!("User: account {}", account.pubkey());
         println!("User: wallet x {}", user_wallet_x.pubkey());
         println!("User: wallet y {}", user_wallet_y.pubkey());
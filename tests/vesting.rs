@@ -0,0 +1,196 @@
+mod utils;
+
+use crate::contract::TestContract;
+use crate::token_x::TokenX;
+use crate::user::User;
+use solana_program::instruction::Instruction;
+use solana_program::program_pack::Pack;
+use solana_program_test::*;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transport;
+use solana_token::error::CrateError;
+use solana_token::state::Vesting;
+use solana_token::{find_program_address, id, instruction};
+use utils::*;
+
+const START: i64 = 1_000;
+
+struct Env {
+    context: ProgramTestContext,
+    test_contract: TestContract,
+    token_x: TokenX,
+    grantor: User,
+    beneficiary: User,
+    vesting: Keypair,
+}
+
+impl Env {
+    /// A grantor holding 100 token X and a beneficiary, at `START`
+    async fn new() -> Self {
+        let (mut context, test_contract, token_x, grantor) = setup(program_test(), 100).await;
+        let beneficiary = add_user(&mut context, &test_contract, &token_x, 0).await;
+        set_time(&mut context, START).await;
+
+        Self {
+            context,
+            test_contract,
+            token_x,
+            grantor,
+            beneficiary,
+            vesting: Keypair::new(),
+        }
+    }
+
+    fn wrap_with_vesting(&self, amount: u64, cliff_ts: i64, end_ts: i64) -> Instruction {
+        instruction::wrap_with_vesting(
+            &id(),
+            &self.grantor.account.pubkey(),
+            &self.grantor.user_wallet_x.pubkey(),
+            &self.token_x.mint.pubkey(),
+            &self.test_contract.pool_mint.pubkey(),
+            &self.vesting.pubkey(),
+            &self.beneficiary.account.pubkey(),
+            amount,
+            cliff_ts,
+            end_ts,
+        )
+    }
+
+    async fn wrap(&mut self, amount: u64, cliff_ts: i64, end_ts: i64) -> transport::Result<()> {
+        let ix = self.wrap_with_vesting(amount, cliff_ts, end_ts);
+        process(
+            &mut self.context,
+            ix,
+            &[&self.grantor.account, &self.vesting],
+        )
+        .await
+    }
+
+    async fn claim(&mut self) -> transport::Result<()> {
+        let ix = instruction::claim_vested(
+            &id(),
+            &self.beneficiary.account.pubkey(),
+            &self.vesting.pubkey(),
+            &self.beneficiary.user_wallet_y.pubkey(),
+        );
+        process(&mut self.context, ix, &[&self.beneficiary.account]).await
+    }
+
+    async fn revoke(&mut self) -> transport::Result<()> {
+        let ix = instruction::revoke_vesting(
+            &id(),
+            &self.grantor.account.pubkey(),
+            &self.vesting.pubkey(),
+            &self.grantor.user_wallet_y.pubkey(),
+        );
+        process(&mut self.context, ix, &[&self.grantor.account]).await
+    }
+
+    /// Pool tokens of the escrow, the beneficiary and the grantor
+    async fn balances(&mut self) -> (u64, u64, u64) {
+        let (escrow, _) = find_program_address(&id(), &self.vesting.pubkey());
+        (
+            get_token_balance(&mut self.context, &escrow).await,
+            get_token_balance(&mut self.context, &self.beneficiary.user_wallet_y.pubkey()).await,
+            get_token_balance(&mut self.context, &self.grantor.user_wallet_y.pubkey()).await,
+        )
+    }
+}
+
+#[tokio::test]
+async fn claim_vests_linearly() {
+    let mut env = Env::new().await;
+
+    env.wrap(100, START + 10, START + 100).await.unwrap();
+    assert_eq!(env.balances().await, (100, 0, 0));
+    assert_eq!(
+        get_token_balance(&mut env.context, &env.grantor.user_wallet_x.pubkey()).await,
+        0
+    );
+    let vesting = get_account(&mut env.context, &env.vesting.pubkey()).await;
+    assert_eq!(vesting.owner, id());
+    assert_eq!(
+        Vesting::unpack(&vesting.data).unwrap(),
+        Vesting {
+            is_initialized: true,
+            pool_mint: env.test_contract.pool_mint.pubkey(),
+            grantor: env.grantor.account.pubkey(),
+            beneficiary: env.beneficiary.account.pubkey(),
+            start_ts: START,
+            cliff_ts: START + 10,
+            end_ts: START + 100,
+            total: 100,
+            claimed: 0,
+            escrow_bump: find_program_address(&id(), &env.vesting.pubkey()).1,
+        }
+    );
+
+    set_time(&mut env.context, START + 9).await;
+    env.claim().await.unwrap();
+    assert_eq!(env.balances().await, (100, 0, 0));
+
+    set_time(&mut env.context, START + 33).await;
+    env.claim().await.unwrap();
+    assert_eq!(env.balances().await, (67, 33, 0));
+
+    set_time(&mut env.context, START + 200).await;
+    env.claim().await.unwrap();
+    assert_eq!(env.balances().await, (0, 100, 0));
+}
+
+#[tokio::test]
+async fn revoke_keeps_vested_claimable() {
+    let mut env = Env::new().await;
+
+    env.wrap(100, START, START + 100).await.unwrap();
+    set_time(&mut env.context, START + 25).await;
+    env.claim().await.unwrap();
+    set_time(&mut env.context, START + 40).await;
+    env.revoke().await.unwrap();
+    assert_eq!(env.balances().await, (15, 25, 60));
+
+    set_time(&mut env.context, START + 60).await;
+    env.claim().await.unwrap();
+    assert_eq!(env.balances().await, (0, 40, 60));
+    env.revoke().await.unwrap();
+    assert_eq!(env.balances().await, (0, 40, 60));
+}
+
+#[tokio::test]
+async fn fail_with_other_signers() {
+    let mut env = Env::new().await;
+
+    env.wrap(100, START, START + 100).await.unwrap();
+    let ix = instruction::claim_vested(
+        &id(),
+        &env.grantor.account.pubkey(),
+        &env.vesting.pubkey(),
+        &env.grantor.user_wallet_y.pubkey(),
+    );
+    assert_eq!(
+        process_error(&mut env.context, ix, &[&env.grantor.account]).await,
+        crate_error(0, CrateError::InvalidVestingAuthority)
+    );
+    let ix = instruction::revoke_vesting(
+        &id(),
+        &env.beneficiary.account.pubkey(),
+        &env.vesting.pubkey(),
+        &env.beneficiary.user_wallet_y.pubkey(),
+    );
+    assert_eq!(
+        process_error(&mut env.context, ix, &[&env.beneficiary.account]).await,
+        crate_error(0, CrateError::InvalidVestingAuthority)
+    );
+}
+
+#[tokio::test]
+async fn fail_with_invalid_schedule() {
+    let mut env = Env::new().await;
+
+    for (cliff_ts, end_ts) in [(START - 1, START + 100), (START + 100, START + 99)] {
+        assert_eq!(
+            env.wrap(100, cliff_ts, end_ts).await.unwrap_err().unwrap(),
+            crate_error(0, CrateError::InvalidVestingSchedule)
+        );
+    }
+}